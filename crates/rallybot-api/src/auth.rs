@@ -0,0 +1,69 @@
+use crate::state::AppState;
+use axum::{
+    extract::{Request, State},
+    http::{header::AUTHORIZATION, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+
+/// Constant-time check of an `Authorization` header value against the admin
+/// token. An empty token never verifies.
+pub fn verify(token: &str, header: &str) -> bool {
+    if token.is_empty() {
+        return false;
+    }
+    let Some(given) = header.strip_prefix("Bearer ") else {
+        return false;
+    };
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Rejects requests that don't carry the admin token
+pub async fn require_admin_token(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let header = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    if !verify(&state.admin.token, header) {
+        tracing::warn!("rejected admin request with invalid token");
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    Ok(next.run(request).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKEN: &str = "c0ffee-5e55i0n5";
+
+    #[test]
+    fn verify_accepts_the_token() {
+        assert!(verify(TOKEN, "Bearer c0ffee-5e55i0n5"));
+    }
+
+    #[test]
+    fn verify_rejects_other_tokens_and_schemes() {
+        assert!(!verify(TOKEN, "Bearer c0ffee-5e55i0n6"));
+        assert!(!verify(TOKEN, "Bearer c0ffee"));
+        assert!(!verify(TOKEN, "Basic c0ffee-5e55i0n5"));
+        assert!(!verify(TOKEN, TOKEN));
+    }
+
+    #[test]
+    fn verify_rejects_empty_token() {
+        assert!(!verify("", "Bearer "));
+    }
+}
@@ -0,0 +1,36 @@
+/// WhatsApp Cloud API settings for the webhook
+#[derive(Clone, Debug, Default)]
+pub struct WhatsAppConfig {
+    /// Token Meta echoes back during the `GET /webhooks/whatsapp` handshake
+    pub verify_token: String,
+    /// App secret used to sign webhook deliveries (`X-Hub-Signature-256`)
+    pub app_secret: String,
+    /// Membership application form the bot sends people who aren't members
+    pub application_url: Option<String>,
+}
+
+impl WhatsAppConfig {
+    pub fn from_env() -> Self {
+        Self {
+            verify_token: std::env::var("WHATSAPP_VERIFY_TOKEN").unwrap_or_default(),
+            app_secret: std::env::var("WHATSAPP_APP_SECRET").unwrap_or_default(),
+            application_url: std::env::var("APPLICATION_URL").ok().filter(|url| !url.is_empty()),
+        }
+    }
+}
+
+/// Settings for the organisers' `/admin` endpoints
+#[derive(Clone, Debug, Default)]
+pub struct AdminConfig {
+    /// Bearer token organisers send in `Authorization`. Left empty, nobody
+    /// gets in.
+    pub token: String,
+}
+
+impl AdminConfig {
+    pub fn from_env() -> Self {
+        Self {
+            token: std::env::var("ADMIN_API_TOKEN").unwrap_or_default(),
+        }
+    }
+}
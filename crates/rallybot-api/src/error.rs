@@ -0,0 +1,287 @@
+use axum::{
+    async_trait,
+    extract::{rejection::JsonRejection, FromRequest, Request},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use rallybot_core::{
+    ApplicationError, AttendanceError, RegistrationError, SessionError, StorageError,
+    TemplateError, UserError,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+
+/// An error response: `{"code": ..., "message": ..., "details": ...}` with a
+/// matching status. `code` is stable and meant for clients to branch on;
+/// `message` is for people.
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub code: &'static str,
+    pub message: String,
+    pub details: Option<Value>,
+}
+
+#[derive(Serialize)]
+struct ErrorBody<'a> {
+    code: &'a str,
+    message: &'a str,
+    details: &'a Option<Value>,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            code,
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    /// The request was understood but its values are not acceptable
+    pub fn validation(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "validation_failed", message)
+    }
+
+    pub fn not_found(code: &'static str, message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, code, message)
+    }
+
+    pub fn conflict(code: &'static str, message: impl Into<String>) -> Self {
+        Self::new(StatusCode::CONFLICT, code, message)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = ErrorBody {
+            code: self.code,
+            message: &self.message,
+            details: &self.details,
+        };
+        (self.status, Json(body)).into_response()
+    }
+}
+
+impl From<StorageError> for ApiError {
+    fn from(error: StorageError) -> Self {
+        match error {
+            StorageError::NotFound => Self::not_found("not_found", "Record not found"),
+            StorageError::Conflict(constraint) => {
+                Self::conflict("conflict", "Conflicts with an existing record")
+                    .with_details(json!({ "constraint": constraint }))
+            }
+            StorageError::Constraint(constraint) => Self::new(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "constraint_violation",
+                "Refers to a record that does not exist or breaks a rule",
+            )
+            .with_details(json!({ "constraint": constraint })),
+            // Failures on our side are logged; the client only gets a generic message
+            StorageError::Connection(_) => {
+                tracing::error!("storage unavailable: {}", error);
+                Self::new(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    "storage_unavailable",
+                    "Storage is temporarily unavailable, please retry",
+                )
+            }
+            StorageError::Database(_) => {
+                tracing::error!("storage failure: {}", error);
+                Self::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "internal_error",
+                    "Something went wrong",
+                )
+            }
+        }
+    }
+}
+
+impl From<RegistrationError> for ApiError {
+    fn from(error: RegistrationError) -> Self {
+        match error {
+            RegistrationError::SessionNotFound => {
+                Self::not_found("session_not_found", "Session not found")
+            }
+            RegistrationError::UserNotFound => Self::not_found("user_not_found", "User not found"),
+            RegistrationError::UserNotApproved => Self::new(
+                StatusCode::FORBIDDEN,
+                "user_not_approved",
+                "User not approved",
+            ),
+            RegistrationError::AlreadyRegistered => {
+                Self::conflict("already_registered", "Already registered")
+            }
+            RegistrationError::NotRegistered => Self::not_found(
+                "not_registered",
+                "User is not registered for this session",
+            ),
+            RegistrationError::SessionFull => Self::conflict("session_full", "Session is full"),
+            RegistrationError::SessionClosed => Self::conflict(
+                "session_closed",
+                "Session is no longer taking sign-ups",
+            ),
+            RegistrationError::SkillLevelMismatch => Self::new(
+                StatusCode::FORBIDDEN,
+                "skill_level_mismatch",
+                "Session is not open to the user's skill level",
+            ),
+            RegistrationError::NoPendingConfirmation => Self::conflict(
+                "no_pending_confirmation",
+                "User has no promotion waiting to be confirmed for this session",
+            ),
+            RegistrationError::TooLateToCancel => Self::conflict(
+                "cancellation_too_late",
+                "Past the cancellation cutoff and no substitute can take the place",
+            ),
+            RegistrationError::SessionStarted => Self::conflict(
+                "session_started",
+                "The session has started; attendance is recorded instead",
+            ),
+            RegistrationError::TooManyLateCancellations => Self::new(
+                StatusCode::FORBIDDEN,
+                "too_many_late_cancellations",
+                "Too many recent late cancellations to sign up",
+            ),
+            RegistrationError::Storage(e) => e.into(),
+        }
+    }
+}
+
+impl From<SessionError> for ApiError {
+    fn from(error: SessionError) -> Self {
+        match error {
+            // The venue is part of the request body, so this is a bad request
+            // rather than a missing resource
+            SessionError::VenueNotFound => Self::new(
+                StatusCode::BAD_REQUEST,
+                "venue_not_found",
+                "Venue not found",
+            ),
+            SessionError::SessionNotFound => {
+                Self::not_found("session_not_found", "Session not found")
+            }
+            SessionError::NotScheduled => Self::conflict(
+                "session_not_scheduled",
+                "Only scheduled sessions can be changed or cancelled",
+            ),
+            SessionError::Invalid(message) => Self::validation(message),
+            SessionError::OverCapacity => Self::conflict(
+                "session_over_capacity",
+                "More players are signed up than the session would have room for",
+            ),
+            SessionError::Storage(e) => e.into(),
+        }
+    }
+}
+
+impl From<TemplateError> for ApiError {
+    fn from(error: TemplateError) -> Self {
+        match error {
+            TemplateError::TemplateNotFound => {
+                Self::not_found("template_not_found", "Session template not found")
+            }
+            TemplateError::VenueNotFound => Self::new(
+                StatusCode::BAD_REQUEST,
+                "venue_not_found",
+                "Venue not found",
+            ),
+            TemplateError::Invalid(message) => Self::validation(message),
+            TemplateError::Storage(e) => e.into(),
+        }
+    }
+}
+
+impl From<UserError> for ApiError {
+    fn from(error: UserError) -> Self {
+        match error {
+            UserError::UserNotFound => Self::not_found("user_not_found", "User not found"),
+            UserError::InvalidTransition { from, to } => Self::conflict(
+                "invalid_status_transition",
+                format!("Cannot move a user from {:?} to {:?}", from, to),
+            )
+            .with_details(json!({ "from": from, "to": to })),
+            UserError::Storage(e) => e.into(),
+        }
+    }
+}
+
+impl From<ApplicationError> for ApiError {
+    fn from(error: ApplicationError) -> Self {
+        match error {
+            ApplicationError::ApplicationNotFound => {
+                Self::not_found("application_not_found", "Application not found")
+            }
+            ApplicationError::Invalid(fields) => {
+                Self::validation("Some fields are not valid").with_details(json!(fields))
+            }
+            ApplicationError::AlreadyMember => Self::conflict(
+                "already_member",
+                "A member with this phone number or email already exists",
+            ),
+            ApplicationError::InvalidTransition { from, to } => Self::conflict(
+                "invalid_status_transition",
+                format!("Cannot move an application from {:?} to {:?}", from, to),
+            )
+            .with_details(json!({ "from": from, "to": to })),
+            ApplicationError::Storage(e) => e.into(),
+        }
+    }
+}
+
+impl From<AttendanceError> for ApiError {
+    fn from(error: AttendanceError) -> Self {
+        match error {
+            AttendanceError::SessionNotFound => {
+                Self::not_found("session_not_found", "Session not found")
+            }
+            AttendanceError::SessionCancelled => {
+                Self::conflict("session_cancelled", "Session was cancelled")
+            }
+            AttendanceError::SessionNotOver => Self::conflict(
+                "session_not_over",
+                "Attendance can be marked once the session has ended",
+            ),
+            AttendanceError::NotRegistered => Self::not_found(
+                "not_registered",
+                "User has no confirmed place in this session",
+            ),
+            AttendanceError::NothingToCheckIn => Self::conflict(
+                "nothing_to_check_in",
+                "None of the user's sessions is open for check-in",
+            ),
+            AttendanceError::Storage(e) => e.into(),
+        }
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        Self::new(rejection.status(), "invalid_body", rejection.body_text())
+    }
+}
+
+/// `Json` that reports malformed bodies as an [`ApiError`]
+pub struct ApiJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ApiJson<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(request, state).await?;
+        Ok(Self(value))
+    }
+}
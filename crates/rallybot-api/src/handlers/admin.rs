@@ -0,0 +1,106 @@
+use crate::error::{ApiError, ApiJson};
+use crate::state::AppState;
+use axum::{
+    extract::{Path, State},
+    response::Json,
+};
+use rallybot_core::{LateCancellation, User, UserStatusChange};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct StatusChangeRequest {
+    /// The admin making the change, e.g. their email; kept in the audit trail
+    pub by: String,
+    pub reason: Option<String>,
+}
+
+impl StatusChangeRequest {
+    pub(crate) fn by(&self) -> Result<&str, ApiError> {
+        match self.by.trim() {
+            "" => Err(ApiError::validation("by must name the admin making the change")),
+            by => Ok(by),
+        }
+    }
+}
+
+/// Applicants waiting for a decision, oldest first
+pub async fn list_pending_users(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<User>>, ApiError> {
+    let users = state.user_repository.list_pending().await?;
+    Ok(Json(users))
+}
+
+/// Approve an applicant, or reinstate a rejected or suspended user, and
+/// welcome them on WhatsApp
+pub async fn approve_user(
+    State(state): State<AppState>,
+    Path(user_id): Path<Uuid>,
+    ApiJson(payload): ApiJson<StatusChangeRequest>,
+) -> Result<Json<User>, ApiError> {
+    let user = state
+        .user_repository
+        .approve(user_id, payload.by()?, payload.reason.clone())
+        .await?;
+    Ok(Json(user))
+}
+
+pub async fn reject_user(
+    State(state): State<AppState>,
+    Path(user_id): Path<Uuid>,
+    ApiJson(payload): ApiJson<StatusChangeRequest>,
+) -> Result<Json<User>, ApiError> {
+    let user = state
+        .user_repository
+        .reject(user_id, payload.by()?, payload.reason.clone())
+        .await?;
+    Ok(Json(user))
+}
+
+pub async fn suspend_user(
+    State(state): State<AppState>,
+    Path(user_id): Path<Uuid>,
+    ApiJson(payload): ApiJson<StatusChangeRequest>,
+) -> Result<Json<User>, ApiError> {
+    let user = state
+        .user_repository
+        .suspend(user_id, payload.by()?, payload.reason.clone())
+        .await?;
+    Ok(Json(user))
+}
+
+/// Every approval, rejection and suspension of the user, oldest first
+pub async fn get_status_history(
+    State(state): State<AppState>,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<Vec<UserStatusChange>>, ApiError> {
+    if state.user_repository.get(user_id).await?.is_none() {
+        return Err(ApiError::not_found("user_not_found", "User not found"));
+    }
+    let history = state.user_repository.status_history(user_id).await?;
+    Ok(Json(history))
+}
+
+#[derive(Serialize)]
+pub struct LateCancellations {
+    pub user_id: Uuid,
+    pub count: usize,
+    pub cancellations: Vec<LateCancellation>,
+}
+
+/// Sessions the user dropped out of after the cancellation cutoff, oldest first
+pub async fn get_late_cancellations(
+    State(state): State<AppState>,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<LateCancellations>, ApiError> {
+    if state.user_repository.get(user_id).await?.is_none() {
+        return Err(ApiError::not_found("user_not_found", "User not found"));
+    }
+    let cancellations = state.user_repository.late_cancellations(user_id).await?;
+    Ok(Json(LateCancellations {
+        user_id,
+        count: cancellations.len(),
+        cancellations,
+    }))
+}
@@ -0,0 +1,116 @@
+use crate::error::{ApiError, ApiJson};
+use crate::handlers::admin::StatusChangeRequest;
+use crate::handlers::users::CreateUserRequest;
+use crate::state::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use rallybot_core::{Application, ApplicationStatus, ApplicationStatusChange, User};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct ListApplicationsQuery {
+    /// `pending` unless given
+    #[serde(default)]
+    pub status: ApplicationStatus,
+}
+
+#[derive(Serialize)]
+pub struct ApprovedApplication {
+    pub application: Application,
+    pub user: User,
+}
+
+/// Apply to join. Sending the same form again returns the application already
+/// on file with 200 instead of 201.
+pub async fn submit_application(
+    State(state): State<AppState>,
+    ApiJson(payload): ApiJson<CreateUserRequest>,
+) -> Result<(StatusCode, Json<Application>), ApiError> {
+    let application = Application::new(
+        payload.first_name,
+        payload.last_name,
+        payload.phone_number,
+        payload.email,
+        payload.city,
+        payload.photo_url,
+        payload.occupation,
+        payload.company,
+        payload.industry,
+        payload.linkedin_url,
+        payload.gender,
+        payload.skill_levels,
+        payload.preferred_side,
+        payload.play_frequency,
+        payload.looking_for,
+    );
+
+    let (application, created) = state.application_repository.submit(application).await?;
+    let status = if created { StatusCode::CREATED } else { StatusCode::OK };
+    Ok((status, Json(application)))
+}
+
+/// Applications in one status, oldest first
+pub async fn list_applications(
+    State(state): State<AppState>,
+    Query(query): Query<ListApplicationsQuery>,
+) -> Result<Json<Vec<Application>>, ApiError> {
+    let applications = state.application_repository.list(query.status).await?;
+    Ok(Json(applications))
+}
+
+pub async fn get_application(
+    State(state): State<AppState>,
+    Path(application_id): Path<Uuid>,
+) -> Result<Json<Application>, ApiError> {
+    let application = state
+        .application_repository
+        .get(application_id)
+        .await?
+        .ok_or_else(application_not_found)?;
+    Ok(Json(application))
+}
+
+/// Accept an application, create the member and welcome them on WhatsApp
+pub async fn approve_application(
+    State(state): State<AppState>,
+    Path(application_id): Path<Uuid>,
+    ApiJson(payload): ApiJson<StatusChangeRequest>,
+) -> Result<Json<ApprovedApplication>, ApiError> {
+    let (application, user) = state
+        .application_repository
+        .approve(application_id, payload.by()?, payload.reason.clone())
+        .await?;
+    Ok(Json(ApprovedApplication { application, user }))
+}
+
+pub async fn reject_application(
+    State(state): State<AppState>,
+    Path(application_id): Path<Uuid>,
+    ApiJson(payload): ApiJson<StatusChangeRequest>,
+) -> Result<Json<Application>, ApiError> {
+    let application = state
+        .application_repository
+        .reject(application_id, payload.by()?, payload.reason.clone())
+        .await?;
+    Ok(Json(application))
+}
+
+/// Every decision on the application, oldest first
+pub async fn get_application_history(
+    State(state): State<AppState>,
+    Path(application_id): Path<Uuid>,
+) -> Result<Json<Vec<ApplicationStatusChange>>, ApiError> {
+    if state.application_repository.get(application_id).await?.is_none() {
+        return Err(application_not_found());
+    }
+    let history = state.application_repository.history(application_id).await?;
+    Ok(Json(history))
+}
+
+fn application_not_found() -> ApiError {
+    ApiError::not_found("application_not_found", "Application not found")
+}
@@ -0,0 +1,58 @@
+use crate::error::{ApiError, ApiJson};
+use crate::state::AppState;
+use axum::{
+    extract::{Path, State},
+    response::Json,
+};
+use rallybot_core::{Attendance, AttendanceStats, AttendanceStatus};
+use serde::Deserialize;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct MarkAttendanceRequest {
+    pub user_id: Uuid,
+    pub status: AttendanceStatus,
+    /// The organiser marking it, e.g. their email
+    pub by: String,
+}
+
+/// Record whether a confirmed player turned up, once the session has ended.
+/// Marking the same player again replaces the earlier record.
+pub async fn mark_attendance(
+    State(state): State<AppState>,
+    Path(session_id): Path<Uuid>,
+    ApiJson(payload): ApiJson<MarkAttendanceRequest>,
+) -> Result<Json<Attendance>, ApiError> {
+    let by = match payload.by.trim() {
+        "" => return Err(ApiError::validation("by must name the organiser")),
+        by => by,
+    };
+    let attendance = state
+        .attendance_repository
+        .mark(session_id, payload.user_id, payload.status, by)
+        .await?;
+    Ok(Json(attendance))
+}
+
+pub async fn get_session_attendance(
+    State(state): State<AppState>,
+    Path(session_id): Path<Uuid>,
+) -> Result<Json<Vec<Attendance>>, ApiError> {
+    if state.session_repository.get(session_id).await?.is_none() {
+        return Err(ApiError::not_found("session_not_found", "Session not found"));
+    }
+    let attendance = state.attendance_repository.for_session(session_id).await?;
+    Ok(Json(attendance))
+}
+
+/// How many sessions the user attended, missed or was excused from
+pub async fn get_attendance_stats(
+    State(state): State<AppState>,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<AttendanceStats>, ApiError> {
+    if state.user_repository.get(user_id).await?.is_none() {
+        return Err(ApiError::not_found("user_not_found", "User not found"));
+    }
+    let stats = state.attendance_repository.stats(user_id).await?;
+    Ok(Json(stats))
+}
@@ -1,9 +1,14 @@
+pub mod admin;
+pub mod applications;
+pub mod attendance;
 pub mod sessions;
+pub mod templates;
 pub mod users;
 pub mod venues;
+pub mod webhooks;
 
 use axum::http::StatusCode;
 
 pub async fn health_check() -> StatusCode {
     StatusCode::OK
-}
\ No newline at end of file
+}
@@ -1,24 +1,179 @@
+use crate::error::{ApiError, ApiJson};
 use crate::state::AppState;
 use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
     response::Json,
 };
+use chrono::{DateTime, FixedOffset, Utc};
+use chrono_tz::Tz;
 use rallybot_core::{
-    Registration, RegistrationError, RegistrationStatus, Session, SessionError, SessionType,
-    SkillLevel,
+    ParseSkillLevelError, Registration, RegistrationEvent, RegistrationStatus, Session, SessionQuery, SessionSummary,
+    SessionType, SessionUpdate, SkillLevel, SortOrder, Venue,
 };
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
 use uuid::Uuid;
 
+/// Largest page `GET /sessions` returns
+const MAX_LIMIT: u32 = 100;
+
 #[derive(Deserialize)]
 pub struct ListSessionsQuery {
     #[serde(rename = "type")]
     pub session_type: Option<SessionType>,
+    /// Sessions starting at or after this time
+    pub from: Option<DateTime<Utc>>,
+    /// Sessions starting before this time
+    pub to: Option<DateTime<Utc>>,
+    pub venue_id: Option<Uuid>,
+    /// Comma-separated, e.g. "C,D"
+    pub skill_levels: Option<String>,
+    #[serde(default)]
+    pub open_slots: bool,
+    #[serde(default)]
+    pub include_cancelled: bool,
+    #[serde(default)]
+    pub order: SortOrder,
+    pub limit: Option<u32>,
+    #[serde(default)]
+    pub offset: u32,
+    /// Only sessions in this Monday-to-Sunday week of their venue's local
+    /// calendar: 0 is the current week, 1 the next
+    pub week: Option<u32>,
+    /// Include the venue and sign-ups with each session
     #[serde(default)]
     pub summary: bool,
 }
 
+impl ListSessionsQuery {
+    fn session_query(&self) -> Result<SessionQuery, ApiError> {
+        let skill_levels = match &self.skill_levels {
+            Some(levels) => levels
+                .split(',')
+                .map(|level| {
+                    level
+                        .trim()
+                        .parse()
+                        .map_err(|e: ParseSkillLevelError| ApiError::validation(e.to_string()))
+                })
+                .collect::<Result<_, _>>()?,
+            None => Vec::new(),
+        };
+        if let (Some(from), Some(to)) = (self.from, self.to) {
+            if from >= to {
+                return Err(ApiError::validation("from must be before to"));
+            }
+        }
+        if self.limit.is_some_and(|limit| !(1..=MAX_LIMIT).contains(&limit)) {
+            return Err(ApiError::validation(format!(
+                "limit must be between 1 and {}",
+                MAX_LIMIT
+            )));
+        }
+
+        Ok(SessionQuery {
+            session_type: self.session_type,
+            from: self.from,
+            to: self.to,
+            venue_id: self.venue_id,
+            skill_levels,
+            has_open_slots: self.open_slots,
+            include_cancelled: self.include_cancelled,
+            order: self.order,
+            limit: self.limit,
+            offset: self.offset,
+        })
+    }
+}
+
+/// A session with its start also given on the venue's clock, e.g.
+/// `"local_datetime": "2030-07-02T19:00:00+01:00"`
+#[derive(Serialize)]
+pub struct LocalSession {
+    #[serde(flatten)]
+    pub session: Session,
+    pub local_datetime: DateTime<FixedOffset>,
+    pub timezone: Tz,
+}
+
+impl LocalSession {
+    fn new(session: Session, timezone: Tz) -> Self {
+        let local_datetime = session.datetime.with_timezone(&timezone).fixed_offset();
+        Self {
+            session,
+            local_datetime,
+            timezone,
+        }
+    }
+}
+
+/// A session as shown in listings: where it is, who's in and how many
+/// places are left
+#[derive(Serialize)]
+pub struct SessionSummaryView {
+    #[serde(flatten)]
+    pub session: LocalSession,
+    pub venue_name: String,
+    pub venue_address: String,
+    pub confirmed_count: usize,
+    pub substitute_count: usize,
+    pub free_slots: usize,
+    pub confirmed_first_names: Vec<String>,
+}
+
+impl From<SessionSummary> for SessionSummaryView {
+    fn from(summary: SessionSummary) -> Self {
+        let free_slots = summary.free_slots();
+        Self {
+            session: LocalSession::new(summary.session, summary.venue.timezone),
+            venue_name: summary.venue.name,
+            venue_address: summary.venue.address,
+            confirmed_count: summary.confirmed_count,
+            substitute_count: summary.substitute_count,
+            free_slots,
+            confirmed_first_names: summary.confirmed_first_names,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum SessionListing {
+    Sessions(Vec<LocalSession>),
+    Summaries(Vec<SessionSummaryView>),
+}
+
+/// Adds local times, looking each venue up once
+pub(crate) async fn localise(
+    state: &AppState,
+    sessions: Vec<Session>,
+) -> Result<Vec<LocalSession>, ApiError> {
+    let mut timezones: HashMap<Uuid, Tz> = HashMap::new();
+    let mut localised = Vec::with_capacity(sessions.len());
+    for session in sessions {
+        let timezone = match timezones.get(&session.venue_id) {
+            Some(timezone) => *timezone,
+            None => {
+                let timezone = state
+                    .venue_repository
+                    .get(session.venue_id)
+                    .await?
+                    .map_or(Venue::DEFAULT_TIMEZONE, |venue| venue.timezone);
+                timezones.insert(session.venue_id, timezone);
+                timezone
+            }
+        };
+        localised.push(LocalSession::new(session, timezone));
+    }
+    Ok(localised)
+}
+
+async fn localise_one(state: &AppState, session: Session) -> Result<LocalSession, ApiError> {
+    let mut localised = localise(state, vec![session]).await?;
+    Ok(localised.remove(0))
+}
+
 #[derive(Deserialize)]
 pub struct CreateSessionRequest {
     pub session_type: SessionType,
@@ -26,33 +181,122 @@ pub struct CreateSessionRequest {
     pub duration_minutes: i32,
     pub venue_id: Uuid,
     pub skill_level: Option<SkillLevel>,
+    pub max_players: Option<i32>,
+    pub max_substitutes: Option<i32>,
 }
 
 pub async fn list_sessions(
     State(state): State<AppState>,
     Query(params): Query<ListSessionsQuery>,
-) -> Json<Vec<Session>> {
-    let sessions = state.session_repository.list(params.session_type).await;
-    Json(sessions)
+) -> Result<Json<SessionListing>, ApiError> {
+    let query = params.session_query()?;
+    if params.summary {
+        let summaries = match params.week {
+            Some(week) => {
+                state
+                    .session_repository
+                    .list_summaries_in_week(&query, Utc::now(), week)
+                    .await?
+            }
+            None => state.session_repository.list_summaries(&query).await?,
+        };
+        let views = summaries.into_iter().map(SessionSummaryView::from).collect();
+        return Ok(Json(SessionListing::Summaries(views)));
+    }
+
+    let sessions = match params.week {
+        Some(week) => {
+            state
+                .session_repository
+                .list_in_week(&query, Utc::now(), week)
+                .await?
+        }
+        None => state.session_repository.list(&query).await?,
+    };
+    Ok(Json(SessionListing::Sessions(localise(&state, sessions).await?)))
 }
 
 pub async fn create_session(
     State(state): State<AppState>,
-    Json(payload): Json<CreateSessionRequest>,
-) -> Result<(StatusCode, Json<Session>), StatusCode> {
+    ApiJson(payload): ApiJson<CreateSessionRequest>,
+) -> Result<(StatusCode, Json<LocalSession>), ApiError> {
     let session = Session::new(
         payload.session_type,
         payload.datetime,
         payload.duration_minutes,
         payload.venue_id,
         payload.skill_level,
+        payload.max_players.unwrap_or(Session::DEFAULT_MAX_PLAYERS),
+        payload.max_substitutes,
     )
-    .map_err(|_| StatusCode::BAD_REQUEST)?;
+    .map_err(ApiError::validation)?;
+
+    let created = state.session_repository.create(session).await?;
+    Ok((StatusCode::CREATED, Json(localise_one(&state, created).await?)))
+}
+
+/// Only the fields present are changed. `"skill_level": null` clears the
+/// level, which is needed when switching to a Mixed session, and
+/// `"max_substitutes": null` lifts the limit on substitutes.
+#[derive(Deserialize)]
+pub struct UpdateSessionRequest {
+    pub session_type: Option<SessionType>,
+    pub datetime: Option<chrono::DateTime<chrono::Utc>>,
+    pub duration_minutes: Option<i32>,
+    pub venue_id: Option<Uuid>,
+    #[serde(default, deserialize_with = "present")]
+    pub skill_level: Option<Option<SkillLevel>>,
+    pub max_players: Option<i32>,
+    #[serde(default, deserialize_with = "present")]
+    pub max_substitutes: Option<Option<i32>>,
+}
+
+/// Tells a field sent as `null` (`Some(None)`) from one left out (`None`)
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// Edit a scheduled session; everyone signed up is told what changed
+pub async fn update_session(
+    State(state): State<AppState>,
+    Path(session_id): Path<Uuid>,
+    ApiJson(payload): ApiJson<UpdateSessionRequest>,
+) -> Result<Json<LocalSession>, ApiError> {
+    let update = SessionUpdate {
+        session_type: payload.session_type,
+        datetime: payload.datetime,
+        duration_minutes: payload.duration_minutes,
+        venue_id: payload.venue_id,
+        skill_level: payload.skill_level,
+        max_players: payload.max_players,
+        max_substitutes: payload.max_substitutes,
+    };
+    let (_, after) = state.session_repository.update(session_id, &update).await?;
+    Ok(Json(localise_one(&state, after).await?))
+}
+
+#[derive(Deserialize)]
+pub struct CancelSessionRequest {
+    pub reason: String,
+}
 
-    match state.session_repository.create(session).await {
-        Ok(created) => Ok((StatusCode::CREATED, Json(created))),
-        Err(SessionError::VenueNotFound) => Err(StatusCode::BAD_REQUEST),
+/// Cancel a scheduled session; everyone signed up is told
+pub async fn cancel_session(
+    State(state): State<AppState>,
+    Path(session_id): Path<Uuid>,
+    ApiJson(payload): ApiJson<CancelSessionRequest>,
+) -> Result<Json<LocalSession>, ApiError> {
+    let reason = payload.reason.trim();
+    if reason.is_empty() {
+        return Err(ApiError::validation("A cancellation reason is required"));
     }
+
+    let session = state.session_repository.cancel(session_id, reason).await?;
+    Ok(Json(localise_one(&state, session).await?))
 }
 
 #[derive(Deserialize)]
@@ -66,45 +310,55 @@ pub struct RegisterResponse {
     pub message: String,
 }
 
+impl RegisterResponse {
+    fn new(status: RegistrationStatus) -> Self {
+        let message = match status {
+            RegistrationStatus::Confirmed => "Successfully registered!".to_string(),
+            RegistrationStatus::Substitute => "Added to substitute list".to_string(),
+        };
+        Self { status, message }
+    }
+}
+
 pub async fn register_for_session(
     State(state): State<AppState>,
     Path(session_id): Path<Uuid>,
-    Json(payload): Json<RegisterRequest>,
-) -> Result<Json<RegisterResponse>, (StatusCode, String)> {
+    ApiJson(payload): ApiJson<RegisterRequest>,
+) -> Result<Json<RegisterResponse>, ApiError> {
     // Find user by phone
     let user = state
         .user_repository
         .get_by_phone(&payload.phone_number)
-        .await
-        .ok_or((StatusCode::NOT_FOUND, "User not found".to_string()))?;
+        .await?
+        .ok_or_else(|| ApiError::not_found("user_not_found", "User not found"))?;
 
     // Register user
     let status = state
         .session_repository
         .register_user(session_id, user.id)
-        .await
-        .map_err(|e| match e {
-            RegistrationError::SessionNotFound => {
-                (StatusCode::NOT_FOUND, "Session not found".to_string())
-            }
-            RegistrationError::UserNotApproved => {
-                (StatusCode::FORBIDDEN, "User not approved".to_string())
-            }
-            RegistrationError::AlreadyRegistered => {
-                (StatusCode::CONFLICT, "Already registered".to_string())
-            }
-            _ => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Registration failed".to_string(),
-            ),
-        })?;
-
-    let message = match status {
-        RegistrationStatus::Confirmed => "Successfully registered!".to_string(),
-        RegistrationStatus::Substitute => "Added to substitute list".to_string(),
-    };
+        .await?;
 
-    Ok(Json(RegisterResponse { status, message }))
+    Ok(Json(RegisterResponse::new(status)))
+}
+
+#[derive(Deserialize)]
+pub struct PlacePlayerRequest {
+    pub user_id: Uuid,
+}
+
+/// Sign a player up on an organiser's say-so, whatever their skill level or
+/// late cancellations
+pub async fn place_player(
+    State(state): State<AppState>,
+    Path(session_id): Path<Uuid>,
+    ApiJson(payload): ApiJson<PlacePlayerRequest>,
+) -> Result<Json<RegisterResponse>, ApiError> {
+    let status = state
+        .session_repository
+        .place_player(session_id, payload.user_id)
+        .await?;
+
+    Ok(Json(RegisterResponse::new(status)))
 }
 
 #[derive(Deserialize)]
@@ -115,38 +369,59 @@ pub struct UnregisterRequest {
 pub async fn unregister_from_session(
     State(state): State<AppState>,
     Path(session_id): Path<Uuid>,
-    Json(payload): Json<UnregisterRequest>,
-) -> Result<StatusCode, StatusCode> {
-    match state
+    ApiJson(payload): ApiJson<UnregisterRequest>,
+) -> Result<StatusCode, ApiError> {
+    state
         .session_repository
         .unregister_user(session_id, payload.user_id)
-        .await
-    {
-        Ok(()) => Ok(StatusCode::NO_CONTENT),
-        Err(RegistrationError::NotRegistered) => Err(StatusCode::NOT_FOUND),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
-    }
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize)]
+pub struct ConfirmSpotRequest {
+    pub phone_number: String,
+}
+
+/// Keep a spot the player was promoted into, before the deadline passes
+pub async fn confirm_spot(
+    State(state): State<AppState>,
+    Path(session_id): Path<Uuid>,
+    ApiJson(payload): ApiJson<ConfirmSpotRequest>,
+) -> Result<Json<Registration>, ApiError> {
+    let user = state
+        .user_repository
+        .get_by_phone(&payload.phone_number)
+        .await?
+        .ok_or_else(|| ApiError::not_found("user_not_found", "User not found"))?;
+
+    let registration = state
+        .session_repository
+        .confirm_spot(session_id, user.id)
+        .await?;
+    Ok(Json(registration))
 }
 
 #[derive(Serialize)]
 pub struct SessionDetails {
     #[serde(flatten)]
-    pub session: Session,
+    pub session: LocalSession,
     pub confirmed_count: usize,
     pub substitute_count: usize,
+    pub available_spots: usize,
 }
 
 pub async fn get_session_details(
     State(state): State<AppState>,
     Path(session_id): Path<Uuid>,
-) -> Result<Json<SessionDetails>, StatusCode> {
+) -> Result<Json<SessionDetails>, ApiError> {
     let session = state
         .session_repository
         .get(session_id)
-        .await
-        .ok_or(StatusCode::NOT_FOUND)?;
+        .await?
+        .ok_or_else(|| ApiError::not_found("session_not_found", "Session not found"))?;
 
-    let registrations = state.session_repository.get_registrations(session_id).await;
+    let registrations = state.session_repository.get_registrations(session_id).await?;
 
     let confirmed_count = registrations
         .iter()
@@ -158,17 +433,33 @@ pub async fn get_session_details(
         .filter(|r| r.status == RegistrationStatus::Substitute)
         .count();
 
+    let available_spots = (session.max_players as usize).saturating_sub(confirmed_count);
+
     Ok(Json(SessionDetails {
-        session,
+        session: localise_one(&state, session).await?,
         confirmed_count,
         substitute_count,
+        available_spots,
     }))
 }
 
 pub async fn get_session_registrations(
     State(state): State<AppState>,
     Path(session_id): Path<Uuid>,
-) -> Json<Vec<Registration>> {
-    let registrations = state.session_repository.get_registrations(session_id).await;
-    Json(registrations)
+) -> Result<Json<Vec<Registration>>, ApiError> {
+    let registrations = state.session_repository.get_registrations(session_id).await?;
+    Ok(Json(registrations))
+}
+
+/// Every sign-up, promotion, demotion and cancellation for the session, in
+/// the order they happened, including those of players no longer registered
+pub async fn get_registration_history(
+    State(state): State<AppState>,
+    Path(session_id): Path<Uuid>,
+) -> Result<Json<Vec<RegistrationEvent>>, ApiError> {
+    if state.session_repository.get(session_id).await?.is_none() {
+        return Err(ApiError::not_found("session_not_found", "Session not found"));
+    }
+    let history = state.session_repository.registration_history(session_id).await?;
+    Ok(Json(history))
 }
@@ -0,0 +1,156 @@
+use crate::error::{ApiError, ApiJson};
+use crate::handlers::sessions::{localise, LocalSession};
+use crate::state::AppState;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use chrono::{NaiveDate, NaiveTime, Utc, Weekday};
+use rallybot_core::{Session, SessionTemplate, SessionType, SkillLevel};
+use serde::Deserialize;
+use uuid::Uuid;
+
+/// How far ahead sessions are generated when the request doesn't say
+const DEFAULT_WEEKS_AHEAD: u32 = 4;
+/// About six months; further out the schedule is too likely to change
+const MAX_WEEKS_AHEAD: u32 = 26;
+
+#[derive(Deserialize)]
+pub struct CreateTemplateRequest {
+    pub session_type: SessionType,
+    pub weekday: Weekday,
+    /// On the venue's clock, e.g. "19:00"
+    pub start_time: NaiveTime,
+    pub duration_minutes: i32,
+    pub venue_id: Uuid,
+    pub skill_level: Option<SkillLevel>,
+    pub max_players: Option<i32>,
+    pub max_substitutes: Option<i32>,
+    #[serde(default)]
+    pub exception_dates: Vec<NaiveDate>,
+}
+
+pub async fn list_templates(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<SessionTemplate>>, ApiError> {
+    let templates = state.template_repository.list().await?;
+    Ok(Json(templates))
+}
+
+pub async fn create_template(
+    State(state): State<AppState>,
+    ApiJson(payload): ApiJson<CreateTemplateRequest>,
+) -> Result<(StatusCode, Json<SessionTemplate>), ApiError> {
+    let mut template = SessionTemplate::new(
+        payload.session_type,
+        payload.weekday,
+        payload.start_time,
+        payload.duration_minutes,
+        payload.venue_id,
+        payload.skill_level,
+        payload.max_players.unwrap_or(Session::DEFAULT_MAX_PLAYERS),
+        payload.max_substitutes,
+    )
+    .map_err(ApiError::validation)?;
+    for date in payload.exception_dates {
+        template.add_exception(date);
+    }
+
+    let created = state.template_repository.create(template).await?;
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+pub async fn get_template(
+    State(state): State<AppState>,
+    Path(template_id): Path<Uuid>,
+) -> Result<Json<SessionTemplate>, ApiError> {
+    state
+        .template_repository
+        .get(template_id)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found("template_not_found", "Session template not found"))
+}
+
+/// Stop generating sessions; the ones already created stay on the schedule
+pub async fn delete_template(
+    State(state): State<AppState>,
+    Path(template_id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    state.template_repository.delete(template_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize)]
+pub struct ExceptionRequest {
+    pub date: NaiveDate,
+}
+
+pub async fn add_exception(
+    State(state): State<AppState>,
+    Path(template_id): Path<Uuid>,
+    ApiJson(payload): ApiJson<ExceptionRequest>,
+) -> Result<Json<SessionTemplate>, ApiError> {
+    let template = state
+        .template_repository
+        .add_exception(template_id, payload.date)
+        .await?;
+    Ok(Json(template))
+}
+
+pub async fn remove_exception(
+    State(state): State<AppState>,
+    Path((template_id, date)): Path<(Uuid, NaiveDate)>,
+) -> Result<Json<SessionTemplate>, ApiError> {
+    let template = state
+        .template_repository
+        .remove_exception(template_id, date)
+        .await?;
+    Ok(Json(template))
+}
+
+#[derive(Deserialize, Default)]
+pub struct GenerateRequest {
+    pub weeks: Option<u32>,
+}
+
+impl GenerateRequest {
+    fn weeks(&self) -> Result<u32, ApiError> {
+        match self.weeks.unwrap_or(DEFAULT_WEEKS_AHEAD) {
+            weeks @ 1..=MAX_WEEKS_AHEAD => Ok(weeks),
+            _ => Err(ApiError::validation(format!(
+                "weeks must be between 1 and {}",
+                MAX_WEEKS_AHEAD
+            ))),
+        }
+    }
+}
+
+/// Create the template's sessions for the coming weeks. Returns only the
+/// sessions that didn't exist yet, so it is safe to call repeatedly.
+pub async fn generate_sessions(
+    State(state): State<AppState>,
+    Path(template_id): Path<Uuid>,
+    ApiJson(payload): ApiJson<GenerateRequest>,
+) -> Result<Json<Vec<LocalSession>>, ApiError> {
+    let weeks = payload.weeks()?;
+    let created = state
+        .template_repository
+        .generate(template_id, Utc::now(), weeks)
+        .await?;
+    Ok(Json(localise(&state, created).await?))
+}
+
+/// [`generate_sessions`] for every template, e.g. from a weekly cron job
+pub async fn generate_all_sessions(
+    State(state): State<AppState>,
+    ApiJson(payload): ApiJson<GenerateRequest>,
+) -> Result<Json<Vec<LocalSession>>, ApiError> {
+    let weeks = payload.weeks()?;
+    let created = state
+        .template_repository
+        .generate_all(Utc::now(), weeks)
+        .await?;
+    Ok(Json(localise(&state, created).await?))
+}
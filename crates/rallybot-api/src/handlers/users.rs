@@ -1,10 +1,14 @@
+use crate::error::{ApiError, ApiJson};
+use crate::handlers::sessions::{localise, LocalSession};
 use crate::state::AppState;
 use axum::{
     extract::{Path, State},
     http::StatusCode,
     response::Json,
 };
-use rallybot_core::{Session, User, Gender, SkillLevel, PreferredSide, PlayFrequency, LookingFor};
+use rallybot_core::{
+    Gender, LookingFor, PlayFrequency, PreferredSide, SkillLevel, StorageError, User,
+};
 use serde::Deserialize;
 
 #[derive(Deserialize)]
@@ -28,11 +32,16 @@ pub struct CreateUserRequest {
 
 pub async fn create_user(
     State(state): State<AppState>,
-    Json(payload): Json<CreateUserRequest>,
-) -> Result<(StatusCode, Json<User>), StatusCode> {
+    ApiJson(payload): ApiJson<CreateUserRequest>,
+) -> Result<(StatusCode, Json<User>), ApiError> {
     // Check if user with this phone already exists
-    if state.user_repository.get_by_phone(&payload.phone_number).await.is_some() {
-        return Err(StatusCode::CONFLICT);
+    if state
+        .user_repository
+        .get_by_phone(&payload.phone_number)
+        .await?
+        .is_some()
+    {
+        return Err(phone_taken());
     }
     
     let user = User::new(
@@ -53,32 +62,54 @@ pub async fn create_user(
         payload.looking_for,
     );
     
-    state.user_repository.create(user.clone()).await;
-    Ok((StatusCode::CREATED, Json(user)))
+    // A duplicate email, or a phone registered since the check above,
+    // surfaces here as a unique key conflict
+    let created = state
+        .user_repository
+        .create(user)
+        .await
+        .map_err(|e| match e {
+            StorageError::Conflict(constraint) if constraint == "users_email_key" => {
+                ApiError::conflict("email_taken", "A user with this email already exists")
+            }
+            StorageError::Conflict(constraint) if constraint == "users_phone_number_key" => {
+                phone_taken()
+            }
+            e => e.into(),
+        })?;
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+fn phone_taken() -> ApiError {
+    ApiError::conflict("phone_taken", "A user with this phone number already exists")
+}
+
+fn user_not_found() -> ApiError {
+    ApiError::not_found("user_not_found", "User not found")
 }
 
 pub async fn get_user_by_phone(
     State(state): State<AppState>,
     Path(phone): Path<String>,
-) -> Result<Json<User>, StatusCode> {
+) -> Result<Json<User>, ApiError> {
     state
         .user_repository
         .get_by_phone(&phone)
-        .await
+        .await?
         .map(Json)
-        .ok_or(StatusCode::NOT_FOUND)
+        .ok_or_else(user_not_found)
 }
 
 pub async fn get_user_sessions(
     State(state): State<AppState>,
     Path(phone): Path<String>,
-) -> Result<Json<Vec<Session>>, StatusCode> {
+) -> Result<Json<Vec<LocalSession>>, ApiError> {
     let user = state
         .user_repository
         .get_by_phone(&phone)
-        .await
-        .ok_or(StatusCode::NOT_FOUND)?;
+        .await?
+        .ok_or_else(user_not_found)?;
 
-    let sessions = state.session_repository.get_user_sessions(user.id).await;
-    Ok(Json(sessions))
+    let sessions = state.session_repository.get_user_sessions(user.id).await?;
+    Ok(Json(localise(&state, sessions).await?))
 }
\ No newline at end of file
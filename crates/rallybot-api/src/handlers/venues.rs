@@ -1,3 +1,4 @@
+use crate::error::{ApiError, ApiJson};
 use crate::state::AppState;
 use axum::{extract::State, http::StatusCode, response::Json};
 use rallybot_core::Venue;
@@ -7,18 +8,26 @@ use serde::Deserialize;
 pub struct CreateVenueRequest {
     pub name: String,
     pub address: String,
+    /// IANA name such as "Europe/Lisbon"; defaults to the club's own zone
+    pub timezone: Option<String>,
 }
 
-pub async fn list_venues(State(state): State<AppState>) -> Json<Vec<Venue>> {
-    let venues = state.venue_repository.list().await;
-    Json(venues)
+pub async fn list_venues(State(state): State<AppState>) -> Result<Json<Vec<Venue>>, ApiError> {
+    let venues = state.venue_repository.list().await?;
+    Ok(Json(venues))
 }
 
 pub async fn create_venue(
     State(state): State<AppState>,
-    Json(payload): Json<CreateVenueRequest>,
-) -> (StatusCode, Json<Venue>) {
-    let venue = Venue::new(payload.name, payload.address);
-    let created = state.venue_repository.create(venue).await;
-    (StatusCode::CREATED, Json(created))
+    ApiJson(payload): ApiJson<CreateVenueRequest>,
+) -> Result<(StatusCode, Json<Venue>), ApiError> {
+    let mut venue = Venue::new(payload.name, payload.address);
+    if let Some(timezone) = payload.timezone {
+        let timezone = timezone
+            .parse()
+            .map_err(|_| ApiError::validation(format!("Unknown timezone {}", timezone)))?;
+        venue = venue.with_timezone(timezone);
+    }
+    let created = state.venue_repository.create(venue).await?;
+    Ok((StatusCode::CREATED, Json(created)))
 }
\ No newline at end of file
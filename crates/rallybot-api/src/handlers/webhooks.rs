@@ -0,0 +1,147 @@
+use crate::state::AppState;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use rallybot_bot::InboundMessage;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct VerifyQuery {
+    #[serde(rename = "hub.mode")]
+    pub mode: Option<String>,
+    #[serde(rename = "hub.verify_token")]
+    pub verify_token: Option<String>,
+    #[serde(rename = "hub.challenge")]
+    pub challenge: Option<String>,
+}
+
+/// Meta's subscription handshake: echo the challenge back if the token matches
+pub async fn verify_webhook(
+    State(state): State<AppState>,
+    Query(params): Query<VerifyQuery>,
+) -> Result<String, StatusCode> {
+    let token_matches = !state.whatsapp.verify_token.is_empty()
+        && params.verify_token.as_deref() == Some(state.whatsapp.verify_token.as_str());
+
+    match (params.mode.as_deref(), params.challenge) {
+        (Some("subscribe"), Some(challenge)) if token_matches => Ok(challenge),
+        _ => Err(StatusCode::FORBIDDEN),
+    }
+}
+
+/// WhatsApp Cloud API notification: `entry[].changes[].value.{contacts, messages}`
+#[derive(Deserialize)]
+pub struct WebhookPayload {
+    #[serde(default)]
+    pub entry: Vec<WebhookEntry>,
+}
+
+#[derive(Deserialize)]
+pub struct WebhookEntry {
+    #[serde(default)]
+    pub changes: Vec<WebhookChange>,
+}
+
+#[derive(Deserialize)]
+pub struct WebhookChange {
+    pub value: WebhookValue,
+}
+
+#[derive(Deserialize)]
+pub struct WebhookValue {
+    #[serde(default)]
+    pub contacts: Vec<WebhookContact>,
+    #[serde(default)]
+    pub messages: Vec<WebhookMessage>,
+}
+
+#[derive(Deserialize)]
+pub struct WebhookContact {
+    pub wa_id: String,
+    pub profile: Option<WebhookProfile>,
+}
+
+#[derive(Deserialize)]
+pub struct WebhookProfile {
+    pub name: String,
+}
+
+#[derive(Deserialize)]
+pub struct WebhookMessage {
+    pub from: String,
+    pub text: Option<WebhookText>,
+    pub interactive: Option<WebhookInteractive>,
+}
+
+#[derive(Deserialize)]
+pub struct WebhookText {
+    pub body: String,
+}
+
+/// A tap on a list row or reply button we sent
+#[derive(Deserialize)]
+pub struct WebhookInteractive {
+    pub list_reply: Option<WebhookReply>,
+    pub button_reply: Option<WebhookReply>,
+}
+
+#[derive(Deserialize)]
+pub struct WebhookReply {
+    pub id: String,
+}
+
+impl WebhookMessage {
+    /// Typed text, or the id of the picked row/button (ids are bot commands)
+    fn command_text(&self) -> Option<String> {
+        if let Some(text) = &self.text {
+            return Some(text.body.clone());
+        }
+        let interactive = self.interactive.as_ref()?;
+        interactive
+            .list_reply
+            .as_ref()
+            .or(interactive.button_reply.as_ref())
+            .map(|reply| reply.id.clone())
+    }
+}
+
+impl WebhookPayload {
+    /// Text messages and interactive replies in the payload, paired with the
+    /// sender's profile name. Status updates and media messages are skipped.
+    pub fn inbound_messages(&self) -> Vec<InboundMessage> {
+        self.entry
+            .iter()
+            .flat_map(|entry| &entry.changes)
+            .flat_map(|change| {
+                let value = &change.value;
+                value.messages.iter().filter_map(move |message| {
+                    let text = message.command_text()?;
+                    let profile_name = value
+                        .contacts
+                        .iter()
+                        .find(|c| c.wa_id == message.from)
+                        .and_then(|c| c.profile.as_ref())
+                        .map(|p| p.name.clone());
+                    Some(InboundMessage::new(&message.from, profile_name, text))
+                })
+            })
+            .collect()
+    }
+}
+
+pub async fn receive_webhook(
+    State(state): State<AppState>,
+    Json(payload): Json<WebhookPayload>,
+) -> StatusCode {
+    for inbound in payload.inbound_messages() {
+        let reply = state.bot.handle_inbound(&inbound).await;
+        if let Err(e) = state.message_sender.send(&inbound.phone, &reply).await {
+            tracing::warn!("failed to send reply to {}: {}", inbound.phone, e);
+        }
+    }
+
+    // Always acknowledge so Meta does not redeliver
+    StatusCode::OK
+}
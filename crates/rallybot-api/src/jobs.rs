@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use rallybot_core::{SessionRepository, StorageResult};
+use std::{sync::Arc, time::Duration};
+use tokio::task::JoinHandle;
+
+/// Pass every promotion not confirmed by `now` to the next substitute. Both
+/// players hear about it through the repository's events. Returns how many
+/// promotions lapsed.
+pub async fn expire_lapsed_promotions(
+    sessions: &dyn SessionRepository,
+    now: DateTime<Utc>,
+) -> StorageResult<usize> {
+    Ok(sessions.expire_lapsed_promotions(now).await?.len())
+}
+
+/// Run [`expire_lapsed_promotions`] every `every` until the process stops
+pub fn spawn_promotion_expiry(
+    sessions: Arc<dyn SessionRepository>,
+    every: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(every);
+        loop {
+            interval.tick().await;
+            match expire_lapsed_promotions(sessions.as_ref(), Utc::now()).await {
+                Ok(0) => {}
+                Ok(lapsed) => tracing::info!("passed on {} unconfirmed spots", lapsed),
+                Err(e) => tracing::error!("could not expire unconfirmed promotions: {}", e),
+            }
+        }
+    })
+}
@@ -1,7 +1,14 @@
+pub mod auth;
+pub mod config;
+pub mod error;
 pub mod handlers;
+pub mod jobs;
+pub mod signature;
 pub mod state;
 
-use axum::{routing::{get, post, delete}, Router};
+use axum::{middleware, routing::{get, post, delete}, Router};
+use config::{AdminConfig, WhatsAppConfig};
+use rallybot_bot::{Bot, ConsoleSender, MessageSender, Notifier};
 use rallybot_core::{InMemoryStorage, Repository, Storage};
 use state::AppState;
 use std::sync::Arc;
@@ -16,23 +23,87 @@ pub fn create_app() -> Router {
 pub fn create_app_with_repository<S: Storage + 'static>(
     repository: Arc<Repository<S>>,
 ) -> Router {
+    create_app_with_whatsapp(
+        repository,
+        WhatsAppConfig::from_env(),
+        AdminConfig::from_env(),
+        Arc::new(ConsoleSender),
+    )
+}
+
+pub fn create_app_with_whatsapp<S: Storage + 'static>(
+    repository: Arc<Repository<S>>,
+    whatsapp: WhatsAppConfig,
+    admin: AdminConfig,
+    message_sender: Arc<dyn MessageSender>,
+) -> Router {
+    // Players hear about changes however they were made: API, bot or job
+    let notifier = Notifier::with_repository(repository.clone(), message_sender.clone());
+    repository.events().subscribe(Arc::new(notifier));
+
     let state = AppState {
         session_repository: repository.clone() as Arc<dyn rallybot_core::SessionRepository>,
         user_repository: repository.clone() as Arc<dyn rallybot_core::UserRepository>,
-        venue_repository: repository as Arc<dyn rallybot_core::VenueRepository>,
+        venue_repository: repository.clone() as Arc<dyn rallybot_core::VenueRepository>,
+        template_repository: repository.clone() as Arc<dyn rallybot_core::TemplateRepository>,
+        application_repository: repository.clone() as Arc<dyn rallybot_core::ApplicationRepository>,
+        attendance_repository: repository.clone() as Arc<dyn rallybot_core::AttendanceRepository>,
+        bot: Arc::new(
+            Bot::with_repository(repository.clone())
+                .with_application_url(whatsapp.application_url.clone()),
+        ),
+        message_sender,
+        whatsapp,
+        admin,
     };
 
+    // Only signed deliveries reach the bot; the GET handshake is authenticated by its token
+    let webhooks = Router::new()
+        .route("/webhooks/whatsapp", post(handlers::webhooks::receive_webhook))
+        .route_layer(middleware::from_fn_with_state(state.clone(), signature::require_whatsapp_signature))
+        .route("/webhooks/whatsapp", get(handlers::webhooks::verify_webhook));
+
+    // Organiser endpoints need the admin token
+    let admin_routes = Router::new()
+        .route("/admin/applications", get(handlers::applications::list_applications))
+        .route("/admin/applications/:id", get(handlers::applications::get_application))
+        .route("/admin/applications/:id/approve", post(handlers::applications::approve_application))
+        .route("/admin/applications/:id/reject", post(handlers::applications::reject_application))
+        .route("/admin/applications/:id/history", get(handlers::applications::get_application_history))
+        .route("/admin/sessions/:id/register", post(handlers::sessions::place_player))
+        .route("/admin/users/pending", get(handlers::admin::list_pending_users))
+        .route("/admin/users/:id/approve", post(handlers::admin::approve_user))
+        .route("/admin/users/:id/reject", post(handlers::admin::reject_user))
+        .route("/admin/users/:id/suspend", post(handlers::admin::suspend_user))
+        .route("/admin/users/:id/history", get(handlers::admin::get_status_history))
+        .route("/admin/users/:id/late-cancellations", get(handlers::admin::get_late_cancellations))
+        .route("/admin/users/:id/attendance", get(handlers::attendance::get_attendance_stats))
+        .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_admin_token));
+
     Router::new()
         .route("/sessions", get(handlers::sessions::list_sessions).post(handlers::sessions::create_session))
-        .route("/sessions/:id", get(handlers::sessions::get_session_details))
+        .route("/sessions/:id", get(handlers::sessions::get_session_details).patch(handlers::sessions::update_session))
+        .route("/sessions/:id/cancel", post(handlers::sessions::cancel_session))
         .route("/sessions/:id/register", post(handlers::sessions::register_for_session))
         .route("/sessions/:id/unregister", delete(handlers::sessions::unregister_from_session))
+        .route("/sessions/:id/confirm", post(handlers::sessions::confirm_spot))
         .route("/sessions/:id/registrations", get(handlers::sessions::get_session_registrations))
+        .route("/sessions/:id/registrations/history", get(handlers::sessions::get_registration_history))
+        .route("/sessions/:id/attendance", get(handlers::attendance::get_session_attendance).post(handlers::attendance::mark_attendance))
+        .route("/session-templates", get(handlers::templates::list_templates).post(handlers::templates::create_template))
+        .route("/session-templates/generate", post(handlers::templates::generate_all_sessions))
+        .route("/session-templates/:id", get(handlers::templates::get_template).delete(handlers::templates::delete_template))
+        .route("/session-templates/:id/exceptions", post(handlers::templates::add_exception))
+        .route("/session-templates/:id/exceptions/:date", delete(handlers::templates::remove_exception))
+        .route("/session-templates/:id/generate", post(handlers::templates::generate_sessions))
         .route("/users", post(handlers::users::create_user))
+        .route("/applications", post(handlers::applications::submit_application))
         .route("/users/:phone", get(handlers::users::get_user_by_phone))
         .route("/users/:phone/sessions", get(handlers::users::get_user_sessions))
         .route("/venues", get(handlers::venues::list_venues).post(handlers::venues::create_venue))
         .route("/health", get(handlers::health_check))
+        .merge(admin_routes)
+        .merge(webhooks)
         .layer(TraceLayer::new_for_http())
         .with_state(state)
-}
\ No newline at end of file
+}
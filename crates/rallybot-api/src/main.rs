@@ -1,5 +1,10 @@
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
-use rallybot_core::{InMemoryStorage, PostgresStorage, Repository};
+use rallybot_api::config::{AdminConfig, WhatsAppConfig};
+use rallybot_bot::{ConsoleSender, MessageSender, WhatsAppClient};
+use rallybot_core::{
+    CancellationPolicy, InMemoryStorage, LateCancelLimit, PostgresStorage, Repository,
+    SessionRepository, Storage,
+};
 use std::sync::Arc;
 
 #[tokio::main]
@@ -14,18 +19,45 @@ async fn main() {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
+    let sender: Arc<dyn MessageSender> = match (
+        std::env::var("WHATSAPP_PHONE_NUMBER_ID"),
+        std::env::var("WHATSAPP_ACCESS_TOKEN"),
+    ) {
+        (Ok(phone_number_id), Ok(access_token)) => {
+            tracing::info!("Sending replies through the WhatsApp Cloud API");
+            Arc::new(WhatsAppClient::new(phone_number_id, access_token))
+        }
+        _ => {
+            tracing::info!("Printing replies to the console");
+            Arc::new(ConsoleSender)
+        }
+    };
+    let whatsapp = WhatsAppConfig::from_env();
+    let admin = AdminConfig::from_env();
+    if admin.token.is_empty() {
+        tracing::warn!("ADMIN_API_TOKEN is not set; the /admin endpoints will refuse every request");
+    }
+
+    // Promoted substitutes confirm within this many hours when it is set
+    let confirmation_window = env_number("PROMOTION_CONFIRM_HOURS").map(chrono::Duration::hours);
+    let cancellation_policy = cancellation_policy_from_env();
+
     let app = if let Ok(database_url) = std::env::var("DATABASE_URL") {
         tracing::info!("Using PostgreSQL storage");
         let storage = PostgresStorage::new(&database_url)
             .await
             .expect("Failed to connect to PostgreSQL");
-        let repository = Arc::new(Repository::new(Arc::new(storage)));
-        rallybot_api::create_app_with_repository(repository)
+        let repository = Repository::new(Arc::new(storage))
+            .with_confirmation_window(confirmation_window)
+            .with_cancellation_policy(cancellation_policy);
+        start(Arc::new(repository), whatsapp, admin, sender)
     } else {
         tracing::info!("Using in-memory storage");
         let storage = Arc::new(InMemoryStorage::new());
-        let repository = Arc::new(Repository::new(storage));
-        rallybot_api::create_app_with_repository(repository)
+        let repository = Repository::new(storage)
+            .with_confirmation_window(confirmation_window)
+            .with_cancellation_policy(cancellation_policy);
+        start(Arc::new(repository), whatsapp, admin, sender)
     };
     
     let port = std::env::var("PORT").unwrap_or_else(|_| "3000".to_string());
@@ -37,4 +69,47 @@ async fn main() {
     
     tracing::info!("listening on {}", listener.local_addr().unwrap());
     axum::serve(listener, app).await.unwrap();
-}
\ No newline at end of file
+}
+
+fn env_number<T: std::str::FromStr>(name: &str) -> Option<T> {
+    std::env::var(name).ok().and_then(|value| value.parse().ok())
+}
+
+/// The default cutoffs, overridden per session type by
+/// `LATE_CANCEL_HOURS_<TYPE>`. Players are limited to `LATE_CANCEL_LIMIT`
+/// late cancellations in the last `LATE_CANCEL_WINDOW_DAYS` (30 by default)
+/// when it is set.
+fn cancellation_policy_from_env() -> CancellationPolicy {
+    let defaults = CancellationPolicy::default();
+    let cutoff = |session_type: &str, default: chrono::Duration| {
+        env_number(&format!("LATE_CANCEL_HOURS_{}", session_type))
+            .map_or(default, chrono::Duration::hours)
+    };
+    CancellationPolicy {
+        coaching: cutoff("COACHING", defaults.coaching),
+        social: cutoff("SOCIAL", defaults.social),
+        league: cutoff("LEAGUE", defaults.league),
+        mixed: cutoff("MIXED", defaults.mixed),
+        limit: env_number("LATE_CANCEL_LIMIT").map(|max| LateCancelLimit {
+            max,
+            within: chrono::Duration::days(env_number("LATE_CANCEL_WINDOW_DAYS").unwrap_or(30)),
+        }),
+    }
+}
+
+/// How often unconfirmed promotions are checked for a passed deadline
+const PROMOTION_EXPIRY_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Start the background jobs and build the app
+fn start<S: Storage + 'static>(
+    repository: Arc<Repository<S>>,
+    whatsapp: WhatsAppConfig,
+    admin: AdminConfig,
+    sender: Arc<dyn MessageSender>,
+) -> axum::Router {
+    rallybot_api::jobs::spawn_promotion_expiry(
+        repository.clone() as Arc<dyn SessionRepository>,
+        PROMOTION_EXPIRY_INTERVAL,
+    );
+    rallybot_api::create_app_with_whatsapp(repository, whatsapp, admin, sender)
+}
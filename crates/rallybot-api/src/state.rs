@@ -1,4 +1,9 @@
-use rallybot_core::{SessionRepository, UserRepository, VenueRepository};
+use crate::config::{AdminConfig, WhatsAppConfig};
+use rallybot_bot::{Bot, MessageSender};
+use rallybot_core::{
+    ApplicationRepository, AttendanceRepository, SessionRepository, TemplateRepository,
+    UserRepository, VenueRepository,
+};
 use std::sync::Arc;
 
 #[derive(Clone)]
@@ -6,4 +11,11 @@ pub struct AppState {
     pub session_repository: Arc<dyn SessionRepository>,
     pub user_repository: Arc<dyn UserRepository>,
     pub venue_repository: Arc<dyn VenueRepository>,
-}
\ No newline at end of file
+    pub template_repository: Arc<dyn TemplateRepository>,
+    pub application_repository: Arc<dyn ApplicationRepository>,
+    pub attendance_repository: Arc<dyn AttendanceRepository>,
+    pub bot: Arc<Bot>,
+    pub message_sender: Arc<dyn MessageSender>,
+    pub whatsapp: WhatsAppConfig,
+    pub admin: AdminConfig,
+}
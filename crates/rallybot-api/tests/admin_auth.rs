@@ -0,0 +1,42 @@
+mod helpers;
+
+use axum::http::{header::AUTHORIZATION, Method, StatusCode};
+use serde_json::json;
+
+#[tokio::test]
+async fn admin_endpoints_need_the_token() {
+    let app = helpers::TestApp::new().await;
+    let user_id = app.create_test_user("+351911111111", false).await;
+    let approve = || {
+        helpers::json_request(
+            Method::POST,
+            format!("/admin/users/{}/approve", user_id),
+            json!({ "by": "marta@example.com" }),
+        )
+    };
+
+    let (status, _) = app.call_anonymously(helpers::get("/admin/users/pending")).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _) = app.call_anonymously(approve()).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let mut wrong_token = approve();
+    wrong_token
+        .headers_mut()
+        .insert(AUTHORIZATION, "Bearer guessed".parse().unwrap());
+    let (status, _) = app.call(wrong_token).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // With the right token the approval goes through
+    let (status, _) = app.call(approve()).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Player-facing endpoints stay open
+    let (status, _) = app.call_anonymously(helpers::get("/venues")).await;
+    assert_eq!(status, StatusCode::OK);
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
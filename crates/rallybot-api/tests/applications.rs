@@ -0,0 +1,174 @@
+mod helpers;
+
+use axum::http::{Method, StatusCode};
+use rallybot_api::config::WhatsAppConfig;
+use rallybot_bot::RecordingSender;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+fn application_form(phone: &str, email: &str) -> Value {
+    json!({
+        "first_name": "Ana",
+        "last_name": "Silva",
+        "phone_number": phone,
+        "email": email,
+        "city": "Lisbon",
+        "occupation": "Engineer",
+        "company": "Acme",
+        "industry": "Software",
+        "linkedin_url": "https://www.linkedin.com/in/ana-silva",
+        "gender": "female",
+        "skill_levels": ["C"],
+        "preferred_side": "left",
+        "play_frequency": "once_week",
+        "looking_for": ["social_connections"]
+    })
+}
+
+async fn submit(app: &helpers::TestApp, form: Value) -> (StatusCode, Value) {
+    let (status, body) = app.call(helpers::json_request(Method::POST, "/applications", form)).await;
+    (status, serde_json::from_str(&body).unwrap())
+}
+
+#[tokio::test]
+async fn submitting_twice_returns_the_same_application() {
+    let app = helpers::TestApp::new().await;
+
+    let (status, first) = submit(&app, application_form("+351911111111", "ana@example.com")).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(first["status"], "pending");
+
+    let (status, again) = submit(&app, application_form("+351911111111", "ana@example.com")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(again["id"], first["id"]);
+
+    let (status, body) = app.call(helpers::get("/admin/applications")).await;
+    assert_eq!(status, StatusCode::OK);
+    let pending: Vec<Value> = serde_json::from_str(&body).unwrap();
+    assert_eq!(pending.len(), 1);
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
+
+#[tokio::test]
+async fn invalid_fields_are_listed() {
+    let app = helpers::TestApp::new().await;
+    let mut form = application_form("912345678", "ana-at-example.com");
+    form["linkedin_url"] = json!("https://example.com/ana");
+    form["skill_levels"] = json!([]);
+
+    let (status, error) = submit(&app, form).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(error["code"], "validation_failed");
+    let fields: Vec<&str> = error["details"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["field"].as_str().unwrap())
+        .collect();
+    assert_eq!(fields, vec!["phone_number", "email", "linkedin_url", "skill_levels"]);
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
+
+#[tokio::test]
+async fn approving_creates_and_welcomes_the_member() {
+    let sender = Arc::new(RecordingSender::new());
+    let app = helpers::TestApp::with_whatsapp(WhatsAppConfig::default(), sender.clone()).await;
+    let (_, application) = submit(&app, application_form("+351911111111", "ana@example.com")).await;
+    let id = application["id"].as_str().unwrap();
+
+    let (status, body) = app
+        .call(helpers::json_request(
+            Method::POST,
+            format!("/admin/applications/{}/approve", id),
+            json!({ "by": "admin@rally.pt", "reason": "Known player" }),
+        ))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let approved: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(approved["application"]["status"], "approved");
+    assert_eq!(approved["application"]["user_id"], approved["user"]["id"]);
+    assert_eq!(approved["user"]["status"], "approved");
+
+    let (status, body) = app.call(helpers::get("/users/+351911111111")).await;
+    assert_eq!(status, StatusCode::OK);
+    let user: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(user["id"], approved["user"]["id"]);
+    assert!(sender.wait_for("+351911111111", 1).await[0].body().contains("Welcome to Rally"));
+
+    let (_, body) = app
+        .call(helpers::get(format!("/admin/applications/{}/history", id)))
+        .await;
+    let history: Vec<Value> = serde_json::from_str(&body).unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0]["from_status"], "pending");
+    assert_eq!(history[0]["to_status"], "approved");
+    assert_eq!(history[0]["changed_by"], "admin@rally.pt");
+
+    // Members can't apply again, and approval can't be undone
+    let (status, error) = submit(&app, application_form("+351911111111", "ana@example.com")).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(error["code"], "already_member");
+    let (status, body) = app
+        .call(helpers::json_request(
+            Method::POST,
+            format!("/admin/applications/{}/reject", id),
+            json!({ "by": "admin@rally.pt" }),
+        ))
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let error: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["code"], "invalid_status_transition");
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
+
+#[tokio::test]
+async fn rejected_applications_are_listed_by_status() {
+    let app = helpers::TestApp::new().await;
+    let (_, application) = submit(&app, application_form("+351911111111", "ana@example.com")).await;
+    let id = application["id"].as_str().unwrap();
+
+    let (status, _) = app
+        .call(helpers::json_request(
+            Method::POST,
+            format!("/admin/applications/{}/reject", id),
+            json!({ "by": "admin@rally.pt", "reason": "Not in Lisbon" }),
+        ))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, body) = app.call(helpers::get("/admin/applications")).await;
+    let pending: Vec<Value> = serde_json::from_str(&body).unwrap();
+    assert!(pending.is_empty());
+    let (_, body) = app
+        .call(helpers::get("/admin/applications?status=rejected"))
+        .await;
+    let rejected: Vec<Value> = serde_json::from_str(&body).unwrap();
+    assert_eq!(rejected.len(), 1);
+    assert_eq!(rejected[0]["id"], id);
+
+    // Turned down, they can apply again with the same details
+    let (status, reapplied) = submit(&app, application_form("+351911111111", "ana@example.com")).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_ne!(reapplied["id"], id);
+    let (_, body) = app.call(helpers::get("/admin/applications")).await;
+    let pending: Vec<Value> = serde_json::from_str(&body).unwrap();
+    assert_eq!(pending.len(), 1);
+
+    let (status, _) = app
+        .call(helpers::get(format!("/admin/applications/{}", uuid::Uuid::new_v4())))
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
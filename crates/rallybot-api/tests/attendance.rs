@@ -0,0 +1,138 @@
+mod helpers;
+
+use axum::http::{Method, StatusCode};
+use chrono::{Duration, Utc};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+/// A mixed session starting `starts_in` from now, with the users signed up
+async fn session_with(app: &helpers::TestApp, starts_in: Duration, phones: &[&str]) -> (String, Vec<Uuid>) {
+    let session = app
+        .create_test_session(json!({
+            "session_type": "X",
+            "datetime": (Utc::now() + starts_in).to_rfc3339(),
+            "skill_level": null
+        }))
+        .await;
+    let session_id = helpers::id_of(&session);
+    let user_ids = app.register_test_users(&session_id, phones).await;
+    (session_id, user_ids)
+}
+
+#[tokio::test]
+async fn organiser_marks_attendance_after_the_session() {
+    let app = helpers::TestApp::new().await;
+    let (session_id, user_ids) =
+        session_with(&app, -Duration::hours(3), &["+351911111111", "+351922222222"]).await;
+
+    for (user_id, status) in [(user_ids[0], "attended"), (user_ids[1], "no_show")] {
+        let (status_code, body) = app
+            .call(helpers::json_request(
+                Method::POST,
+                format!("/sessions/{}/attendance", session_id),
+                json!({ "user_id": user_id, "status": status, "by": "admin@rally.pt" }),
+            ))
+            .await;
+        assert_eq!(status_code, StatusCode::OK);
+        let attendance: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(attendance["status"], status);
+        assert_eq!(attendance["recorded_by"], "admin@rally.pt");
+    }
+
+    let (status, body) = app.call(helpers::get(format!("/sessions/{}/attendance", session_id))).await;
+    assert_eq!(status, StatusCode::OK);
+    let attendance: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(attendance.as_array().unwrap().len(), 2);
+
+    let (status, body) = app
+        .call(helpers::get(format!("/admin/users/{}/attendance", user_ids[1])))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let stats: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(stats["attended"], 0);
+    assert_eq!(stats["no_show"], 1);
+    assert_eq!(stats["excused"], 0);
+
+    // Someone who wasn't playing
+    let (status, body) = app
+        .call(helpers::json_request(
+            Method::POST,
+            format!("/sessions/{}/attendance", session_id),
+            json!({ "user_id": Uuid::new_v4(), "status": "attended", "by": "admin@rally.pt" }),
+        ))
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let error: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["code"], "not_registered");
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
+
+#[tokio::test]
+async fn no_shows_cannot_withdraw_afterwards() {
+    let app = helpers::TestApp::new().await;
+    let (session_id, user_ids) = session_with(&app, -Duration::hours(3), &["+351911111111"]).await;
+
+    let (status, _) = app
+        .call(helpers::json_request(
+            Method::POST,
+            format!("/sessions/{}/attendance", session_id),
+            json!({ "user_id": user_ids[0], "status": "no_show", "by": "admin@rally.pt" }),
+        ))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = app
+        .call(helpers::json_request(
+            Method::DELETE,
+            format!("/sessions/{}/unregister", session_id),
+            json!({ "user_id": user_ids[0] }),
+        ))
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let error: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["code"], "session_started");
+
+    // The no-show still counts against them
+    let (_, body) = app
+        .call(helpers::get(format!("/admin/users/{}/attendance", user_ids[0])))
+        .await;
+    let stats: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(stats["no_show"], 1);
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
+
+#[tokio::test]
+async fn attendance_waits_for_the_session_to_end() {
+    let app = helpers::TestApp::new().await;
+    let (session_id, user_ids) = session_with(&app, Duration::hours(1), &["+351911111111"]).await;
+
+    let (status, body) = app
+        .call(helpers::json_request(
+            Method::POST,
+            format!("/sessions/{}/attendance", session_id),
+            json!({ "user_id": user_ids[0], "status": "excused", "by": "admin@rally.pt" }),
+        ))
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let error: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["code"], "session_not_over");
+
+    let (status, _) = app
+        .call(helpers::json_request(
+            Method::POST,
+            format!("/sessions/{}/attendance", session_id),
+            json!({ "user_id": user_ids[0], "status": "excused", "by": " " }),
+        ))
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
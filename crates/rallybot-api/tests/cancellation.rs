@@ -0,0 +1,124 @@
+mod helpers;
+
+use axum::{
+    body::Body,
+    http::{Method, Request, StatusCode},
+};
+use rallybot_api::config::WhatsAppConfig;
+use rallybot_bot::RecordingSender;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+async fn create_session(app: &helpers::TestApp) -> String {
+    helpers::id_of(&app.create_test_session(json!({ "max_players": 2 })).await)
+}
+
+fn cancel(session_id: &str, reason: &str) -> Request<Body> {
+    helpers::json_request(
+        Method::POST,
+        format!("/sessions/{}/cancel", session_id),
+        json!({ "reason": reason }),
+    )
+}
+
+#[tokio::test]
+async fn cancel_session_hides_it_and_blocks_sign_ups() {
+    let app = helpers::TestApp::new().await;
+    let session_id = create_session(&app).await;
+    app.create_test_user("+351912345678", true).await;
+
+    let (status, body) = app.call(cancel(&session_id, "Courts closed")).await;
+    assert_eq!(status, StatusCode::OK);
+    let session: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(session["status"], "cancelled");
+    assert_eq!(session["cancellation_reason"], "Courts closed");
+
+    let (_, body) = app.call(helpers::get("/sessions")).await;
+    let sessions: Vec<Value> = serde_json::from_str(&body).unwrap();
+    assert!(sessions.is_empty());
+
+    let (status, body) = app
+        .call(helpers::json_request(
+            Method::POST,
+            format!("/sessions/{}/register", session_id),
+            json!({ "phone_number": "+351912345678" }),
+        ))
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let error: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["code"], "session_closed");
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
+
+#[tokio::test]
+async fn cancel_twice_returns_409() {
+    let app = helpers::TestApp::new().await;
+    let session_id = create_session(&app).await;
+
+    let (status, _) = app.call(cancel(&session_id, "Rain")).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = app.call(cancel(&session_id, "Rain")).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let error: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["code"], "session_not_scheduled");
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
+
+#[tokio::test]
+async fn cancel_unknown_session_returns_404() {
+    let app = helpers::TestApp::new().await;
+
+    let (status, body) = app
+        .call(cancel("00000000-0000-0000-0000-000000000000", "Rain"))
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let error: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["code"], "session_not_found");
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
+
+#[tokio::test]
+async fn cancel_requires_a_reason() {
+    let app = helpers::TestApp::new().await;
+    let session_id = create_session(&app).await;
+
+    let (status, body) = app.call(cancel(&session_id, "  ")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let error: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["code"], "validation_failed");
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
+
+#[tokio::test]
+async fn cancel_notifies_players_and_substitutes() {
+    let sender = Arc::new(RecordingSender::new());
+    let app = helpers::TestApp::with_whatsapp(WhatsAppConfig::default(), sender.clone()).await;
+    let session_id = create_session(&app).await;
+
+    // Two confirmed and one substitute
+    let phones = ["+351911111111", "+351922222222", "+351933333333"];
+    app.register_test_users(&session_id, &phones).await;
+
+    let (status, _) = app.call(cancel(&session_id, "Courts closed")).await;
+    assert_eq!(status, StatusCode::OK);
+
+    for phone in phones {
+        let sent = sender.wait_for(phone, 1).await;
+        assert_eq!(sent.len(), 1);
+        assert!(sent[0].body().contains("cancelled"));
+        assert!(sent[0].body().contains("Reason: Courts closed"));
+    }
+}
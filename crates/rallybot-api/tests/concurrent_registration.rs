@@ -0,0 +1,93 @@
+mod helpers;
+
+use axum::http::{Method, StatusCode};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+const SIGN_UPS: usize = 20;
+
+/// Many players tapping "join" at the same moment must never overfill a session
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+async fn concurrent_registrations_in_memory() {
+    let app = helpers::TestApp::with_in_memory().await;
+    run_concurrent_registrations(app).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+#[serial_test::serial]
+async fn concurrent_registrations_postgres() {
+    let app = helpers::TestApp::with_postgres().await;
+    run_concurrent_registrations(app).await;
+}
+
+async fn run_concurrent_registrations(app: helpers::TestApp) {
+    let session = app
+        .create_test_session(json!({
+            "datetime": "2024-12-31T18:00:00Z",
+            "max_players": 4,
+            "max_substitutes": 3
+        }))
+        .await;
+    let session_id = helpers::id_of(&session);
+
+    let mut phones = Vec::new();
+    for i in 0..SIGN_UPS {
+        let phone = format!("+3519100000{:02}", i);
+        app.create_test_user(&phone, true).await;
+        phones.push(phone);
+    }
+    
+    // Fire every sign-up at once
+    let app = Arc::new(app);
+    let handles: Vec<_> = phones
+        .into_iter()
+        .map(|phone| {
+            let app = app.clone();
+            let session_id = session_id.clone();
+            tokio::spawn(async move {
+                app.call(helpers::json_request(
+                    Method::POST,
+                    format!("/sessions/{}/register", session_id),
+                    json!({ "phone_number": phone }),
+                ))
+                .await
+            })
+        })
+        .collect();
+    
+    let mut confirmed = 0;
+    let mut substitutes = 0;
+    let mut full = 0;
+    for handle in handles {
+        let (status, body) = handle.await.unwrap();
+        match status {
+            StatusCode::OK => {
+                let response: Value = serde_json::from_str(&body).unwrap();
+                match response["status"].as_str().unwrap() {
+                    "confirmed" => confirmed += 1,
+                    "substitute" => substitutes += 1,
+                    other => panic!("unexpected status {}", other),
+                }
+            }
+            StatusCode::CONFLICT => full += 1,
+            other => panic!("unexpected response {}: {}", other, body),
+        }
+    }
+    
+    assert_eq!(confirmed, 4);
+    assert_eq!(substitutes, 3);
+    assert_eq!(full, SIGN_UPS - 7);
+    
+    // What was stored agrees with what was reported
+    let (status, body) = app.call(helpers::get(format!("/sessions/{}", session_id))).await;
+    assert_eq!(status, StatusCode::OK);
+    let details: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(details["confirmed_count"], 4);
+    assert_eq!(details["substitute_count"], 3);
+    
+    if let Ok(app) = Arc::try_unwrap(app) {
+        if let Some(test_db) = app.test_db {
+            test_db.cleanup().await;
+        }
+    }
+}
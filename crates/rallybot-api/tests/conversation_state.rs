@@ -0,0 +1,81 @@
+mod helpers;
+
+use chrono::Duration;
+use rallybot_core::{ConversationMenu, ConversationState, SessionType};
+use uuid::Uuid;
+
+/// Conversation state must round-trip through both storage implementations
+#[tokio::test]
+async fn test_conversation_state_in_memory() {
+    let app = helpers::TestApp::with_in_memory().await;
+    run_conversation_state_flow(app).await;
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_conversation_state_postgres() {
+    let app = helpers::TestApp::with_postgres().await;
+    run_conversation_state_flow(app).await;
+}
+
+async fn run_conversation_state_flow(app: helpers::TestApp) {
+    let phone = "+351911111111";
+    let session_ids = vec![Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+
+    assert!(app.storage.get_conversation_state(phone).await.unwrap().is_none());
+
+    // Save a session list and read it back in order
+    app.storage
+        .save_conversation_state(ConversationState::new(
+            phone.to_string(),
+            ConversationMenu::SessionList,
+            Some(SessionType::League),
+            session_ids.clone(),
+            Duration::hours(1),
+        ))
+        .await
+        .unwrap();
+
+    let state = app.storage.get_conversation_state(phone).await.unwrap().unwrap();
+    assert_eq!(state.menu, ConversationMenu::SessionList);
+    assert_eq!(state.session_type, Some(SessionType::League));
+    assert_eq!(state.session_ids, session_ids);
+    assert_eq!(state.session_at(2), Some(session_ids[1]));
+
+    // Saving again replaces the previous menu
+    app.storage
+        .save_conversation_state(ConversationState::new(
+            phone.to_string(),
+            ConversationMenu::MainMenu,
+            None,
+            vec![],
+            Duration::hours(1),
+        ))
+        .await
+        .unwrap();
+
+    let state = app.storage.get_conversation_state(phone).await.unwrap().unwrap();
+    assert_eq!(state.menu, ConversationMenu::MainMenu);
+    assert_eq!(state.session_type, None);
+    assert!(state.session_ids.is_empty());
+
+    // Expired state is not returned
+    app.storage
+        .save_conversation_state(ConversationState::new(
+            phone.to_string(),
+            ConversationMenu::SessionList,
+            Some(SessionType::Social),
+            session_ids,
+            Duration::seconds(-1),
+        ))
+        .await
+        .unwrap();
+    assert!(app.storage.get_conversation_state(phone).await.unwrap().is_none());
+
+    assert!(app.storage.delete_conversation_state(phone).await.unwrap());
+    assert!(!app.storage.delete_conversation_state(phone).await.unwrap());
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
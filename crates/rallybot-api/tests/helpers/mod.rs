@@ -1,23 +1,58 @@
+#![allow(dead_code)]
+
 mod config;
 
 use axum::{
     body::Body,
-    http::{Request, StatusCode},
+    http::{header::AUTHORIZATION, Method, Request, StatusCode},
     Router,
 };
-use rallybot_api::create_app_with_repository;
+use rallybot_api::{
+    config::{AdminConfig, WhatsAppConfig},
+    create_app_with_whatsapp,
+};
+use rallybot_bot::{ConsoleSender, MessageSender};
 use fake::{faker::*, Fake};
 use rallybot_core::{
     Gender, InMemoryStorage, LookingFor, PlayFrequency, PostgresStorage, PreferredSide, 
-    Repository, SkillLevel, Storage, User, Venue,
+    Repository, SkillLevel, Storage, User, UserStatus, Venue,
 };
 use rand::{seq::SliceRandom, Rng};
+use serde_json::{json, Value};
 use std::sync::Arc;
 use tower::ServiceExt;
 use uuid::Uuid;
 
 pub use config::{StorageType, TestDatabase};
 
+/// The token test apps expect on `/admin` requests
+pub const ADMIN_TOKEN: &str = "test-admin-token";
+
+pub fn admin_config() -> AdminConfig {
+    AdminConfig {
+        token: ADMIN_TOKEN.to_string(),
+    }
+}
+
+/// A request carrying `body` as JSON
+pub fn json_request(method: Method, uri: impl AsRef<str>, body: Value) -> Request<Body> {
+    Request::builder()
+        .method(method)
+        .uri(uri.as_ref())
+        .header("content-type", "application/json")
+        .body(Body::from(serde_json::to_string(&body).unwrap()))
+        .unwrap()
+}
+
+pub fn get(uri: impl AsRef<str>) -> Request<Body> {
+    Request::builder().uri(uri.as_ref()).body(Body::empty()).unwrap()
+}
+
+/// The `id` of a created resource
+pub fn id_of(resource: &Value) -> String {
+    resource["id"].as_str().unwrap().to_string()
+}
+
 pub struct TestApp {
     pub app: Router,
     pub storage: Arc<dyn Storage>,
@@ -35,7 +70,24 @@ impl TestApp {
     pub async fn with_in_memory() -> Self {
         let storage = Arc::new(InMemoryStorage::new());
         let repository = Arc::new(Repository::new(storage.clone()));
-        let app = create_app_with_repository(repository);
+        let app = create_app_with_whatsapp(
+            repository,
+            WhatsAppConfig::default(),
+            admin_config(),
+            Arc::new(ConsoleSender),
+        );
+        
+        Self { 
+            app, 
+            storage: storage as Arc<dyn Storage>,
+            test_db: None,
+        }
+    }
+    
+    pub async fn with_whatsapp(whatsapp: WhatsAppConfig, sender: Arc<dyn MessageSender>) -> Self {
+        let storage = Arc::new(InMemoryStorage::new());
+        let repository = Arc::new(Repository::new(storage.clone()));
+        let app = create_app_with_whatsapp(repository, whatsapp, admin_config(), sender);
         
         Self { 
             app, 
@@ -49,7 +101,12 @@ impl TestApp {
         let pool = test_db.get_pool().await;
         let storage = Arc::new(PostgresStorage::new_with_pool(pool));
         let repository = Arc::new(Repository::new(storage.clone()));
-        let app = create_app_with_repository(repository);
+        let app = create_app_with_whatsapp(
+            repository,
+            WhatsAppConfig::default(),
+            admin_config(),
+            Arc::new(ConsoleSender),
+        );
         
         Self { 
             app, 
@@ -58,7 +115,6 @@ impl TestApp {
         }
     }
 
-    #[allow(dead_code)]
     pub async fn create_test_user(&self, phone: &str, approved: bool) -> Uuid {
         let mut rng = rand::thread_rng();
         
@@ -74,12 +130,8 @@ impl TestApp {
             company::en::Industry().fake(),
             format!("https://linkedin.com/in/{}", internet::en::Username().fake::<String>()),
             if rng.gen_bool(0.5) { Gender::Male } else { Gender::Female },
-            vec![
-                *[SkillLevel::Beginner, SkillLevel::LowIntermediate, SkillLevel::Intermediate, 
-                  SkillLevel::UpperIntermediate, SkillLevel::Advanced]
-                    .choose(&mut rng)
-                    .unwrap()
-            ],
+            // Fits the "C" sessions most tests create
+            vec![SkillLevel::Intermediate],
             *[PreferredSide::Right, PreferredSide::Left, PreferredSide::Flexible]
                 .choose(&mut rng)
                 .unwrap(),
@@ -94,18 +146,18 @@ impl TestApp {
         );
         
         let mut user = user;
-        user.is_approved = approved;
+        if approved {
+            user.status = UserStatus::Approved;
+        }
         
-        self.storage.create_user(user.clone()).await;
+        self.storage.create_user(user.clone()).await.unwrap();
         user.id
     }
 
-    #[allow(dead_code)]
     pub async fn create_test_venue(&self) -> Uuid {
         self.create_test_venue_with_data(None, None).await
     }
 
-    #[allow(dead_code)]
     pub async fn create_test_venue_with_data(&self, name: Option<&str>, address: Option<&str>) -> Uuid {
         let venue = Venue::new(
             name.map(|s| s.to_string())
@@ -117,11 +169,68 @@ impl TestApp {
                     address::en::CountryName().fake::<String>()
                 )),
         );
-        self.storage.create_venue(venue.clone()).await;
+        self.storage.create_venue(venue.clone()).await.unwrap();
         venue.id
     }
 
-    pub async fn call(&self, request: Request<Body>) -> (StatusCode, String) {
+    /// Create a session through the API and return it. `fields` override a
+    /// Social "C" session on 31 December 2030 at a new venue; a null field is
+    /// left out.
+    pub async fn create_test_session(&self, fields: Value) -> Value {
+        let mut session = json!({
+            "session_type": "S",
+            "datetime": "2030-12-31T18:00:00Z",
+            "duration_minutes": 90,
+            "skill_level": "C"
+        });
+        if fields.get("venue_id").is_none() {
+            session["venue_id"] = json!(self.create_test_venue().await);
+        }
+        let session_fields = session.as_object_mut().unwrap();
+        for (field, value) in fields.as_object().unwrap() {
+            if value.is_null() {
+                session_fields.remove(field);
+            } else {
+                session_fields.insert(field.clone(), value.clone());
+            }
+        }
+
+        let (status, body) = self.call(json_request(Method::POST, "/sessions", session)).await;
+        assert_eq!(status, StatusCode::CREATED, "{}", body);
+        serde_json::from_str(&body).unwrap()
+    }
+
+    /// Sign an approved user up for the session with each phone, in order
+    pub async fn register_test_users(&self, session_id: &str, phones: &[&str]) -> Vec<Uuid> {
+        let mut user_ids = Vec::new();
+        for phone in phones {
+            user_ids.push(self.create_test_user(phone, true).await);
+            let (status, body) = self
+                .call(json_request(
+                    Method::POST,
+                    format!("/sessions/{}/register", session_id),
+                    json!({ "phone_number": phone }),
+                ))
+                .await;
+            assert_eq!(status, StatusCode::OK, "{}", body);
+            // Keep sign-up times apart so substitutes queue in this order
+            tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+        }
+        user_ids
+    }
+
+    /// Send `request` as an organiser, with the admin token unless the
+    /// request sets its own `Authorization`
+    pub async fn call(&self, mut request: Request<Body>) -> (StatusCode, String) {
+        if !request.headers().contains_key(AUTHORIZATION) {
+            let token = format!("Bearer {}", ADMIN_TOKEN).parse().unwrap();
+            request.headers_mut().insert(AUTHORIZATION, token);
+        }
+        self.call_anonymously(request).await
+    }
+
+    /// Send `request` exactly as built
+    pub async fn call_anonymously(&self, request: Request<Body>) -> (StatusCode, String) {
         let response = self.app.clone().oneshot(request).await.unwrap();
         let status = response.status();
         let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
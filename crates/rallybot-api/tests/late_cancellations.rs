@@ -0,0 +1,67 @@
+mod helpers;
+
+use axum::http::{Method, StatusCode};
+use chrono::{Duration, Utc};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+const PHONES: [&str; 3] = ["+351911111111", "+351922222222", "+351933333333"];
+
+async fn unregister(app: &helpers::TestApp, session_id: &str, user_id: Uuid) -> (StatusCode, String) {
+    app.call(helpers::json_request(
+        Method::DELETE,
+        format!("/sessions/{}/unregister", session_id),
+        json!({ "user_id": user_id }),
+    ))
+    .await
+}
+
+#[tokio::test]
+async fn late_cancellation_is_refused_without_a_substitute_and_recorded_otherwise() {
+    let app = helpers::TestApp::new().await;
+
+    // Coaching sessions close for cancellations twelve hours before the start
+    let session = app
+        .create_test_session(json!({
+            "session_type": "C",
+            "datetime": (Utc::now() + Duration::hours(6)).to_rfc3339(),
+            "max_players": 2
+        }))
+        .await;
+    let session_id = helpers::id_of(&session);
+    let user_ids = app.register_test_users(&session_id, &PHONES).await;
+
+    // The substitute takes the place
+    let (status, _) = unregister(&app, &session_id, user_ids[0]).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+
+    // Nobody is left to take this one
+    let (status, body) = unregister(&app, &session_id, user_ids[1]).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let error: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["code"], "cancellation_too_late");
+
+    let (status, body) = app
+        .call(helpers::get(format!("/admin/users/{}/late-cancellations", user_ids[0])))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let report: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(report["count"], 1);
+    assert_eq!(report["cancellations"][0]["session_id"], session_id);
+
+    let (status, body) = app
+        .call(helpers::get(format!("/admin/users/{}/late-cancellations", user_ids[1])))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let report: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(report["count"], 0);
+
+    let (status, _) = app
+        .call(helpers::get(format!("/admin/users/{}/late-cancellations", Uuid::new_v4())))
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
@@ -40,7 +40,7 @@ async fn register_for_session_returns_confirmed() {
     
     let request = Request::builder()
         .method(Method::POST)
-        .uri(&format!("/sessions/{}/register", session_id))
+        .uri(format!("/sessions/{}/register", session_id))
         .header("content-type", "application/json")
         .body(Body::from(serde_json::to_string(&register_body).unwrap()))
         .unwrap();
@@ -88,7 +88,7 @@ async fn register_with_unapproved_user_returns_403() {
     
     let request = Request::builder()
         .method(Method::POST)
-        .uri(&format!("/sessions/{}/register", session_id))
+        .uri(format!("/sessions/{}/register", session_id))
         .header("content-type", "application/json")
         .body(Body::from(serde_json::to_string(&register_body).unwrap()))
         .unwrap();
@@ -96,7 +96,9 @@ async fn register_with_unapproved_user_returns_403() {
     let (status, body) = app.call(request).await;
     
     assert_eq!(status, StatusCode::FORBIDDEN);
-    assert_eq!(body, "User not approved");
+    let error: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["code"], "user_not_approved");
+    assert_eq!(error["message"], "User not approved");
 }
 
 #[tokio::test]
@@ -134,7 +136,7 @@ async fn fifth_registration_becomes_substitute() {
         
         let request = Request::builder()
             .method(Method::POST)
-            .uri(&format!("/sessions/{}/register", session_id))
+            .uri(format!("/sessions/{}/register", session_id))
             .header("content-type", "application/json")
             .body(Body::from(serde_json::to_string(&register_body).unwrap()))
             .unwrap();
@@ -154,7 +156,7 @@ async fn fifth_registration_becomes_substitute() {
     
     let request = Request::builder()
         .method(Method::POST)
-        .uri(&format!("/sessions/{}/register", session_id))
+        .uri(format!("/sessions/{}/register", session_id))
         .header("content-type", "application/json")
         .body(Body::from(serde_json::to_string(&register_body).unwrap()))
         .unwrap();
@@ -199,7 +201,7 @@ async fn double_registration_returns_conflict() {
     // First registration
     let request = Request::builder()
         .method(Method::POST)
-        .uri(&format!("/sessions/{}/register", session_id))
+        .uri(format!("/sessions/{}/register", session_id))
         .header("content-type", "application/json")
         .body(Body::from(serde_json::to_string(&register_body).unwrap()))
         .unwrap();
@@ -209,7 +211,7 @@ async fn double_registration_returns_conflict() {
     // Second registration (should fail)
     let request = Request::builder()
         .method(Method::POST)
-        .uri(&format!("/sessions/{}/register", session_id))
+        .uri(format!("/sessions/{}/register", session_id))
         .header("content-type", "application/json")
         .body(Body::from(serde_json::to_string(&register_body).unwrap()))
         .unwrap();
@@ -217,5 +219,70 @@ async fn double_registration_returns_conflict() {
     let (status, body) = app.call(request).await;
     
     assert_eq!(status, StatusCode::CONFLICT);
-    assert_eq!(body, "Already registered");
-}
\ No newline at end of file
+    let error: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["code"], "already_registered");
+    assert_eq!(error["message"], "Already registered");
+}
+
+#[tokio::test]
+async fn register_outside_skill_level_needs_override() {
+    let app = helpers::TestApp::new().await;
+    let venue_id = app.create_test_venue().await;
+    let user_id = app.create_test_user("+351912345678", true).await;
+
+    let session_body = json!({
+        "session_type": "L",
+        "datetime": "2030-12-31T18:00:00Z",
+        "duration_minutes": 90,
+        "venue_id": venue_id,
+        "skill_level": "H"
+    });
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/sessions")
+        .header("content-type", "application/json")
+        .body(Body::from(serde_json::to_string(&session_body).unwrap()))
+        .unwrap();
+    let (_status, body) = app.call(request).await;
+    let session: Value = serde_json::from_str(&body).unwrap();
+    let session_id = session["id"].as_str().unwrap();
+
+    let register = |path: &str, body: Value| {
+        Request::builder()
+            .method(Method::POST)
+            .uri(format!("{}/sessions/{}/register", path, session_id))
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_string(&body).unwrap()))
+            .unwrap()
+    };
+
+    let (status, body) = app
+        .call(register("", json!({ "phone_number": "+351912345678" })))
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let error: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["code"], "skill_level_mismatch");
+
+    // Players can't override it themselves
+    let (status, _) = app
+        .call(register(
+            "",
+            json!({
+                "phone_number": "+351912345678",
+                "override_skill_level": true
+            }),
+        ))
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, body) = app
+        .call(register("/admin", json!({ "user_id": user_id })))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let response: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(response["status"], "confirmed");
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
@@ -0,0 +1,78 @@
+mod helpers;
+
+use axum::http::{Method, StatusCode};
+use chrono::{Duration, Utc};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+const PHONES: [&str; 3] = ["+351911111111", "+351922222222", "+351933333333"];
+
+#[tokio::test]
+async fn history_shows_who_was_first_on_the_substitutes_list() {
+    let app = helpers::TestApp::new().await;
+
+    // Inside the coaching cancellation cutoff, so leaving is a late cancellation
+    let session = app
+        .create_test_session(json!({
+            "session_type": "C",
+            "datetime": (Utc::now() + Duration::hours(6)).to_rfc3339(),
+            "max_players": 2
+        }))
+        .await;
+    let session_id = helpers::id_of(&session);
+    let user_ids = app.register_test_users(&session_id, &PHONES).await;
+
+    let (status, _) = app
+        .call(helpers::json_request(
+            Method::DELETE,
+            format!("/sessions/{}/unregister", session_id),
+            json!({ "user_id": user_ids[0] }),
+        ))
+        .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+
+    let (status, body) = app
+        .call(helpers::get(format!("/sessions/{}/registrations/history", session_id)))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let history: Vec<Value> = serde_json::from_str(&body).unwrap();
+    let entries: Vec<(String, &str, &str)> = history
+        .iter()
+        .map(|e| {
+            (
+                e["user_id"].as_str().unwrap().to_string(),
+                e["kind"].as_str().unwrap(),
+                e["actor"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    let user = |i: usize| user_ids[i].to_string();
+    assert_eq!(
+        entries,
+        vec![
+            (user(0), "registered", "player"),
+            (user(1), "registered", "player"),
+            (user(2), "registered", "player"),
+            (user(0), "late_cancelled", "player"),
+            (user(2), "promoted", "system"),
+        ]
+    );
+    assert_eq!(history[2]["status"], "substitute");
+    assert_eq!(history[3]["status"], Value::Null);
+
+    // The player who left is gone from the registrations but not the history
+    let (_, body) = app
+        .call(helpers::get(format!("/sessions/{}/registrations", session_id)))
+        .await;
+    let registrations: Vec<Value> = serde_json::from_str(&body).unwrap();
+    assert_eq!(registrations.len(), 2);
+
+    let (status, _) = app
+        .call(helpers::get(format!("/sessions/{}/registrations/history", Uuid::new_v4())))
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
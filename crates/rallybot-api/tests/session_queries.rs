@@ -0,0 +1,139 @@
+mod helpers;
+
+use axum::http::{Method, StatusCode};
+use serde_json::{json, Value};
+
+async fn create_session(
+    app: &helpers::TestApp,
+    venue_id: uuid::Uuid,
+    datetime: &str,
+    skill_level: &str,
+    max_players: i32,
+) -> String {
+    let session = app
+        .create_test_session(json!({
+            "datetime": datetime,
+            "venue_id": venue_id,
+            "skill_level": skill_level,
+            "max_players": max_players
+        }))
+        .await;
+    helpers::id_of(&session)
+}
+
+async fn list(app: &helpers::TestApp, query: &str) -> (StatusCode, Value) {
+    let (status, body) = app.call(helpers::get(format!("/sessions?{}", query))).await;
+    (status, serde_json::from_str(&body).unwrap())
+}
+
+fn ids(sessions: &Value) -> Vec<&str> {
+    sessions
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["id"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn filters_by_venue_level_and_date_range() {
+    let app = helpers::TestApp::new().await;
+    let venue_a = app.create_test_venue().await;
+    let venue_b = app.create_test_venue().await;
+    let first = create_session(&app, venue_a, "2030-03-04T18:00:00Z", "C", 4).await;
+    let second = create_session(&app, venue_a, "2030-03-05T18:00:00Z", "D", 4).await;
+    let third = create_session(&app, venue_b, "2030-03-06T18:00:00Z", "C", 4).await;
+
+    let (status, sessions) = list(&app, &format!("venue_id={}", venue_a)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(ids(&sessions), vec![first.as_str(), second.as_str()]);
+
+    let (_, sessions) = list(&app, "skill_levels=C").await;
+    assert_eq!(ids(&sessions), vec![first.as_str(), third.as_str()]);
+
+    let (_, sessions) = list(&app, "skill_levels=D,E").await;
+    assert_eq!(ids(&sessions), vec![second.as_str()]);
+
+    let (_, sessions) = list(&app, "from=2030-03-05T00:00:00Z&to=2030-03-06T18:00:00Z").await;
+    assert_eq!(ids(&sessions), vec![second.as_str()]);
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
+
+#[tokio::test]
+async fn sorts_and_pages() {
+    let app = helpers::TestApp::new().await;
+    let venue_id = app.create_test_venue().await;
+    let mut created = Vec::new();
+    for day in 1..=5 {
+        let datetime = format!("2030-03-0{}T18:00:00Z", day);
+        created.push(create_session(&app, venue_id, &datetime, "C", 4).await);
+    }
+
+    let (_, sessions) = list(&app, "limit=2&offset=2").await;
+    assert_eq!(ids(&sessions), vec![created[2].as_str(), created[3].as_str()]);
+
+    let (_, sessions) = list(&app, "order=desc&limit=2").await;
+    assert_eq!(ids(&sessions), vec![created[4].as_str(), created[3].as_str()]);
+
+    let (_, sessions) = list(&app, "order=desc&limit=2&offset=4").await;
+    assert_eq!(ids(&sessions), vec![created[0].as_str()]);
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
+
+#[tokio::test]
+async fn open_slots_and_cancelled_sessions() {
+    let app = helpers::TestApp::new().await;
+    let venue_id = app.create_test_venue().await;
+    let full = create_session(&app, venue_id, "2030-03-04T18:00:00Z", "C", 2).await;
+    let open = create_session(&app, venue_id, "2030-03-05T18:00:00Z", "C", 2).await;
+    let cancelled = create_session(&app, venue_id, "2030-03-06T18:00:00Z", "C", 2).await;
+
+    app.register_test_users(&full, &["+351911111111", "+351922222222"]).await;
+    let (status, _) = app
+        .call(helpers::json_request(
+            Method::POST,
+            format!("/sessions/{}/cancel", cancelled),
+            json!({ "reason": "Rain" }),
+        ))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, sessions) = list(&app, "open_slots=true").await;
+    assert_eq!(ids(&sessions), vec![open.as_str()]);
+
+    let (_, sessions) = list(&app, "include_cancelled=true").await;
+    assert_eq!(
+        ids(&sessions),
+        vec![full.as_str(), open.as_str(), cancelled.as_str()]
+    );
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
+
+#[tokio::test]
+async fn rejects_bad_parameters() {
+    let app = helpers::TestApp::new().await;
+
+    for query in [
+        "skill_levels=C,Z",
+        "limit=0",
+        "limit=101",
+        "from=2030-03-05T00:00:00Z&to=2030-03-04T00:00:00Z",
+    ] {
+        let (status, error) = list(&app, query).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", query);
+        assert_eq!(error["code"], "validation_failed");
+    }
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
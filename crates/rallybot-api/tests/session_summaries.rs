@@ -0,0 +1,82 @@
+mod helpers;
+
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+
+async fn list(app: &helpers::TestApp, query: &str) -> Vec<Value> {
+    let (status, body) = app.call(helpers::get(format!("/sessions?{}", query))).await;
+    assert_eq!(status, StatusCode::OK);
+    serde_json::from_str(&body).unwrap()
+}
+
+#[tokio::test]
+async fn summary_includes_venue_and_sign_ups() {
+    let app = helpers::TestApp::new().await;
+    let venue_id = app
+        .create_test_venue_with_data(Some("Summary Club"), Some("1 Court Lane"))
+        .await;
+    let session = app
+        .create_test_session(json!({
+            "datetime": "2030-07-02T18:00:00Z",
+            "venue_id": venue_id,
+            "max_players": 2
+        }))
+        .await;
+    let session_id = helpers::id_of(&session);
+    app.register_test_users(&session_id, &["+351911111111", "+351922222222", "+351933333333"])
+        .await;
+
+    let summaries = list(&app, "summary=true").await;
+    assert_eq!(summaries.len(), 1);
+    let summary = &summaries[0];
+    assert_eq!(summary["id"], session_id);
+    assert_eq!(summary["local_datetime"], "2030-07-02T19:00:00+01:00");
+    assert_eq!(summary["venue_name"], "Summary Club");
+    assert_eq!(summary["venue_address"], "1 Court Lane");
+    assert_eq!(summary["confirmed_count"], 2);
+    assert_eq!(summary["substitute_count"], 1);
+    assert_eq!(summary["free_slots"], 0);
+    assert_eq!(summary["confirmed_first_names"].as_array().unwrap().len(), 2);
+
+    // Without the flag the listing is unchanged
+    let sessions = list(&app, "").await;
+    assert!(sessions[0].get("venue_name").is_none());
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
+
+#[tokio::test]
+async fn summary_applies_the_same_filters() {
+    let app = helpers::TestApp::new().await;
+    let venue_id = app.create_test_venue().await;
+    let mut ids = Vec::new();
+    for max_players in [2, 4] {
+        let session = app
+            .create_test_session(json!({
+                "datetime": "2030-07-02T18:00:00Z",
+                "venue_id": venue_id,
+                "max_players": max_players
+            }))
+            .await;
+        ids.push(helpers::id_of(&session));
+    }
+    app.register_test_users(&ids[0], &["+351911111111", "+351922222222"]).await;
+
+    let summaries = list(&app, "summary=true&open_slots=true").await;
+    assert_eq!(summaries.len(), 1);
+    assert_eq!(summaries[0]["id"], ids[1].as_str());
+    assert_eq!(summaries[0]["free_slots"], 4);
+    assert_eq!(summaries[0]["confirmed_first_names"], json!([]));
+
+    let summaries = list(&app, "summary=true&limit=1&offset=1").await;
+    assert_eq!(summaries.len(), 1);
+
+    let summaries = list(&app, "summary=true&week=0").await;
+    assert!(summaries.is_empty());
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
@@ -0,0 +1,291 @@
+mod helpers;
+
+use axum::{
+    body::Body,
+    http::{Method, Request, StatusCode},
+};
+use serde_json::{json, Value};
+
+async fn create_template(app: &helpers::TestApp, venue_id: uuid::Uuid) -> String {
+    let (status, body) = app
+        .call(helpers::json_request(
+            Method::POST,
+            "/session-templates",
+            json!({
+                "session_type": "S",
+                "weekday": "Tue",
+                "start_time": "19:00",
+                "duration_minutes": 90,
+                "venue_id": venue_id,
+                "skill_level": "C",
+                "max_players": 8
+            }),
+        ))
+        .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let template: Value = serde_json::from_str(&body).unwrap();
+    template["id"].as_str().unwrap().to_string()
+}
+
+fn generate(template_id: &str, weeks: u32) -> Request<Body> {
+    helpers::json_request(
+        Method::POST,
+        format!("/session-templates/{}/generate", template_id),
+        json!({ "weeks": weeks }),
+    )
+}
+
+#[tokio::test]
+async fn create_and_fetch_template() {
+    let app = helpers::TestApp::new().await;
+    let venue_id = app.create_test_venue().await;
+    let template_id = create_template(&app, venue_id).await;
+
+    let (status, body) = app.call(helpers::get(format!("/session-templates/{}", template_id))).await;
+    assert_eq!(status, StatusCode::OK);
+    let template: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(template["weekday"], "Tue");
+    assert_eq!(template["start_time"], "19:00:00");
+    assert_eq!(template["max_players"], 8);
+
+    let (_, body) = app.call(helpers::get("/session-templates")).await;
+    let templates: Vec<Value> = serde_json::from_str(&body).unwrap();
+    assert_eq!(templates.len(), 1);
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
+
+#[tokio::test]
+async fn create_template_validates_like_a_session() {
+    let app = helpers::TestApp::new().await;
+    let venue_id = app.create_test_venue().await;
+
+    let (status, body) = app
+        .call(helpers::json_request(
+            Method::POST,
+            "/session-templates",
+            json!({
+                "session_type": "X",
+                "weekday": "Sat",
+                "start_time": "10:00",
+                "duration_minutes": 90,
+                "venue_id": venue_id,
+                "skill_level": "C"
+            }),
+        ))
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let error: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["message"], "Mixed sessions cannot have a skill level");
+
+    let (status, body) = app
+        .call(helpers::json_request(
+            Method::POST,
+            "/session-templates",
+            json!({
+                "session_type": "S",
+                "weekday": "Sat",
+                "start_time": "10:00",
+                "duration_minutes": 90,
+                "venue_id": "00000000-0000-0000-0000-000000000000",
+                "skill_level": "C"
+            }),
+        ))
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let error: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["code"], "venue_not_found");
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
+
+#[tokio::test]
+async fn generate_is_idempotent() {
+    let app = helpers::TestApp::new().await;
+    let venue_id = app.create_test_venue().await;
+    let template_id = create_template(&app, venue_id).await;
+
+    let (status, body) = app.call(generate(&template_id, 4)).await;
+    assert_eq!(status, StatusCode::OK);
+    let created: Vec<Value> = serde_json::from_str(&body).unwrap();
+    assert_eq!(created.len(), 4);
+    assert!(created.iter().all(|s| s["template_id"] == template_id.as_str()));
+
+    let (status, body) = app.call(generate(&template_id, 4)).await;
+    assert_eq!(status, StatusCode::OK);
+    let created: Vec<Value> = serde_json::from_str(&body).unwrap();
+    assert!(created.is_empty());
+
+    let (_, body) = app.call(helpers::get("/sessions")).await;
+    let sessions: Vec<Value> = serde_json::from_str(&body).unwrap();
+    assert_eq!(sessions.len(), 4);
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
+
+#[tokio::test]
+async fn moved_sessions_are_not_generated_again() {
+    let app = helpers::TestApp::new().await;
+    let venue_id = app.create_test_venue().await;
+    let template_id = create_template(&app, venue_id).await;
+
+    let (_, body) = app.call(generate(&template_id, 2)).await;
+    let created: Vec<Value> = serde_json::from_str(&body).unwrap();
+    let start: chrono::DateTime<chrono::Utc> = created[0]["datetime"].as_str().unwrap().parse().unwrap();
+
+    // The organiser moves the first session back an hour
+    let (status, _) = app
+        .call(helpers::json_request(
+            Method::PATCH,
+            format!("/sessions/{}", helpers::id_of(&created[0])),
+            json!({ "datetime": start + chrono::Duration::hours(1) }),
+        ))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = app.call(generate(&template_id, 2)).await;
+    assert_eq!(status, StatusCode::OK);
+    let created: Vec<Value> = serde_json::from_str(&body).unwrap();
+    assert!(created.is_empty());
+
+    let (_, body) = app.call(helpers::get("/sessions")).await;
+    let sessions: Vec<Value> = serde_json::from_str(&body).unwrap();
+    assert_eq!(sessions.len(), 2);
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
+
+#[tokio::test]
+async fn exception_dates_are_skipped() {
+    let app = helpers::TestApp::new().await;
+    let venue_id = app.create_test_venue().await;
+    let template_id = create_template(&app, venue_id).await;
+
+    let (status, body) = app.call(generate(&template_id, 2)).await;
+    assert_eq!(status, StatusCode::OK);
+    let created: Vec<Value> = serde_json::from_str(&body).unwrap();
+    // The Tuesday of the third week, not generated yet
+    let last_date = created.last().unwrap()["datetime"].as_str().unwrap()[..10].to_string();
+    let next_date = (chrono::NaiveDate::parse_from_str(&last_date, "%Y-%m-%d").unwrap()
+        + chrono::Duration::weeks(1))
+    .to_string();
+
+    let (status, body) = app
+        .call(helpers::json_request(
+            Method::POST,
+            format!("/session-templates/{}/exceptions", template_id),
+            json!({ "date": next_date }),
+        ))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let template: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(template["exception_dates"], json!([next_date]));
+
+    let (_, body) = app.call(generate(&template_id, 3)).await;
+    let created: Vec<Value> = serde_json::from_str(&body).unwrap();
+    assert!(created.is_empty());
+
+    let (status, body) = app
+        .call(helpers::json_request(
+            Method::DELETE,
+            format!("/session-templates/{}/exceptions/{}", template_id, next_date),
+            json!({}),
+        ))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let template: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(template["exception_dates"], json!([]));
+
+    let (_, body) = app.call(generate(&template_id, 3)).await;
+    let created: Vec<Value> = serde_json::from_str(&body).unwrap();
+    assert_eq!(created.len(), 1);
+    assert!(created[0]["datetime"].as_str().unwrap().starts_with(&next_date));
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
+
+#[tokio::test]
+async fn generate_rejects_out_of_range_weeks_and_unknown_templates() {
+    let app = helpers::TestApp::new().await;
+    let venue_id = app.create_test_venue().await;
+    let template_id = create_template(&app, venue_id).await;
+
+    let (status, body) = app.call(generate(&template_id, 0)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let error: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["code"], "validation_failed");
+
+    let (status, _) = app.call(generate(&template_id, 27)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = app
+        .call(generate("00000000-0000-0000-0000-000000000000", 4))
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let error: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["code"], "template_not_found");
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
+
+#[tokio::test]
+async fn generate_all_covers_every_template() {
+    let app = helpers::TestApp::new().await;
+    let venue_id = app.create_test_venue().await;
+    create_template(&app, venue_id).await;
+    create_template(&app, venue_id).await;
+
+    let (status, body) = app
+        .call(helpers::json_request(
+            Method::POST,
+            "/session-templates/generate",
+            json!({ "weeks": 2 }),
+        ))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let created: Vec<Value> = serde_json::from_str(&body).unwrap();
+    assert_eq!(created.len(), 4);
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
+
+#[tokio::test]
+async fn deleting_a_template_keeps_generated_sessions() {
+    let app = helpers::TestApp::new().await;
+    let venue_id = app.create_test_venue().await;
+    let template_id = create_template(&app, venue_id).await;
+    app.call(generate(&template_id, 2)).await;
+
+    let delete = |uri: String| helpers::json_request(Method::DELETE, uri, json!({}));
+    let (status, _) = app
+        .call(delete(format!("/session-templates/{}", template_id)))
+        .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = app
+        .call(delete(format!("/session-templates/{}", template_id)))
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (_, body) = app.call(helpers::get("/sessions")).await;
+    let sessions: Vec<Value> = serde_json::from_str(&body).unwrap();
+    assert_eq!(sessions.len(), 2);
+    assert!(sessions.iter().all(|s| s["template_id"].is_null()));
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
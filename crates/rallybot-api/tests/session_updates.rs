@@ -0,0 +1,156 @@
+mod helpers;
+
+use axum::{
+    body::Body,
+    http::{Method, Request, StatusCode},
+};
+use rallybot_api::config::WhatsAppConfig;
+use rallybot_bot::RecordingSender;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+fn patch(session_id: &str, body: Value) -> Request<Body> {
+    helpers::json_request(Method::PATCH, format!("/sessions/{}", session_id), body)
+}
+
+#[tokio::test]
+async fn patch_changes_only_given_fields() {
+    let app = helpers::TestApp::new().await;
+    let venue_id = app.create_test_venue().await;
+    let session_id = helpers::id_of(&app.create_test_session(json!({ "venue_id": venue_id })).await);
+
+    let (status, body) = app
+        .call(patch(&session_id, json!({ "datetime": "2030-12-31T18:30:00Z" })))
+        .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let session: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(session["datetime"], "2030-12-31T18:30:00Z");
+    assert_eq!(session["duration_minutes"], 90);
+    assert_eq!(session["skill_level"], "C");
+    assert_eq!(session["venue_id"], venue_id.to_string());
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
+
+#[tokio::test]
+async fn patch_to_mixed_requires_clearing_the_level() {
+    let app = helpers::TestApp::new().await;
+    let session_id = helpers::id_of(&app.create_test_session(json!({})).await);
+
+    let (status, body) = app
+        .call(patch(&session_id, json!({ "session_type": "X" })))
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let error: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["message"], "Mixed sessions cannot have a skill level");
+
+    let (status, body) = app
+        .call(patch(
+            &session_id,
+            json!({ "session_type": "X", "skill_level": null }),
+        ))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let session: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(session["session_type"], "X");
+    assert!(session["skill_level"].is_null());
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
+
+#[tokio::test]
+async fn patch_rejects_invalid_duration_and_unknown_venue() {
+    let app = helpers::TestApp::new().await;
+    let session_id = helpers::id_of(&app.create_test_session(json!({})).await);
+
+    let (status, body) = app
+        .call(patch(&session_id, json!({ "duration_minutes": 150 })))
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let error: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["code"], "validation_failed");
+
+    let (status, body) = app
+        .call(patch(
+            &session_id,
+            json!({ "venue_id": "00000000-0000-0000-0000-000000000000" }),
+        ))
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let error: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["code"], "venue_not_found");
+
+    let (status, _) = app
+        .call(patch(
+            "00000000-0000-0000-0000-000000000000",
+            json!({ "duration_minutes": 60 }),
+        ))
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
+
+#[tokio::test]
+async fn patch_notifies_registered_players() {
+    let sender = Arc::new(RecordingSender::new());
+    let app = helpers::TestApp::with_whatsapp(WhatsAppConfig::default(), sender.clone()).await;
+    let session_id = helpers::id_of(&app.create_test_session(json!({})).await);
+
+    let phones = ["+351911111111", "+351922222222"];
+    app.register_test_users(&session_id, &phones).await;
+
+    let (status, _) = app
+        .call(patch(&session_id, json!({ "datetime": "2030-12-31T18:30:00Z" })))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    for phone in phones {
+        let sent = sender.wait_for(phone, 1).await;
+        assert_eq!(sent.len(), 1);
+        assert!(sent[0].body().contains("⏰ moved from 18:00 to 18:30"));
+    }
+}
+
+#[tokio::test]
+async fn patch_capacity_keeps_room_for_everyone_signed_up() {
+    let app = helpers::TestApp::new().await;
+    let session_id = helpers::id_of(&app.create_test_session(json!({ "max_players": 2 })).await);
+    app.register_test_users(&session_id, &["+351911111111", "+351922222222", "+351933333333"])
+        .await;
+
+    let (status, body) = app.call(patch(&session_id, json!({ "max_players": 1 }))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let error: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["message"], "Max players must be between 2 and 16");
+
+    let (status, body) = app.call(patch(&session_id, json!({ "max_substitutes": 0 }))).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let error: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["code"], "session_over_capacity");
+
+    // A third place goes to the substitute
+    let (status, body) = app
+        .call(patch(&session_id, json!({ "max_players": 3, "max_substitutes": 0 })))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let session: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(session["max_players"], 3);
+    assert_eq!(session["max_substitutes"], 0);
+
+    let (_, body) = app.call(helpers::get(format!("/sessions/{}", session_id))).await;
+    let details: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(details["confirmed_count"], 3);
+    assert_eq!(details["substitute_count"], 0);
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
@@ -58,9 +58,11 @@ async fn create_session_with_invalid_venue_returns_400() {
         .body(Body::from(serde_json::to_string(&body).unwrap()))
         .unwrap();
     
-    let (status, _body) = app.call(request).await;
+    let (status, body) = app.call(request).await;
     
     assert_eq!(status, StatusCode::BAD_REQUEST);
+    let error: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["code"], "venue_not_found");
 }
 
 #[tokio::test]
@@ -85,9 +87,12 @@ async fn create_session_with_invalid_duration_returns_400() {
         .body(Body::from(serde_json::to_string(&body).unwrap()))
         .unwrap();
     
-    let (status, _body) = app.call(request).await;
+    let (status, body) = app.call(request).await;
     
     assert_eq!(status, StatusCode::BAD_REQUEST);
+    let error: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["code"], "validation_failed");
+    assert_eq!(error["message"], "Duration must be between 60 and 120 minutes");
 }
 
 #[tokio::test]
@@ -192,4 +197,131 @@ async fn create_session_includes_skill_level_in_response() {
     
     let response: Value = serde_json::from_str(&body).unwrap();
     assert_eq!(response["skill_level"], "E");
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn create_session_with_capacity() {
+    let app = helpers::TestApp::new().await;
+    
+    let venue_id = app.create_test_venue().await;
+    
+    let body = json!({
+        "session_type": "C",
+        "datetime": "2024-12-31T18:00:00Z",
+        "duration_minutes": 90,
+        "venue_id": venue_id,
+        "skill_level": "B",
+        "max_players": 8,
+        "max_substitutes": 2
+    });
+    
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/sessions")
+        .header("content-type", "application/json")
+        .body(Body::from(serde_json::to_string(&body).unwrap()))
+        .unwrap();
+    
+    let (status, body) = app.call(request).await;
+    
+    assert_eq!(status, StatusCode::CREATED);
+    
+    let response: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(response["max_players"], 8);
+    assert_eq!(response["max_substitutes"], 2);
+    
+    // Details report the free places out of the configured capacity
+    let request = Request::builder()
+        .method(Method::GET)
+        .uri(format!("/sessions/{}", response["id"].as_str().unwrap()))
+        .body(Body::empty())
+        .unwrap();
+    
+    let (status, body) = app.call(request).await;
+    
+    assert_eq!(status, StatusCode::OK);
+    
+    let details: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(details["max_players"], 8);
+    assert_eq!(details["confirmed_count"], 0);
+    assert_eq!(details["available_spots"], 8);
+}
+
+#[tokio::test]
+async fn create_session_defaults_to_four_players() {
+    let app = helpers::TestApp::new().await;
+    
+    let venue_id = app.create_test_venue().await;
+    
+    let body = json!({
+        "session_type": "X",
+        "datetime": "2024-12-31T18:00:00Z",
+        "duration_minutes": 90,
+        "venue_id": venue_id
+    });
+    
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/sessions")
+        .header("content-type", "application/json")
+        .body(Body::from(serde_json::to_string(&body).unwrap()))
+        .unwrap();
+    
+    let (status, body) = app.call(request).await;
+    
+    assert_eq!(status, StatusCode::CREATED);
+    
+    let response: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(response["max_players"], 4);
+    assert!(response["max_substitutes"].is_null());
+}
+
+#[tokio::test]
+async fn create_session_with_invalid_capacity_returns_400() {
+    let app = helpers::TestApp::new().await;
+    
+    let venue_id = app.create_test_venue().await;
+    
+    let body = json!({
+        "session_type": "S",
+        "datetime": "2024-12-31T18:00:00Z",
+        "duration_minutes": 90,
+        "venue_id": venue_id,
+        "skill_level": "C",
+        "max_players": 1
+    });
+    
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/sessions")
+        .header("content-type", "application/json")
+        .body(Body::from(serde_json::to_string(&body).unwrap()))
+        .unwrap();
+    
+    let (status, body) = app.call(request).await;
+    
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let error: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["code"], "validation_failed");
+    assert_eq!(error["message"], "Max players must be between 2 and 16");
+}
+
+#[tokio::test]
+async fn malformed_body_returns_json_error() {
+    let app = helpers::TestApp::new().await;
+    
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/sessions")
+        .header("content-type", "application/json")
+        .body(Body::from(r#"{"session_type": "S""#))
+        .unwrap();
+    
+    let (status, body) = app.call(request).await;
+    
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let error: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["code"], "invalid_body");
+    assert!(error["message"].is_string());
+    assert!(error["details"].is_null());
+}
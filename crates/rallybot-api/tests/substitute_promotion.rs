@@ -0,0 +1,140 @@
+mod helpers;
+
+use axum::http::{Method, StatusCode};
+use chrono::{Duration, Utc};
+use rallybot_api::{config::WhatsAppConfig, create_app_with_whatsapp, jobs};
+use rallybot_bot::{OutboundMessage, RecordingSender};
+use rallybot_core::{InMemoryStorage, PostgresStorage, Repository, SessionRepository, Storage};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use uuid::Uuid;
+
+const PHONES: [&str; 4] = ["+351911111111", "+351922222222", "+351933333333", "+351944444444"];
+
+/// A full two-player session with two substitutes, signed up in `PHONES` order
+async fn full_session(app: &helpers::TestApp) -> (String, Vec<Uuid>) {
+    let session = app
+        .create_test_session(json!({
+            "session_type": "C",
+            "datetime": (Utc::now() + Duration::days(2)).to_rfc3339(),
+            "max_players": 2
+        }))
+        .await;
+    let session_id = helpers::id_of(&session);
+    let user_ids = app.register_test_users(&session_id, &PHONES).await;
+    (session_id, user_ids)
+}
+
+async fn unregister(app: &helpers::TestApp, session_id: &str, user_id: Uuid) {
+    let (status, _) = app
+        .call(helpers::json_request(
+            Method::DELETE,
+            format!("/sessions/{}/unregister", session_id),
+            json!({ "user_id": user_id }),
+        ))
+        .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+}
+
+#[tokio::test]
+async fn promoted_substitute_is_told_right_away() {
+    let sender = Arc::new(RecordingSender::new());
+    let app = helpers::TestApp::with_whatsapp(WhatsAppConfig::default(), sender.clone()).await;
+    let (session_id, user_ids) = full_session(&app).await;
+
+    unregister(&app, &session_id, user_ids[1]).await;
+
+    let sent = sender.wait_for(PHONES[2], 1).await;
+    assert_eq!(sent.len(), 1);
+    assert!(sent[0].body().contains("A spot opened up"));
+    assert!(!sent[0].body().contains("Please confirm"));
+    assert!(sender.sent_to(PHONES[3]).is_empty());
+
+    // Nothing to confirm without a confirmation window
+    let (status, body) = app
+        .call(helpers::json_request(
+            Method::POST,
+            format!("/sessions/{}/confirm", session_id),
+            json!({ "phone_number": PHONES[2] }),
+        ))
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let error: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["code"], "no_pending_confirmation");
+}
+
+#[tokio::test]
+async fn unconfirmed_spot_passes_to_the_next_substitute() {
+    match helpers::StorageType::from_env() {
+        helpers::StorageType::InMemory => {
+            expire_unconfirmed_spot(Arc::new(InMemoryStorage::new()), None).await
+        }
+        helpers::StorageType::Postgres => {
+            let test_db = helpers::TestDatabase::new().await;
+            let storage = PostgresStorage::new_with_pool(test_db.get_pool().await);
+            expire_unconfirmed_spot(Arc::new(storage), Some(test_db)).await
+        }
+    }
+}
+
+async fn expire_unconfirmed_spot<S: Storage + 'static>(
+    storage: Arc<S>,
+    test_db: Option<helpers::TestDatabase>,
+) {
+    let sender = Arc::new(RecordingSender::new());
+    let repository = Arc::new(
+        Repository::new(storage.clone()).with_confirmation_window(Some(Duration::hours(2))),
+    );
+    let app = helpers::TestApp {
+        app: create_app_with_whatsapp(
+            repository.clone(),
+            WhatsAppConfig::default(),
+            helpers::admin_config(),
+            sender.clone(),
+        ),
+        storage: storage.clone() as Arc<dyn Storage>,
+        test_db,
+    };
+    let (session_id, user_ids) = full_session(&app).await;
+
+    unregister(&app, &session_id, user_ids[0]).await;
+    let offer = &sender.wait_for(PHONES[2], 1).await[0];
+    assert!(offer.body().contains("Please confirm by"));
+    assert!(matches!(offer, OutboundMessage::Buttons { .. }));
+
+    // The job leaves the offer alone until the deadline
+    let sessions = repository.as_ref() as &dyn SessionRepository;
+    let now = Utc::now();
+    assert_eq!(jobs::expire_lapsed_promotions(sessions, now).await.unwrap(), 0);
+    let deadline = now + Duration::hours(2) + Duration::minutes(1);
+    assert_eq!(jobs::expire_lapsed_promotions(sessions, deadline).await.unwrap(), 1);
+
+    let lapsed = sender.wait_for(PHONES[2], 2).await;
+    assert_eq!(lapsed.len(), 2);
+    assert!(lapsed[1].body().contains("didn't confirm your spot in time"));
+    assert!(sender.wait_for(PHONES[3], 1).await[0].body().contains("Please confirm by"));
+
+    // The next substitute keeps the spot by confirming
+    let (status, body) = app
+        .call(helpers::json_request(
+            Method::POST,
+            format!("/sessions/{}/confirm", session_id),
+            json!({ "phone_number": PHONES[3] }),
+        ))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let registration: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(registration["status"], "confirmed");
+    assert_eq!(registration["confirm_by"], Value::Null);
+
+    let registrations = storage
+        .get_registrations(session_id.parse().unwrap())
+        .await
+        .unwrap();
+    let players: Vec<Uuid> = registrations.iter().map(|r| r.user_id).collect();
+    assert_eq!(players, vec![user_ids[1], user_ids[3]]);
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
@@ -0,0 +1,103 @@
+mod helpers;
+
+use axum::http::{Method, StatusCode};
+use chrono::{Duration, Utc};
+use rallybot_core::{calendar, Venue};
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn venue_timezone_defaults_and_validates() {
+    let app = helpers::TestApp::new().await;
+
+    let (status, body) = app
+        .call(helpers::json_request(Method::POST, "/venues", json!({ "name": "Lisbon Club", "address": "Lisbon" })))
+        .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let venue: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(venue["timezone"], "Europe/Lisbon");
+
+    let (status, body) = app
+        .call(helpers::json_request(
+            Method::POST,
+            "/venues",
+            json!({ "name": "Madrid Club", "address": "Madrid", "timezone": "Europe/Madrid" }),
+        ))
+        .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let venue: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(venue["timezone"], "Europe/Madrid");
+
+    let (status, body) = app
+        .call(helpers::json_request(
+            Method::POST,
+            "/venues",
+            json!({ "name": "Nowhere", "address": "Nowhere", "timezone": "Europe/Atlantis" }),
+        ))
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let error: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["code"], "validation_failed");
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
+
+#[tokio::test]
+async fn sessions_carry_local_time_across_dst() {
+    let app = helpers::TestApp::new().await;
+    let (_, body) = app
+        .call(helpers::json_request(Method::POST, "/venues", json!({ "name": "Lisbon Club", "address": "Lisbon" })))
+        .await;
+    let venue: Value = serde_json::from_str(&body).unwrap();
+    let venue_id = venue["id"].as_str().unwrap();
+
+    // Lisbon is on UTC+1 in summer and UTC in winter
+    let summer = app.create_test_session(json!({ "venue_id": venue_id, "datetime": "2030-07-02T18:00:00Z" })).await;
+    assert_eq!(summer["local_datetime"], "2030-07-02T19:00:00+01:00");
+    assert_eq!(summer["timezone"], "Europe/Lisbon");
+
+    let winter = app.create_test_session(json!({ "venue_id": venue_id, "datetime": "2030-12-31T18:00:00Z" })).await;
+    assert_eq!(winter["local_datetime"], "2030-12-31T18:00:00Z");
+
+    let (_, body) = app
+        .call(helpers::get(format!("/sessions/{}", helpers::id_of(&summer))))
+        .await;
+    let details: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(details["local_datetime"], "2030-07-02T19:00:00+01:00");
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
+
+#[tokio::test]
+async fn week_filter_uses_the_local_week() {
+    let app = helpers::TestApp::new().await;
+
+    // First and last minute of next week, on Lisbon's clock
+    let next_week = calendar::local_week(Venue::DEFAULT_TIMEZONE, Utc::now(), 1);
+    app.create_test_session(json!({ "datetime": next_week.start.to_rfc3339() })).await;
+    app.create_test_session(json!({ "datetime": (next_week.end - Duration::minutes(1)).to_rfc3339() })).await;
+    // Just after it ends
+    app.create_test_session(json!({ "datetime": next_week.end.to_rfc3339() })).await;
+
+    let list = |week: u32| helpers::get(format!("/sessions?week={}", week));
+
+    let (status, body) = app.call(list(1)).await;
+    assert_eq!(status, StatusCode::OK);
+    let sessions: Vec<Value> = serde_json::from_str(&body).unwrap();
+    assert_eq!(sessions.len(), 2);
+
+    let (_, body) = app.call(list(2)).await;
+    let sessions: Vec<Value> = serde_json::from_str(&body).unwrap();
+    assert_eq!(sessions.len(), 1);
+
+    let (_, body) = app.call(list(0)).await;
+    let sessions: Vec<Value> = serde_json::from_str(&body).unwrap();
+    assert!(sessions.is_empty());
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
@@ -0,0 +1,127 @@
+mod helpers;
+
+use axum::http::{Method, StatusCode};
+use rallybot_api::config::WhatsAppConfig;
+use rallybot_bot::RecordingSender;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use uuid::Uuid;
+
+async fn change_status(
+    app: &helpers::TestApp,
+    user_id: Uuid,
+    action: &str,
+    reason: Option<&str>,
+) -> (StatusCode, Value) {
+    let (status, body) = app
+        .call(helpers::json_request(
+            Method::POST,
+            format!("/admin/users/{}/{}", user_id, action),
+            json!({ "by": "admin@rally.pt", "reason": reason }),
+        ))
+        .await;
+    (status, serde_json::from_str(&body).unwrap())
+}
+
+#[tokio::test]
+async fn approving_an_applicant_welcomes_them() {
+    let sender = Arc::new(RecordingSender::new());
+    let app = helpers::TestApp::with_whatsapp(WhatsAppConfig::default(), sender.clone()).await;
+    let applicant = app.create_test_user("+351911111111", false).await;
+    app.create_test_user("+351922222222", true).await;
+
+    let (status, body) = app.call(helpers::get("/admin/users/pending")).await;
+    assert_eq!(status, StatusCode::OK);
+    let pending: Vec<Value> = serde_json::from_str(&body).unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0]["id"], applicant.to_string());
+    assert_eq!(pending[0]["status"], "pending");
+    assert_eq!(pending[0]["is_approved"], false);
+
+    let (status, user) = change_status(&app, applicant, "approve", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(user["status"], "approved");
+    assert_eq!(user["is_approved"], true);
+
+    let sent = sender.wait_for("+351911111111", 1).await;
+    assert_eq!(sent.len(), 1);
+    assert!(sent[0].body().contains("Welcome to Rally"));
+
+    let (_, body) = app.call(helpers::get("/admin/users/pending")).await;
+    let pending: Vec<Value> = serde_json::from_str(&body).unwrap();
+    assert!(pending.is_empty());
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
+
+#[tokio::test]
+async fn status_changes_are_audited() {
+    let app = helpers::TestApp::new().await;
+    let user_id = app.create_test_user("+351911111111", false).await;
+
+    let (status, _) = change_status(&app, user_id, "approve", None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, user) = change_status(&app, user_id, "suspend", Some("Repeated no-shows")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(user["status"], "suspended");
+    assert_eq!(user["is_approved"], false);
+
+    let (status, body) = app
+        .call(helpers::get(format!("/admin/users/{}/history", user_id)))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let history: Vec<Value> = serde_json::from_str(&body).unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0]["from_status"], "pending");
+    assert_eq!(history[0]["to_status"], "approved");
+    assert_eq!(history[1]["to_status"], "suspended");
+    assert_eq!(history[1]["changed_by"], "admin@rally.pt");
+    assert_eq!(history[1]["reason"], "Repeated no-shows");
+
+    // Suspended members can't sign up
+    let session = app.create_test_session(json!({ "datetime": "2030-07-02T18:00:00Z" })).await;
+    let (status, _) = app
+        .call(helpers::json_request(
+            Method::POST,
+            format!("/sessions/{}/register", helpers::id_of(&session)),
+            json!({ "phone_number": "+351911111111" }),
+        ))
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
+
+#[tokio::test]
+async fn invalid_transitions_and_requests_are_refused() {
+    let app = helpers::TestApp::new().await;
+    let user_id = app.create_test_user("+351911111111", false).await;
+
+    let (status, error) = change_status(&app, user_id, "suspend", None).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(error["code"], "invalid_status_transition");
+    assert_eq!(error["details"]["from"], "pending");
+
+    let (status, error) = change_status(&app, Uuid::new_v4(), "approve", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(error["code"], "user_not_found");
+
+    let (status, body) = app
+        .call(helpers::json_request(
+            Method::POST,
+            format!("/admin/users/{}/reject", user_id),
+            json!({ "by": " " }),
+        ))
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let error: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["code"], "validation_failed");
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
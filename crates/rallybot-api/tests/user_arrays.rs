@@ -1,6 +1,6 @@
 mod helpers;
 
-use rallybot_core::{Gender, LookingFor, PlayFrequency, PreferredSide, SkillLevel, User};
+use rallybot_core::{Gender, LookingFor, PlayFrequency, PreferredSide, SkillLevel, User, UserStatus};
 use uuid::Uuid;
 
 #[tokio::test]
@@ -25,15 +25,15 @@ async fn test_user_with_multiple_skill_levels_and_looking_for() {
         preferred_side: PreferredSide::Right,
         play_frequency: PlayFrequency::SeveralTimesWeek,
         looking_for: vec![LookingFor::SocialConnections, LookingFor::BusinessOpportunities], // Both options
-        is_approved: true,
+        status: UserStatus::Approved,
         created_at: chrono::Utc::now(),
     };
     
     // Save the user
-    app.storage.create_user(user.clone()).await;
+    app.storage.create_user(user.clone()).await.unwrap();
     
     // Retrieve the user
-    let retrieved = app.storage.get_user(user.id).await.unwrap();
+    let retrieved = app.storage.get_user(user.id).await.unwrap().unwrap();
     
     // Verify arrays are preserved
     assert_eq!(retrieved.skill_levels.len(), 2);
@@ -73,13 +73,13 @@ async fn test_user_with_empty_arrays_fails() {
         preferred_side: PreferredSide::Left,
         play_frequency: PlayFrequency::OnceWeek,
         looking_for: vec![], // Empty array
-        is_approved: true,
+        status: UserStatus::Approved,
         created_at: chrono::Utc::now(),
     };
     
     // This might fail depending on database constraints
     // PostgreSQL allows empty arrays by default, but we might want to add CHECK constraints
-    app.storage.create_user(user).await;
+    app.storage.create_user(user).await.unwrap();
     
     // Cleanup for postgres
     if let Some(test_db) = app.test_db {
@@ -0,0 +1,94 @@
+mod helpers;
+
+use axum::{
+    body::Body,
+    http::{Method, Request, StatusCode},
+};
+use serde_json::{json, Value};
+
+fn create_user_request(phone: &str, email: &str) -> Request<Body> {
+    let body = json!({
+        "first_name": "Ana",
+        "last_name": "Silva",
+        "phone_number": phone,
+        "email": email,
+        "city": "Lisbon",
+        "photo_url": null,
+        "occupation": "Engineer",
+        "company": "Rally",
+        "industry": "Sports",
+        "linkedin_url": "https://linkedin.com/in/ana",
+        "gender": "female",
+        "skill_levels": ["C"],
+        "preferred_side": "flexible",
+        "play_frequency": "once_week",
+        "looking_for": ["social_connections"]
+    });
+
+    Request::builder()
+        .method(Method::POST)
+        .uri("/users")
+        .header("content-type", "application/json")
+        .body(Body::from(serde_json::to_string(&body).unwrap()))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn create_user_returns_201() {
+    let app = helpers::TestApp::new().await;
+
+    let (status, body) = app
+        .call(create_user_request("+351910000001", "ana@example.com"))
+        .await;
+
+    assert_eq!(status, StatusCode::CREATED);
+    let response: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(response["phone_number"], "+351910000001");
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
+
+#[tokio::test]
+async fn duplicate_phone_returns_409() {
+    let app = helpers::TestApp::new().await;
+
+    let (status, _) = app
+        .call(create_user_request("+351910000002", "first@example.com"))
+        .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, body) = app
+        .call(create_user_request("+351910000002", "second@example.com"))
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let error: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["code"], "phone_taken");
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
+
+#[tokio::test]
+async fn duplicate_email_returns_409() {
+    let app = helpers::TestApp::new().await;
+
+    let (status, _) = app
+        .call(create_user_request("+351910000003", "same@example.com"))
+        .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    // Only the unique key on email catches this one
+    let (status, body) = app
+        .call(create_user_request("+351910000004", "same@example.com"))
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let error: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["code"], "email_taken");
+
+    if let Some(test_db) = app.test_db {
+        test_db.cleanup().await;
+    }
+}
@@ -0,0 +1,253 @@
+mod helpers;
+
+use axum::{
+    body::Body,
+    http::{Method, Request, StatusCode},
+};
+use rallybot_api::{config::WhatsAppConfig, signature};
+use rallybot_bot::{sender::OutboundMessage, RecordingSender};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+const APP_SECRET: &str = "b3f4c1d2e5a6978812";
+
+fn whatsapp_config() -> WhatsAppConfig {
+    WhatsAppConfig {
+        verify_token: "let-me-in".to_string(),
+        app_secret: APP_SECRET.to_string(),
+        ..WhatsAppConfig::default()
+    }
+}
+
+fn text_message_payload(from: &str, name: &str, text: &str) -> Value {
+    json!({
+        "object": "whatsapp_business_account",
+        "entry": [{
+            "id": "102290129340398",
+            "changes": [{
+                "field": "messages",
+                "value": {
+                    "messaging_product": "whatsapp",
+                    "metadata": {
+                        "display_phone_number": "15550783881",
+                        "phone_number_id": "106540352242922"
+                    },
+                    "contacts": [{
+                        "profile": { "name": name },
+                        "wa_id": from
+                    }],
+                    "messages": [{
+                        "from": from,
+                        "id": "wamid.HBgLMTY1MDM4Nzk0MzkVAgASGBQzQTRBNjU5OUFFRTAzODEwMTQ0RgA=",
+                        "timestamp": "1749416383",
+                        "type": "text",
+                        "text": { "body": text }
+                    }]
+                }
+            }]
+        }]
+    })
+}
+
+fn post_webhook(payload: &Value) -> Request<Body> {
+    let body = payload.to_string();
+    Request::builder()
+        .method(Method::POST)
+        .uri("/webhooks/whatsapp")
+        .header("content-type", "application/json")
+        .header("x-hub-signature-256", signature::sign(APP_SECRET, body.as_bytes()))
+        .body(Body::from(body))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn verify_handshake_echoes_challenge() {
+    let sender = Arc::new(RecordingSender::new());
+    let app = helpers::TestApp::with_whatsapp(whatsapp_config(), sender).await;
+
+    let request = Request::builder()
+        .uri("/webhooks/whatsapp?hub.mode=subscribe&hub.verify_token=let-me-in&hub.challenge=1158201444")
+        .body(Body::empty())
+        .unwrap();
+
+    let (status, body) = app.call(request).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "1158201444");
+}
+
+#[tokio::test]
+async fn verify_handshake_rejects_wrong_token() {
+    let sender = Arc::new(RecordingSender::new());
+    let app = helpers::TestApp::with_whatsapp(whatsapp_config(), sender).await;
+
+    let request = Request::builder()
+        .uri("/webhooks/whatsapp?hub.mode=subscribe&hub.verify_token=guess&hub.challenge=1158201444")
+        .body(Body::empty())
+        .unwrap();
+
+    let (status, _body) = app.call(request).await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn inbound_message_from_unregistered_user_gets_reply() {
+    let sender = Arc::new(RecordingSender::new());
+    let app = helpers::TestApp::with_whatsapp(whatsapp_config(), sender.clone()).await;
+
+    let (status, _body) = app
+        .call(post_webhook(&text_message_payload("351900000000", "Stranger", "hi")))
+        .await;
+
+    assert_eq!(status, StatusCode::OK);
+
+    let sent = sender.sent();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].0, "+351900000000");
+    assert!(sent[0].1.body().starts_with("Hi there! It looks like you're not registered"));
+}
+
+#[tokio::test]
+async fn inbound_greeting_uses_profile_name() {
+    let sender = Arc::new(RecordingSender::new());
+    let app = helpers::TestApp::with_whatsapp(whatsapp_config(), sender.clone()).await;
+    app.create_test_user("+351912345678", true).await;
+
+    let (status, _body) = app
+        .call(post_webhook(&text_message_payload("351912345678", "Rita", "🎾")))
+        .await;
+
+    assert_eq!(status, StatusCode::OK);
+
+    let sent = sender.sent();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].0, "+351912345678");
+    assert!(sent[0].1.body().starts_with("👋 Hey Rita!"));
+    assert!(matches!(sent[0].1, OutboundMessage::List { .. }));
+}
+
+#[tokio::test]
+async fn list_reply_is_handled_as_command() {
+    let sender = Arc::new(RecordingSender::new());
+    let app = helpers::TestApp::with_whatsapp(whatsapp_config(), sender.clone()).await;
+    app.create_test_user("+351912345678", true).await;
+
+    let payload = json!({
+        "object": "whatsapp_business_account",
+        "entry": [{
+            "id": "102290129340398",
+            "changes": [{
+                "field": "messages",
+                "value": {
+                    "messaging_product": "whatsapp",
+                    "contacts": [{ "profile": { "name": "Rita" }, "wa_id": "351912345678" }],
+                    "messages": [{
+                        "from": "351912345678",
+                        "id": "wamid.list",
+                        "timestamp": "1749416383",
+                        "type": "interactive",
+                        "interactive": {
+                            "type": "list_reply",
+                            "list_reply": { "id": "C", "title": "Coaching Classes" }
+                        }
+                    }]
+                }
+            }]
+        }]
+    });
+
+    let (status, _body) = app.call(post_webhook(&payload)).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        sender.sent_to("+351912345678"),
+        vec![OutboundMessage::text("Sorry, there are no Coaching Classes available this week...")]
+    );
+}
+
+#[tokio::test]
+async fn status_updates_are_acknowledged_without_reply() {
+    let sender = Arc::new(RecordingSender::new());
+    let app = helpers::TestApp::with_whatsapp(whatsapp_config(), sender.clone()).await;
+
+    let payload = json!({
+        "object": "whatsapp_business_account",
+        "entry": [{
+            "id": "102290129340398",
+            "changes": [{
+                "field": "messages",
+                "value": {
+                    "messaging_product": "whatsapp",
+                    "statuses": [{ "id": "wamid.x", "status": "delivered" }]
+                }
+            }]
+        }]
+    });
+
+    let (status, _body) = app.call(post_webhook(&payload)).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(sender.sent().is_empty());
+}
+
+#[tokio::test]
+async fn unsigned_delivery_is_rejected() {
+    let sender = Arc::new(RecordingSender::new());
+    let app = helpers::TestApp::with_whatsapp(whatsapp_config(), sender.clone()).await;
+    app.create_test_user("+351912345678", true).await;
+
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/webhooks/whatsapp")
+        .header("content-type", "application/json")
+        .body(Body::from(text_message_payload("351912345678", "Rita", "hi").to_string()))
+        .unwrap();
+
+    let (status, _body) = app.call(request).await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert!(sender.sent().is_empty());
+}
+
+#[tokio::test]
+async fn delivery_signed_with_wrong_secret_is_rejected() {
+    let sender = Arc::new(RecordingSender::new());
+    let app = helpers::TestApp::with_whatsapp(whatsapp_config(), sender.clone()).await;
+    app.create_test_user("+351912345678", true).await;
+
+    let body = text_message_payload("351912345678", "Rita", "hi").to_string();
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/webhooks/whatsapp")
+        .header("content-type", "application/json")
+        .header("x-hub-signature-256", signature::sign("not-the-secret", body.as_bytes()))
+        .body(Body::from(body))
+        .unwrap();
+
+    let (status, _body) = app.call(request).await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert!(sender.sent().is_empty());
+}
+
+#[tokio::test]
+async fn tampered_delivery_is_rejected() {
+    let sender = Arc::new(RecordingSender::new());
+    let app = helpers::TestApp::with_whatsapp(whatsapp_config(), sender.clone()).await;
+
+    let signed = text_message_payload("351912345678", "Rita", "hi").to_string();
+    let tampered = text_message_payload("351912345678", "Rita", "1").to_string();
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/webhooks/whatsapp")
+        .header("content-type", "application/json")
+        .header("x-hub-signature-256", signature::sign(APP_SECRET, signed.as_bytes()))
+        .body(Body::from(tampered))
+        .unwrap();
+
+    let (status, _body) = app.call(request).await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert!(sender.sent().is_empty());
+}
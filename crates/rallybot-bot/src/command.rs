@@ -0,0 +1,97 @@
+use rallybot_core::SessionType;
+
+const KEYCAP_SUFFIX: &str = "\u{FE0F}\u{20E3}";
+
+/// A parsed inbound message
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Command {
+    /// "hey", "hi", "hello" or 🎾
+    Menu,
+    /// C, S, L or X
+    List(SessionType),
+    /// 0 - show the sessions the user is registered to
+    MySessions,
+    /// A number picked from the last session list
+    Select(usize),
+    /// "confirm" - keep a spot the player was promoted into
+    Confirm,
+    /// "check in" - the player has arrived at a session about to start
+    CheckIn,
+    Unknown,
+}
+
+impl Command {
+    pub fn parse(text: &str) -> Self {
+        let text = text.trim();
+        let lowered = text.to_lowercase();
+
+        match lowered.as_str() {
+            "hey" | "hi" | "hello" | "🎾" => return Command::Menu,
+            "c" => return Command::List(SessionType::Coaching),
+            "s" => return Command::List(SessionType::Social),
+            "l" => return Command::List(SessionType::League),
+            "x" => return Command::List(SessionType::Mixed),
+            "confirm" => return Command::Confirm,
+            "check in" | "checkin" | "check-in" => return Command::CheckIn,
+            _ => {}
+        }
+
+        // Accept both "2" and the keycap emoji "2️⃣"
+        let digits = text.strip_suffix(KEYCAP_SUFFIX).unwrap_or(text);
+        match digits.parse::<usize>() {
+            Ok(0) => Command::MySessions,
+            Ok(n) => Command::Select(n),
+            Err(_) => Command::Unknown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_greetings() {
+        assert_eq!(Command::parse("hey"), Command::Menu);
+        assert_eq!(Command::parse(" Hi "), Command::Menu);
+        assert_eq!(Command::parse("HELLO"), Command::Menu);
+        assert_eq!(Command::parse("🎾"), Command::Menu);
+    }
+
+    #[test]
+    fn parse_session_types() {
+        assert_eq!(Command::parse("C"), Command::List(SessionType::Coaching));
+        assert_eq!(Command::parse("s"), Command::List(SessionType::Social));
+        assert_eq!(Command::parse("L"), Command::List(SessionType::League));
+        assert_eq!(Command::parse("x"), Command::List(SessionType::Mixed));
+    }
+
+    #[test]
+    fn parse_confirm() {
+        assert_eq!(Command::parse("confirm"), Command::Confirm);
+        assert_eq!(Command::parse(" Confirm "), Command::Confirm);
+    }
+
+    #[test]
+    fn parse_check_in() {
+        assert_eq!(Command::parse("check in"), Command::CheckIn);
+        assert_eq!(Command::parse("Check-in"), Command::CheckIn);
+        assert_eq!(Command::parse("CHECKIN"), Command::CheckIn);
+    }
+
+    #[test]
+    fn parse_numbers() {
+        assert_eq!(Command::parse("0"), Command::MySessions);
+        assert_eq!(Command::parse("0️⃣"), Command::MySessions);
+        assert_eq!(Command::parse("2"), Command::Select(2));
+        assert_eq!(Command::parse("3️⃣"), Command::Select(3));
+        assert_eq!(Command::parse("12"), Command::Select(12));
+    }
+
+    #[test]
+    fn parse_unknown() {
+        assert_eq!(Command::parse("book me in"), Command::Unknown);
+        assert_eq!(Command::parse(""), Command::Unknown);
+        assert_eq!(Command::parse("-1"), Command::Unknown);
+    }
+}
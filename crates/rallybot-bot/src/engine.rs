@@ -0,0 +1,805 @@
+use crate::{
+    command::Command,
+    inbound::InboundMessage,
+    messages::{self, session_type_name},
+    render::{self, SessionCard},
+    sender::{self, ListRow, ListSection, OutboundMessage},
+};
+use chrono::{Duration, Utc};
+use rallybot_core::{
+    AttendanceError, AttendanceRepository, ConversationMenu, ConversationRepository,
+    ConversationState, RegistrationError, RegistrationStatus, Repository, Session, SessionQuery,
+    SessionRepository, SessionType, Storage, StorageResult, User, UserRepository, VenueRepository,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// How long a shown menu stays valid for a numbered reply
+const CONVERSATION_TTL_HOURS: i64 = 24;
+
+/// Conversation engine: turns an inbound (phone, text) pair into the reply
+pub struct Bot {
+    users: Arc<dyn UserRepository>,
+    sessions: Arc<dyn SessionRepository>,
+    venues: Arc<dyn VenueRepository>,
+    conversations: Arc<dyn ConversationRepository>,
+    attendance: Arc<dyn AttendanceRepository>,
+    /// The membership application form shown to people who aren't members
+    application_url: Option<String>,
+}
+
+impl Bot {
+    pub fn new(
+        users: Arc<dyn UserRepository>,
+        sessions: Arc<dyn SessionRepository>,
+        venues: Arc<dyn VenueRepository>,
+        conversations: Arc<dyn ConversationRepository>,
+        attendance: Arc<dyn AttendanceRepository>,
+    ) -> Self {
+        Self {
+            users,
+            sessions,
+            venues,
+            conversations,
+            attendance,
+            application_url: None,
+        }
+    }
+
+    pub fn with_repository<S: Storage + 'static>(repository: Arc<Repository<S>>) -> Self {
+        Self::new(
+            repository.clone() as Arc<dyn UserRepository>,
+            repository.clone() as Arc<dyn SessionRepository>,
+            repository.clone() as Arc<dyn VenueRepository>,
+            repository.clone() as Arc<dyn ConversationRepository>,
+            repository as Arc<dyn AttendanceRepository>,
+        )
+    }
+
+    pub fn with_application_url(mut self, application_url: Option<String>) -> Self {
+        self.application_url = application_url;
+        self
+    }
+
+    /// Text of the reply to a plain (phone, text) message
+    pub async fn handle_message(&self, phone: &str, text: &str) -> String {
+        self.handle_inbound(&InboundMessage::new(phone, None, text.to_string()))
+            .await
+            .body()
+            .to_string()
+    }
+
+    /// Reply to an inbound message, greeting the player by their WhatsApp profile
+    /// name when there is one
+    pub async fn handle_inbound(&self, message: &InboundMessage) -> OutboundMessage {
+        match self.reply(message).await {
+            Ok(reply) => reply,
+            Err(e) => {
+                tracing::error!("storage error while replying to {}: {}", message.phone, e);
+                OutboundMessage::text(messages::TRY_AGAIN)
+            }
+        }
+    }
+
+    async fn reply(&self, message: &InboundMessage) -> StorageResult<OutboundMessage> {
+        let Some(user) = self.users.get_by_phone(&message.phone).await? else {
+            return Ok(OutboundMessage::text(messages::unregistered(
+                self.application_url.as_deref(),
+            )));
+        };
+        let name = message
+            .profile_name
+            .as_deref()
+            .filter(|n| !n.trim().is_empty())
+            .unwrap_or(&user.first_name);
+
+        Ok(match Command::parse(&message.text) {
+            Command::Menu => {
+                self.remember(&user, ConversationMenu::MainMenu, None, Vec::new())
+                    .await?;
+                messages::menu_list(name)
+            }
+            Command::List(session_type) => self.list_sessions(&user, session_type).await?,
+            Command::MySessions => self.my_sessions(&user).await?,
+            Command::Select(n) => OutboundMessage::text(self.join_session(&user, name, n).await?),
+            Command::Confirm => OutboundMessage::text(self.confirm_spots(&user).await?),
+            Command::CheckIn => OutboundMessage::text(self.check_in(&user).await?),
+            Command::Unknown => messages::unknown_command(),
+        })
+    }
+
+    async fn list_sessions(
+        &self,
+        user: &User,
+        session_type: SessionType,
+    ) -> StorageResult<OutboundMessage> {
+        // What's still to come in each venue's current week
+        let now = Utc::now();
+        let query = SessionQuery {
+            from: Some(now),
+            ..SessionQuery::of_type(session_type)
+        };
+        let sessions = self
+            .sessions
+            .list_eligible_in_week(&query, user, now, 0)
+            .await?;
+
+        self.remember(
+            user,
+            ConversationMenu::SessionList,
+            Some(session_type),
+            sessions.iter().map(|s| s.id).collect(),
+        )
+        .await?;
+
+        if sessions.is_empty() {
+            return Ok(OutboundMessage::text(messages::no_sessions(session_type)));
+        }
+        let cards = self.cards(sessions).await?;
+
+        // Too many sessions for a native list: send them all as plain text
+        if cards.len() > sender::MAX_LIST_ROWS {
+            return Ok(OutboundMessage::Text(render::session_list(
+                session_type,
+                &cards,
+                sender::MAX_TEXT_BODY,
+            )));
+        }
+
+        let rows = cards
+            .iter()
+            .enumerate()
+            .map(|(i, card)| ListRow {
+                id: (i + 1).to_string(),
+                title: format!("{} {}", i + 1, card.starts_at().format("%a %-d %H:%M")),
+                description: Some(messages::truncate(
+                    &card.venue.name,
+                    sender::MAX_ROW_DESCRIPTION,
+                )),
+            })
+            .collect();
+        Ok(OutboundMessage::List {
+            body: render::session_list(session_type, &cards, sender::MAX_INTERACTIVE_BODY),
+            button: "Join a session".to_string(),
+            sections: vec![ListSection {
+                title: messages::truncate(
+                    session_type_name(session_type),
+                    sender::MAX_SECTION_TITLE,
+                ),
+                rows,
+            }],
+        })
+    }
+
+    async fn my_sessions(&self, user: &User) -> StorageResult<OutboundMessage> {
+        let now = Utc::now();
+        let mut sessions: Vec<Session> = self
+            .sessions
+            .get_user_sessions(user.id)
+            .await?
+            .into_iter()
+            .filter(|s| s.datetime >= now)
+            .collect();
+        sessions.sort_by_key(|s| s.datetime);
+
+        self.remember(
+            user,
+            ConversationMenu::MySessions,
+            None,
+            sessions.iter().map(|s| s.id).collect(),
+        )
+        .await?;
+
+        let cards = self.cards(sessions).await?;
+        Ok(OutboundMessage::Text(render::my_sessions(&cards, user.id)))
+    }
+
+    async fn join_session(&self, user: &User, name: &str, n: usize) -> StorageResult<String> {
+        // Numbers only join from a session list; after "0" they refer to nothing joinable
+        let session_id = self
+            .conversations
+            .get(&user.phone_number)
+            .await?
+            .filter(|state| state.menu == ConversationMenu::SessionList)
+            .and_then(|state| state.session_at(n));
+        let Some(session_id) = session_id else {
+            return Ok(messages::UNKNOWN_COMMAND.to_string());
+        };
+
+        Ok(
+            match self.sessions.register_user(session_id, user.id).await {
+                Ok(RegistrationStatus::Confirmed) => {
+                    let Some(session) = self.sessions.get(session_id).await? else {
+                        return Ok(messages::SESSION_UNAVAILABLE.to_string());
+                    };
+                    render::signed_up(name, &self.card(session).await?, user.id)
+                }
+                Ok(RegistrationStatus::Substitute) => messages::SUBSTITUTE.to_string(),
+                Err(RegistrationError::AlreadyRegistered) => {
+                    messages::ALREADY_REGISTERED.to_string()
+                }
+                Err(RegistrationError::UserNotApproved) => messages::NOT_APPROVED.to_string(),
+                Err(RegistrationError::SessionFull) => messages::SESSION_FULL.to_string(),
+                Err(RegistrationError::SkillLevelMismatch) => {
+                    messages::SKILL_LEVEL_MISMATCH.to_string()
+                }
+                Err(RegistrationError::TooManyLateCancellations) => {
+                    messages::TOO_MANY_LATE_CANCELLATIONS.to_string()
+                }
+                Err(RegistrationError::Storage(e)) => return Err(e),
+                Err(_) => messages::SESSION_UNAVAILABLE.to_string(),
+            },
+        )
+    }
+
+    /// Keep every spot the player was promoted into and hasn't confirmed yet
+    async fn confirm_spots(&self, user: &User) -> StorageResult<&'static str> {
+        let mut confirmed = false;
+        for pending in self.sessions.pending_confirmations(user.id).await? {
+            match self
+                .sessions
+                .confirm_spot(pending.session_id, user.id)
+                .await
+            {
+                Ok(_) => confirmed = true,
+                Err(RegistrationError::Storage(e)) => return Err(e),
+                // Lapsed or withdrawn in the meantime
+                Err(_) => {}
+            }
+        }
+        Ok(if confirmed {
+            messages::SPOT_CONFIRMED
+        } else {
+            messages::NOTHING_TO_CONFIRM
+        })
+    }
+
+    async fn check_in(&self, user: &User) -> StorageResult<&'static str> {
+        match self.attendance.check_in(user, Utc::now()).await {
+            Ok(_) => Ok(messages::CHECKED_IN),
+            Err(AttendanceError::Storage(e)) => Err(e),
+            Err(_) => Ok(messages::NOTHING_TO_CHECK_IN),
+        }
+    }
+
+    async fn cards(&self, sessions: Vec<Session>) -> StorageResult<Vec<SessionCard>> {
+        let mut cards = Vec::with_capacity(sessions.len());
+        for session in sessions {
+            cards.push(self.card(session).await?);
+        }
+        Ok(cards)
+    }
+
+    /// Load the venue and registered players needed to render a session
+    async fn card(&self, session: Session) -> StorageResult<SessionCard> {
+        SessionCard::load(
+            session,
+            self.users.as_ref(),
+            self.sessions.as_ref(),
+            self.venues.as_ref(),
+        )
+        .await
+    }
+
+    async fn remember(
+        &self,
+        user: &User,
+        menu: ConversationMenu,
+        session_type: Option<SessionType>,
+        session_ids: Vec<Uuid>,
+    ) -> StorageResult<()> {
+        let state = ConversationState::new(
+            user.phone_number.clone(),
+            menu,
+            session_type,
+            session_ids,
+            Duration::hours(CONVERSATION_TTL_HOURS),
+        );
+        self.conversations.save(state).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+    use rallybot_core::{
+        calendar, Gender, InMemoryStorage, LookingFor, PlayFrequency, PreferredSide,
+        RegistrationActor, SkillLevel, UserStatus, Venue,
+    };
+
+    struct Fixture {
+        bot: Bot,
+        storage: Arc<InMemoryStorage>,
+        venue: Venue,
+    }
+
+    async fn setup() -> Fixture {
+        let storage = Arc::new(InMemoryStorage::new());
+        let venue = Venue::new("Sports Center A".to_string(), "1 Court Road".to_string());
+        storage.create_venue(venue.clone()).await.unwrap();
+        let bot = Bot::with_repository(Arc::new(Repository::new(storage.clone())));
+        Fixture {
+            bot,
+            storage,
+            venue,
+        }
+    }
+
+    async fn create_user(
+        storage: &Arc<InMemoryStorage>,
+        first_name: &str,
+        phone: &str,
+        approved: bool,
+    ) -> User {
+        let mut user = User::new(
+            first_name.to_string(),
+            "Silva".to_string(),
+            phone.to_string(),
+            format!("{}@example.com", first_name.to_lowercase()),
+            "Lisbon".to_string(),
+            None,
+            "Engineer".to_string(),
+            "Rally".to_string(),
+            "Sports".to_string(),
+            format!("https://linkedin.com/in/{}", first_name.to_lowercase()),
+            Gender::Female,
+            vec![SkillLevel::UpperIntermediate],
+            PreferredSide::Flexible,
+            PlayFrequency::OnceWeek,
+            vec![LookingFor::SocialConnections],
+        );
+        if approved {
+            user.status = UserStatus::Approved;
+        }
+        storage.create_user(user.clone()).await.unwrap();
+        user
+    }
+
+    async fn create_session(
+        fixture: &Fixture,
+        session_type: SessionType,
+        datetime: DateTime<Utc>,
+    ) -> Session {
+        let skill_level = match session_type {
+            SessionType::Mixed => None,
+            _ => Some(SkillLevel::UpperIntermediate),
+        };
+        let session = Session::new(
+            session_type,
+            datetime,
+            90,
+            fixture.venue.id,
+            skill_level,
+            Session::DEFAULT_MAX_PLAYERS,
+            None,
+        )
+        .unwrap();
+        fixture
+            .storage
+            .create_session(session.clone())
+            .await
+            .unwrap();
+        session
+    }
+
+    /// The `n`th of ten steps from now to the end of the venue's week, so the
+    /// sessions are listed whichever day the tests run on
+    fn this_week(n: i32) -> DateTime<Utc> {
+        let now = Utc::now();
+        let week = calendar::local_week(Venue::DEFAULT_TIMEZONE, now, 0);
+        now + (week.end - now) * n / 10
+    }
+
+    fn next_week() -> DateTime<Utc> {
+        calendar::local_week(Venue::DEFAULT_TIMEZONE, Utc::now(), 1).start + Duration::hours(19)
+    }
+
+    #[tokio::test]
+    async fn unregistered_user_gets_application_message() {
+        let fixture = setup().await;
+
+        let reply = fixture.bot.handle_message("+351900000000", "hey").await;
+        assert_eq!(reply, messages::UNREGISTERED);
+
+        let reply = fixture.bot.handle_message("+351900000000", "S").await;
+        assert_eq!(reply, messages::UNREGISTERED);
+    }
+
+    #[tokio::test]
+    async fn unregistered_user_gets_the_application_link() {
+        let fixture = setup().await;
+        let bot = Bot::with_repository(Arc::new(Repository::new(fixture.storage.clone())))
+            .with_application_url(Some("https://rally.pt/apply".to_string()));
+
+        let reply = bot.handle_message("+351900000000", "hey").await;
+        assert!(reply.contains("https://rally.pt/apply"));
+    }
+
+    #[tokio::test]
+    async fn greeting_shows_menu_with_first_name() {
+        let fixture = setup().await;
+        create_user(&fixture.storage, "Ana", "+351911111111", true).await;
+
+        let reply = fixture.bot.handle_message("+351911111111", "hello").await;
+        assert!(reply.starts_with("👋 Hey Ana! What type of session would you like to join?"));
+        assert!(reply.contains("X - Mixed levels Social Games"));
+    }
+
+    #[tokio::test]
+    async fn greeting_prefers_whatsapp_profile_name() {
+        let fixture = setup().await;
+        create_user(&fixture.storage, "Ana", "+351911111111", true).await;
+
+        let inbound =
+            InboundMessage::new("351911111111", Some("Annie".to_string()), "hi".to_string());
+        let reply = fixture.bot.handle_inbound(&inbound).await;
+        assert!(reply.body().starts_with("👋 Hey Annie!"));
+    }
+
+    #[tokio::test]
+    async fn menu_and_listing_use_native_lists() {
+        let fixture = setup().await;
+        create_user(&fixture.storage, "Ana", "+351911111111", true).await;
+        create_session(&fixture, SessionType::Social, this_week(1)).await;
+        create_session(&fixture, SessionType::Social, this_week(2)).await;
+
+        let menu = fixture
+            .bot
+            .handle_inbound(&InboundMessage::new(
+                "+351911111111",
+                None,
+                "hi".to_string(),
+            ))
+            .await;
+        let OutboundMessage::List { sections, .. } = menu else {
+            panic!("menu should be a list, got {:?}", menu);
+        };
+        let ids: Vec<&str> = sections[0].rows.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["C", "S", "L", "X", "0"]);
+
+        let listing = fixture
+            .bot
+            .handle_inbound(&InboundMessage::new("+351911111111", None, "S".to_string()))
+            .await;
+        let OutboundMessage::List { sections, .. } = listing else {
+            panic!("listing should be a list, got {:?}", listing);
+        };
+        let ids: Vec<&str> = sections[0].rows.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "2"]);
+        assert_eq!(
+            sections[0].rows[0].description.as_deref(),
+            Some("Sports Center A")
+        );
+
+        let unknown = fixture
+            .bot
+            .handle_inbound(&InboundMessage::new(
+                "+351911111111",
+                None,
+                "??".to_string(),
+            ))
+            .await;
+        assert!(matches!(unknown, OutboundMessage::Buttons { .. }));
+    }
+
+    #[tokio::test]
+    async fn unknown_command_gets_fallback() {
+        let fixture = setup().await;
+        create_user(&fixture.storage, "Ana", "+351911111111", true).await;
+
+        let reply = fixture.bot.handle_message("+351911111111", "what?").await;
+        assert_eq!(reply, messages::UNKNOWN_COMMAND);
+    }
+
+    #[tokio::test]
+    async fn listing_only_shows_this_weeks_sessions_of_that_type() {
+        let fixture = setup().await;
+        create_user(&fixture.storage, "Ana", "+351911111111", true).await;
+        create_session(&fixture, SessionType::Social, this_week(2)).await;
+        create_session(&fixture, SessionType::Social, this_week(1)).await;
+        create_session(&fixture, SessionType::Social, next_week()).await;
+        create_session(
+            &fixture,
+            SessionType::Social,
+            Utc::now() - Duration::hours(1),
+        )
+        .await;
+        create_session(&fixture, SessionType::League, this_week(1)).await;
+
+        let reply = fixture.bot.handle_message("+351911111111", "s").await;
+        assert!(reply
+            .starts_with("Thanks for choosing! Here are the Social Games available this week:"));
+        assert!(reply.contains("1️⃣ ⏰"));
+        assert!(reply.contains("2️⃣ ⏰"));
+        assert!(!reply.contains("3️⃣"));
+        assert!(reply.contains("📍 Sports Center A"));
+        assert!(reply.contains("🎯 Upper-Intermediate"));
+    }
+
+    #[tokio::test]
+    async fn listing_leaves_out_other_skill_levels() {
+        let fixture = setup().await;
+        create_user(&fixture.storage, "Ana", "+351911111111", true).await;
+        create_session(&fixture, SessionType::League, this_week(1)).await;
+        let elite = Session::new(
+            SessionType::League,
+            this_week(2),
+            90,
+            fixture.venue.id,
+            Some(SkillLevel::Elite),
+            Session::DEFAULT_MAX_PLAYERS,
+            None,
+        )
+        .unwrap();
+        fixture.storage.create_session(elite).await.unwrap();
+
+        let reply = fixture.bot.handle_message("+351911111111", "l").await;
+        assert!(reply.contains("1️⃣ ⏰"));
+        assert!(!reply.contains("2️⃣"));
+        assert!(!reply.contains("🎯 Elite"));
+    }
+
+    #[tokio::test]
+    async fn listing_empty_state() {
+        let fixture = setup().await;
+        create_user(&fixture.storage, "Ana", "+351911111111", true).await;
+
+        let reply = fixture.bot.handle_message("+351911111111", "C").await;
+        assert_eq!(
+            reply,
+            "Sorry, there are no Coaching Classes available this week..."
+        );
+    }
+
+    #[tokio::test]
+    async fn number_joins_session_from_last_listing() {
+        let fixture = setup().await;
+        create_user(&fixture.storage, "Ana", "+351911111111", true).await;
+        create_session(&fixture, SessionType::League, this_week(3)).await;
+        let earliest = create_session(&fixture, SessionType::League, this_week(1)).await;
+
+        fixture.bot.handle_message("+351911111111", "L").await;
+        let reply = fixture.bot.handle_message("+351911111111", "1").await;
+
+        assert!(reply.starts_with("✅ Congratulations, Ana! You're signed up!"));
+        assert!(reply.contains("League Games\nLevel: Upper-Intermediate"));
+        assert!(reply.contains("👤 *Ana Silva*"));
+
+        let registrations = fixture
+            .storage
+            .get_registrations(earliest.id)
+            .await
+            .unwrap();
+        assert_eq!(registrations.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn full_session_adds_substitute() {
+        let fixture = setup().await;
+        let session = create_session(&fixture, SessionType::Social, this_week(1)).await;
+        for i in 0..4 {
+            let player = create_user(
+                &fixture.storage,
+                &format!("Player{}", i),
+                &format!("+35192000000{}", i),
+                true,
+            )
+            .await;
+            fixture
+                .storage
+                .create_registration(rallybot_core::Registration::new(
+                    player.id,
+                    session.id,
+                    RegistrationStatus::Confirmed,
+                ))
+                .await
+                .unwrap();
+        }
+        create_user(&fixture.storage, "Ana", "+351911111111", true).await;
+
+        let listing = fixture.bot.handle_message("+351911111111", "S").await;
+        assert_eq!(listing.matches("👤").count(), 4);
+
+        let reply = fixture.bot.handle_message("+351911111111", "1").await;
+        assert_eq!(reply, messages::SUBSTITUTE);
+
+        let listing = fixture.bot.handle_message("+351911111111", "S").await;
+        assert!(listing.contains("    Substitutes:\n🎾 Ana Silva"));
+    }
+
+    #[tokio::test]
+    async fn full_substitutes_list_is_reported() {
+        let fixture = setup().await;
+        let session = Session::new(
+            SessionType::Social,
+            this_week(1),
+            90,
+            fixture.venue.id,
+            Some(SkillLevel::UpperIntermediate),
+            2,
+            Some(0),
+        )
+        .unwrap();
+        fixture
+            .storage
+            .create_session(session.clone())
+            .await
+            .unwrap();
+        for i in 0..2 {
+            let player = create_user(
+                &fixture.storage,
+                &format!("Player{}", i),
+                &format!("+35192000000{}", i),
+                true,
+            )
+            .await;
+            fixture
+                .storage
+                .create_registration(rallybot_core::Registration::new(
+                    player.id,
+                    session.id,
+                    RegistrationStatus::Confirmed,
+                ))
+                .await
+                .unwrap();
+        }
+        create_user(&fixture.storage, "Ana", "+351911111111", true).await;
+
+        fixture.bot.handle_message("+351911111111", "S").await;
+        let reply = fixture.bot.handle_message("+351911111111", "1").await;
+        assert_eq!(reply, messages::SESSION_FULL);
+    }
+
+    #[tokio::test]
+    async fn number_without_listing_is_unknown() {
+        let fixture = setup().await;
+        create_user(&fixture.storage, "Ana", "+351911111111", true).await;
+        create_session(&fixture, SessionType::Social, this_week(1)).await;
+
+        let reply = fixture.bot.handle_message("+351911111111", "1").await;
+        assert_eq!(reply, messages::UNKNOWN_COMMAND);
+
+        fixture.bot.handle_message("+351911111111", "S").await;
+        let reply = fixture.bot.handle_message("+351911111111", "2").await;
+        assert_eq!(reply, messages::UNKNOWN_COMMAND);
+    }
+
+    #[tokio::test]
+    async fn listing_survives_bot_restart() {
+        let fixture = setup().await;
+        create_user(&fixture.storage, "Ana", "+351911111111", true).await;
+        let session = create_session(&fixture, SessionType::Social, this_week(1)).await;
+
+        fixture.bot.handle_message("+351911111111", "S").await;
+
+        let restarted = Bot::with_repository(Arc::new(Repository::new(fixture.storage.clone())));
+        let reply = restarted.handle_message("+351911111111", "1").await;
+        assert!(reply.starts_with("✅ Congratulations, Ana!"));
+        assert_eq!(
+            fixture
+                .storage
+                .get_registrations(session.id)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn expired_listing_is_ignored() {
+        let fixture = setup().await;
+        create_user(&fixture.storage, "Ana", "+351911111111", true).await;
+        let session = create_session(&fixture, SessionType::Social, this_week(1)).await;
+
+        fixture
+            .storage
+            .save_conversation_state(ConversationState::new(
+                "+351911111111".to_string(),
+                ConversationMenu::SessionList,
+                Some(SessionType::Social),
+                vec![session.id],
+                Duration::seconds(-1),
+            ))
+            .await
+            .unwrap();
+
+        let reply = fixture.bot.handle_message("+351911111111", "1").await;
+        assert_eq!(reply, messages::UNKNOWN_COMMAND);
+    }
+
+    #[tokio::test]
+    async fn double_join_is_reported() {
+        let fixture = setup().await;
+        create_user(&fixture.storage, "Ana", "+351911111111", true).await;
+        create_session(&fixture, SessionType::Social, this_week(1)).await;
+
+        fixture.bot.handle_message("+351911111111", "S").await;
+        fixture.bot.handle_message("+351911111111", "1").await;
+        let reply = fixture.bot.handle_message("+351911111111", "1").await;
+        assert_eq!(reply, messages::ALREADY_REGISTERED);
+    }
+
+    #[tokio::test]
+    async fn unapproved_user_cannot_join() {
+        let fixture = setup().await;
+        create_user(&fixture.storage, "Ana", "+351911111111", false).await;
+        create_session(&fixture, SessionType::Social, this_week(1)).await;
+
+        fixture.bot.handle_message("+351911111111", "S").await;
+        let reply = fixture.bot.handle_message("+351911111111", "1").await;
+        assert_eq!(reply, messages::NOT_APPROVED);
+    }
+
+    #[tokio::test]
+    async fn my_sessions_groups_by_type() {
+        let fixture = setup().await;
+        create_user(&fixture.storage, "Ana", "+351911111111", true).await;
+
+        let reply = fixture.bot.handle_message("+351911111111", "0").await;
+        assert_eq!(reply, messages::NO_UPCOMING_SESSIONS);
+
+        create_session(&fixture, SessionType::Social, this_week(1)).await;
+        create_session(&fixture, SessionType::League, this_week(2)).await;
+        fixture.bot.handle_message("+351911111111", "S").await;
+        fixture.bot.handle_message("+351911111111", "1").await;
+        fixture.bot.handle_message("+351911111111", "L").await;
+        fixture.bot.handle_message("+351911111111", "1").await;
+
+        let reply = fixture.bot.handle_message("+351911111111", "0").await;
+        assert!(reply.starts_with("Here are your upcoming events:\n\nSocial Games\n1️⃣ ⏰"));
+        assert!(reply.contains("\n\nLeague Games\n1️⃣ ⏰"));
+        assert_eq!(reply.matches("👤 *Ana Silva*").count(), 2);
+
+        // Numbers after "0" do not join anything
+        let reply = fixture.bot.handle_message("+351911111111", "1").await;
+        assert_eq!(reply, messages::UNKNOWN_COMMAND);
+    }
+
+    #[tokio::test]
+    async fn check_in_when_the_session_is_about_to_start() {
+        let fixture = setup().await;
+        let ana = create_user(&fixture.storage, "Ana", "+351911111111", true).await;
+
+        let reply = fixture
+            .bot
+            .handle_message("+351911111111", "check in")
+            .await;
+        assert_eq!(reply, messages::NOTHING_TO_CHECK_IN);
+
+        let session = Session::new(
+            SessionType::Mixed,
+            Utc::now() + Duration::minutes(10),
+            90,
+            fixture.venue.id,
+            None,
+            Session::DEFAULT_MAX_PLAYERS,
+            None,
+        )
+        .unwrap();
+        fixture
+            .storage
+            .create_session(session.clone())
+            .await
+            .unwrap();
+        fixture
+            .storage
+            .register_within_capacity(session.id, ana.id, RegistrationActor::Player)
+            .await
+            .unwrap();
+
+        let reply = fixture
+            .bot
+            .handle_message("+351911111111", "Check in")
+            .await;
+        assert_eq!(reply, messages::CHECKED_IN);
+        let attendance = fixture
+            .storage
+            .get_session_attendance(session.id)
+            .await
+            .unwrap();
+        assert_eq!(attendance.len(), 1);
+        assert_eq!(attendance[0].user_id, ana.id);
+    }
+}
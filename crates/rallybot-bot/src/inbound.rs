@@ -0,0 +1,41 @@
+/// A message received from a player
+#[derive(Debug, Clone, PartialEq)]
+pub struct InboundMessage {
+    /// E.164 phone number, e.g. "+351911111111"
+    pub phone: String,
+    /// Name from the sender's WhatsApp profile
+    pub profile_name: Option<String>,
+    pub text: String,
+}
+
+impl InboundMessage {
+    pub fn new(phone: &str, profile_name: Option<String>, text: String) -> Self {
+        Self {
+            phone: normalize_phone(phone),
+            profile_name,
+            text,
+        }
+    }
+}
+
+/// WhatsApp sends numbers without the leading "+" while users are stored in E.164
+pub fn normalize_phone(phone: &str) -> String {
+    let phone = phone.trim();
+    if phone.starts_with('+') {
+        phone.to_string()
+    } else {
+        format!("+{}", phone)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_adds_plus() {
+        assert_eq!(normalize_phone("351911111111"), "+351911111111");
+        assert_eq!(normalize_phone("+351911111111"), "+351911111111");
+        assert_eq!(normalize_phone(" 351911111111 "), "+351911111111");
+    }
+}
@@ -0,0 +1,16 @@
+pub mod command;
+pub mod engine;
+pub mod inbound;
+pub mod messages;
+pub mod notifier;
+pub mod render;
+pub mod sender;
+
+pub use command::Command;
+pub use engine::Bot;
+pub use inbound::InboundMessage;
+pub use notifier::Notifier;
+pub use render::SessionCard;
+pub use sender::{
+    ConsoleSender, MessageSender, OutboundMessage, RecordingSender, SendError, WhatsAppClient,
+};
@@ -0,0 +1,177 @@
+use crate::sender::{ListRow, ListSection, OutboundMessage, ReplyButton};
+use rallybot_core::SessionType;
+
+pub const UNREGISTERED: &str = "Hi there! It looks like you're not registered with our community yet. Only registered members can interact with me and sign up for Rally events.
+
+🎾 Want to join the fun? Ask one of the organisers how to apply to become a member.
+
+Once your application is approved, you'll be ready to hit the court and Rally with us! ✨";
+
+pub const UNKNOWN_COMMAND: &str =
+    "Sorry, I don't understand that command ! Press 🎾 to see the menu";
+
+pub const NOT_APPROVED: &str = "Your membership application hasn't been approved yet. You'll be able to sign up for sessions as soon as it is! ✨";
+
+pub const ALREADY_REGISTERED: &str =
+    "You're already registered for this session! Reply 0 to see your upcoming events.";
+
+pub const SESSION_UNAVAILABLE: &str =
+    "Sorry, that session is no longer available. Press 🎾 to see the menu";
+
+pub const SUBSTITUTE: &str = "⚠️ This event is currently full.
+📋 You've been added to the substitutes list! If a spot opens up, I'll notify you right away.";
+
+pub const SESSION_FULL: &str =
+    "Sorry, this event and its substitutes list are full. Press 🎾 to see the menu";
+
+pub const SKILL_LEVEL_MISMATCH: &str =
+    "Sorry, this event is for a different skill level. Press 🎾 to see the sessions open to you";
+
+pub const TOO_MANY_LATE_CANCELLATIONS: &str =
+    "Sorry, you've dropped out of too many sessions at short notice lately to sign up yourself. Please ask one of the organisers to add you";
+
+pub const TRY_AGAIN: &str =
+    "Sorry, something went wrong on our side. Please try again in a moment 🙏";
+
+pub const SPOT_CONFIRMED: &str =
+    "🙌 You're all set, your spot is confirmed! Reply 0 to see your upcoming events.";
+
+pub const NOTHING_TO_CONFIRM: &str =
+    "You have no spot waiting to be confirmed. Press 🎾 to see the menu";
+
+pub const CHECKED_IN: &str = "✅ You're checked in, enjoy the game!";
+
+pub const NOTHING_TO_CHECK_IN: &str =
+    "You have no session to check in to right now. Check-in opens 30 minutes before the start";
+
+pub const NO_UPCOMING_SESSIONS: &str =
+    "You're not registered to any upcoming events yet. Press 🎾 to see the menu";
+
+/// [`UNREGISTERED`], pointing at the application form when there is one
+pub fn unregistered(application_url: Option<&str>) -> String {
+    match application_url {
+        Some(url) => format!(
+            "Hi there! It looks like you're not registered with our community yet. Only registered members can interact with me and sign up for Rally events.
+
+🎾 Want to join the fun? Apply to become a member here: {}
+
+Once your application is approved, you'll be ready to hit the court and Rally with us! ✨",
+            url
+        ),
+        None => UNREGISTERED.to_string(),
+    }
+}
+
+pub fn menu(name: &str) -> String {
+    format!(
+        "👋 Hey {}! What type of session would you like to join?
+
+C - Coaching Classes
+S - Social Games
+L - League Games
+X - Mixed levels Social Games
+0️⃣ Show the events I'm registered to
+
+👉 Reply with the right letter to see available sessions or 0 to show your upcoming sessions!",
+        name
+    )
+}
+
+/// Sent when an admin approves a membership application
+pub fn welcome(name: &str) -> String {
+    format!(
+        "🎉 Welcome to Rally, {}! Your membership application has been approved.
+
+You can now sign up for Coaching Classes, Social Games and League Games right here. Press 🎾 or say hi to see the menu!",
+        name
+    )
+}
+
+/// The main menu as a native list, one row per command
+pub fn menu_list(name: &str) -> OutboundMessage {
+    let row = |id: &str, title: &str| ListRow {
+        id: id.to_string(),
+        title: title.to_string(),
+        description: None,
+    };
+    OutboundMessage::List {
+        body: menu(name),
+        button: "Choose".to_string(),
+        sections: vec![ListSection {
+            title: "Sessions".to_string(),
+            rows: vec![
+                row("C", "Coaching Classes"),
+                row("S", "Social Games"),
+                row("L", "League Games"),
+                row("X", "Mixed levels Social"),
+                row("0", "My events"),
+            ],
+        }],
+    }
+}
+
+/// The fallback reply, with a button that brings the menu back
+pub fn unknown_command() -> OutboundMessage {
+    OutboundMessage::Buttons {
+        body: UNKNOWN_COMMAND.to_string(),
+        buttons: vec![ReplyButton {
+            id: "🎾".to_string(),
+            title: "🎾 Menu".to_string(),
+        }],
+    }
+}
+
+/// `body` with a button for keeping a spot the player was promoted into
+pub fn confirm_spot(body: String) -> OutboundMessage {
+    OutboundMessage::Buttons {
+        body,
+        buttons: vec![ReplyButton {
+            id: "confirm".to_string(),
+            title: "✅ Keep my spot".to_string(),
+        }],
+    }
+}
+
+pub fn session_type_name(session_type: SessionType) -> &'static str {
+    match session_type {
+        SessionType::Coaching => "Coaching Classes",
+        SessionType::Social => "Social Games",
+        SessionType::League => "League Games",
+        SessionType::Mixed => "Mixed levels Social Games",
+    }
+}
+
+pub fn sessions_header(session_type: SessionType) -> String {
+    format!(
+        "Thanks for choosing! Here are the {} available this week:",
+        session_type_name(session_type)
+    )
+}
+
+pub fn no_sessions(session_type: SessionType) -> String {
+    format!(
+        "Sorry, there are no {} available this week...",
+        session_type_name(session_type)
+    )
+}
+
+/// Cut `text` to at most `max` characters, ending with "…" when shortened
+pub fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncate_long_text() {
+        assert_eq!(truncate("Sports Center A", 20), "Sports Center A");
+        assert_eq!(truncate("Sports Center A", 8), "Sports …");
+    }
+}
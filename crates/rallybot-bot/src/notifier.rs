@@ -0,0 +1,376 @@
+use crate::{
+    messages,
+    render::{self, SessionCard},
+    sender::{MessageSender, OutboundMessage},
+};
+use rallybot_core::{
+    DomainEvent, EventSubscriber, Registration, Repository, Session, SessionRepository, Storage,
+    StorageResult, User, UserRepository, Venue, VenueRepository,
+};
+use std::sync::Arc;
+
+/// Messages players get without writing to the bot first, e.g. when an
+/// organiser moves or cancels a session. Subscribe it to the repository's
+/// events to send them as things happen.
+pub struct Notifier {
+    users: Arc<dyn UserRepository>,
+    sessions: Arc<dyn SessionRepository>,
+    venues: Arc<dyn VenueRepository>,
+    sender: Arc<dyn MessageSender>,
+}
+
+impl Notifier {
+    pub fn new(
+        users: Arc<dyn UserRepository>,
+        sessions: Arc<dyn SessionRepository>,
+        venues: Arc<dyn VenueRepository>,
+        sender: Arc<dyn MessageSender>,
+    ) -> Self {
+        Self {
+            users,
+            sessions,
+            venues,
+            sender,
+        }
+    }
+
+    pub fn with_repository<S: Storage + 'static>(
+        repository: Arc<Repository<S>>,
+        sender: Arc<dyn MessageSender>,
+    ) -> Self {
+        Self::new(
+            repository.clone() as Arc<dyn UserRepository>,
+            repository.clone() as Arc<dyn SessionRepository>,
+            repository as Arc<dyn VenueRepository>,
+            sender,
+        )
+    }
+
+    /// Tell every confirmed player and substitute that the session is off.
+    /// Returns how many of them the message reached.
+    pub async fn session_cancelled(&self, session: &Session) -> StorageResult<usize> {
+        let card = self.card(session.clone()).await?;
+        let message = OutboundMessage::text(render::session_cancelled(&card));
+        Ok(self.send_to_players(&card, &message).await)
+    }
+
+    /// Tell everyone signed up what an organiser changed. Nothing is sent when
+    /// the edit left the session as it was. Returns how many players were reached.
+    pub async fn session_changed(&self, before: &Session, after: &Session) -> StorageResult<usize> {
+        let card = self.card(after.clone()).await?;
+        let before_venue = if before.venue_id == after.venue_id {
+            card.venue.clone()
+        } else {
+            self.venues
+                .get(before.venue_id)
+                .await?
+                .unwrap_or_else(|| Venue {
+                    id: before.venue_id,
+                    name: String::new(),
+                    ..card.venue.clone()
+                })
+        };
+
+        let changes = render::session_changes(before, &before_venue, after, &card.venue);
+        if changes.is_empty() {
+            return Ok(0);
+        }
+        let message = OutboundMessage::text(render::session_changed(&card, &changes));
+        Ok(self.send_to_players(&card, &message).await)
+    }
+
+    /// Welcome a newly approved member. Returns whether the message went out;
+    /// a failed send is logged rather than undoing the approval.
+    pub async fn user_approved(&self, user: &User) -> bool {
+        let message = OutboundMessage::text(messages::welcome(&user.first_name));
+        match self.sender.send(&user.phone_number, &message).await {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::warn!("failed to welcome {}: {}", user.phone_number, e);
+                false
+            }
+        }
+    }
+
+    /// Tell a substitute they were moved into a freed place, with a button to
+    /// keep it when they have to confirm. Returns whether the message went out.
+    pub async fn substitute_promoted(&self, registration: &Registration) -> StorageResult<bool> {
+        let Some((user, card)) = self.player_and_card(registration).await? else {
+            return Ok(false);
+        };
+        let body = render::promoted(&user.first_name, &card, user.id, registration.confirm_by);
+        let message = match registration.confirm_by {
+            Some(_) => messages::confirm_spot(body),
+            None => OutboundMessage::text(body),
+        };
+        Ok(self.send_to(&user, &message).await)
+    }
+
+    /// Tell a promoted substitute their spot went to the next in line
+    pub async fn promotion_lapsed(&self, registration: &Registration) -> StorageResult<bool> {
+        let Some((user, card)) = self.player_and_card(registration).await? else {
+            return Ok(false);
+        };
+        let message = OutboundMessage::text(render::promotion_lapsed(&card));
+        Ok(self.send_to(&user, &message).await)
+    }
+
+    async fn player_and_card(
+        &self,
+        registration: &Registration,
+    ) -> StorageResult<Option<(User, SessionCard)>> {
+        let Some(user) = self.users.get(registration.user_id).await? else {
+            return Ok(None);
+        };
+        let Some(session) = self.sessions.get(registration.session_id).await? else {
+            return Ok(None);
+        };
+        Ok(Some((user, self.card(session).await?)))
+    }
+
+    async fn send_to(&self, user: &User, message: &OutboundMessage) -> bool {
+        match self.sender.send(&user.phone_number, message).await {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::warn!("failed to notify {}: {}", user.phone_number, e);
+                false
+            }
+        }
+    }
+
+    async fn card(&self, session: Session) -> StorageResult<SessionCard> {
+        SessionCard::load(
+            session,
+            self.users.as_ref(),
+            self.sessions.as_ref(),
+            self.venues.as_ref(),
+        )
+        .await
+    }
+
+    /// A failed send is logged and skipped so one bad number doesn't stop the rest
+    async fn send_to_players(&self, card: &SessionCard, message: &OutboundMessage) -> usize {
+        let mut delivered = 0;
+        for (_, player) in &card.players {
+            match self.sender.send(&player.phone_number, message).await {
+                Ok(()) => delivered += 1,
+                Err(e) => tracing::warn!("failed to notify {}: {}", player.phone_number, e),
+            }
+        }
+        delivered
+    }
+}
+
+/// Whatever happened has already been stored, so a message that can't be
+/// sent is only logged
+#[async_trait::async_trait]
+impl EventSubscriber for Notifier {
+    async fn handle(&self, event: &DomainEvent) {
+        match event {
+            DomainEvent::SessionUpdated { before, after } => {
+                if let Err(e) = self.session_changed(before, after).await {
+                    tracing::error!(
+                        "could not notify players of changed session {}: {}",
+                        after.id,
+                        e
+                    );
+                }
+            }
+            DomainEvent::SessionCancelled { session } => {
+                if let Err(e) = self.session_cancelled(session).await {
+                    tracing::error!(
+                        "could not notify players of cancelled session {}: {}",
+                        session.id,
+                        e
+                    );
+                }
+            }
+            DomainEvent::SubstitutePromoted { registration } => {
+                if let Err(e) = self.substitute_promoted(registration).await {
+                    tracing::error!(
+                        "could not notify promoted substitute {}: {}",
+                        registration.user_id,
+                        e
+                    );
+                }
+            }
+            DomainEvent::PromotionLapsed { registration } => {
+                if let Err(e) = self.promotion_lapsed(registration).await {
+                    tracing::error!(
+                        "could not notify {} of lapsed promotion: {}",
+                        registration.user_id,
+                        e
+                    );
+                }
+            }
+            DomainEvent::UserApproved { user } => {
+                self.user_approved(user).await;
+            }
+            DomainEvent::UserRegistered { .. }
+            | DomainEvent::UserUnregistered { .. }
+            | DomainEvent::SessionCreated { .. } => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sender::RecordingSender;
+    use chrono::{Duration, Utc};
+    use rallybot_core::{
+        Gender, InMemoryStorage, LookingFor, PlayFrequency, PreferredSide, SessionType,
+        SessionUpdate, SkillLevel, User, UserStatus,
+    };
+
+    fn user(first_name: &str, phone: &str) -> User {
+        let mut user = User::new(
+            first_name.to_string(),
+            "Silva".to_string(),
+            phone.to_string(),
+            format!("{}@example.com", first_name.to_lowercase()),
+            "Lisbon".to_string(),
+            None,
+            "Engineer".to_string(),
+            "Rally".to_string(),
+            "Sports".to_string(),
+            "https://linkedin.com/in/player".to_string(),
+            Gender::Female,
+            vec![SkillLevel::Intermediate],
+            PreferredSide::Flexible,
+            PlayFrequency::OnceWeek,
+            vec![LookingFor::SocialConnections],
+        );
+        user.status = UserStatus::Approved;
+        user
+    }
+
+    const PHONES: [&str; 3] = ["+351911111111", "+351922222222", "+351933333333"];
+
+    /// A two-player session with two confirmed players and one substitute
+    async fn setup() -> (
+        Arc<InMemoryStorage>,
+        Arc<Repository<InMemoryStorage>>,
+        Arc<RecordingSender>,
+        Notifier,
+        Session,
+    ) {
+        let storage = Arc::new(InMemoryStorage::new());
+        let repository = Arc::new(Repository::new(storage.clone()));
+        let sender = Arc::new(RecordingSender::new());
+        let notifier = Notifier::with_repository(repository.clone(), sender.clone());
+
+        let venue = Venue::new("Sports Center A".to_string(), "1 Court Road".to_string());
+        storage.create_venue(venue.clone()).await.unwrap();
+        let session = Session::new(
+            SessionType::Social,
+            Utc::now() + Duration::days(1),
+            90,
+            venue.id,
+            Some(SkillLevel::Intermediate),
+            2,
+            None,
+        )
+        .unwrap();
+        storage.create_session(session.clone()).await.unwrap();
+
+        for (i, phone) in PHONES.iter().enumerate() {
+            let player = user(&format!("Player{}", i), phone);
+            storage.create_user(player.clone()).await.unwrap();
+            repository
+                .register_user(session.id, player.id)
+                .await
+                .unwrap();
+        }
+        (storage, repository, sender, notifier, session)
+    }
+
+    #[tokio::test]
+    async fn cancellation_reaches_players_and_substitutes() {
+        let (_, repository, sender, notifier, session) = setup().await;
+
+        let cancelled = repository
+            .cancel(session.id, "Courts closed")
+            .await
+            .unwrap();
+        assert_eq!(notifier.session_cancelled(&cancelled).await.unwrap(), 3);
+
+        for phone in PHONES {
+            let sent = sender.sent_to(phone);
+            assert_eq!(sent.len(), 1);
+            assert!(sent[0].body().contains("Reason: Courts closed"));
+        }
+    }
+
+    #[tokio::test]
+    async fn venue_change_names_both_venues() {
+        let (storage, repository, sender, notifier, session) = setup().await;
+        let venue = Venue::new("Sports Center B".to_string(), "2 Court Road".to_string());
+        storage.create_venue(venue.clone()).await.unwrap();
+
+        let update = SessionUpdate {
+            venue_id: Some(venue.id),
+            ..Default::default()
+        };
+        let (before, after) = repository.update(session.id, &update).await.unwrap();
+        assert_eq!(notifier.session_changed(&before, &after).await.unwrap(), 3);
+
+        for phone in PHONES {
+            let sent = sender.sent_to(phone);
+            assert_eq!(sent.len(), 1);
+            assert!(sent[0]
+                .body()
+                .contains("📍 moved from Sports Center A to Sports Center B"));
+        }
+    }
+
+    #[tokio::test]
+    async fn unchanged_session_sends_nothing() {
+        let (_, repository, sender, notifier, session) = setup().await;
+
+        let (before, after) = repository
+            .update(session.id, &SessionUpdate::default())
+            .await
+            .unwrap();
+        assert_eq!(notifier.session_changed(&before, &after).await.unwrap(), 0);
+        assert!(sender.sent().is_empty());
+    }
+
+    #[tokio::test]
+    async fn promoted_substitute_is_asked_to_confirm() {
+        let (storage, _, sender, notifier, session) = setup().await;
+        let repository =
+            Repository::new(storage.clone()).with_confirmation_window(Some(Duration::hours(2)));
+        let first = storage.get_user_by_phone(PHONES[0]).await.unwrap().unwrap();
+
+        let promoted = repository
+            .unregister_user(session.id, first.id)
+            .await
+            .unwrap();
+        assert_eq!(promoted.len(), 1);
+        assert!(notifier.substitute_promoted(&promoted[0]).await.unwrap());
+
+        let sent = sender.sent_to(PHONES[2]);
+        assert_eq!(sent.len(), 1);
+        assert!(sent[0].body().contains("A spot opened up"));
+        assert!(sent[0].body().contains("Please confirm by"));
+        assert!(matches!(sent[0], OutboundMessage::Buttons { .. }));
+    }
+
+    #[tokio::test]
+    async fn subscribed_notifier_reacts_to_events() {
+        let (_, repository, sender, notifier, session) = setup().await;
+        repository.events().subscribe(Arc::new(notifier));
+
+        repository
+            .cancel(session.id, "Courts closed")
+            .await
+            .unwrap();
+
+        for phone in PHONES {
+            let sent = sender.wait_for(phone, 1).await;
+            assert_eq!(sent.len(), 1);
+            assert!(sent[0].body().contains("Reason: Courts closed"));
+        }
+    }
+}
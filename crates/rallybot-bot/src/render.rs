@@ -0,0 +1,623 @@
+use crate::messages::{self, session_type_name};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use rallybot_core::{
+    Registration, RegistrationStatus, Session, SessionRepository, SessionType, SkillLevel,
+    StorageResult, User, UserRepository, Venue, VenueRepository,
+};
+use uuid::Uuid;
+
+/// Shown under a session that didn't fit in the message
+pub const READ_MORE: &str = "Read more";
+
+/// A session with everything needed to draw it
+#[derive(Debug, Clone)]
+pub struct SessionCard {
+    pub session: Session,
+    pub venue: Venue,
+    /// Registrations with their players, in sign-up order
+    pub players: Vec<(Registration, User)>,
+}
+
+impl SessionCard {
+    pub fn new(session: Session, venue: Venue, mut players: Vec<(Registration, User)>) -> Self {
+        players.sort_by_key(|(registration, _)| registration.created_at);
+        Self {
+            session,
+            venue,
+            players,
+        }
+    }
+
+    /// Load the venue and registered players needed to render a session
+    pub async fn load(
+        session: Session,
+        users: &dyn UserRepository,
+        sessions: &dyn SessionRepository,
+        venues: &dyn VenueRepository,
+    ) -> StorageResult<Self> {
+        let venue = venues
+            .get(session.venue_id)
+            .await?
+            .unwrap_or_else(|| Venue {
+                id: session.venue_id,
+                name: String::new(),
+                address: String::new(),
+                timezone: Venue::DEFAULT_TIMEZONE,
+            });
+
+        let mut players = Vec::new();
+        for registration in sessions.get_registrations(session.id).await? {
+            if let Some(player) = users.get(registration.user_id).await? {
+                players.push((registration, player));
+            }
+        }
+        Ok(Self::new(session, venue, players))
+    }
+
+    /// The start on the venue's clock, which is what players go by
+    pub fn starts_at(&self) -> DateTime<Tz> {
+        self.venue.local(self.session.datetime)
+    }
+}
+
+/// Keycap emoji for a list position: 1️⃣, 2️⃣ … 🔟, then one keycap per digit
+pub fn keycap(n: usize) -> String {
+    if n == 10 {
+        return "🔟".to_string();
+    }
+    n.to_string()
+        .chars()
+        .map(|digit| format!("{}\u{FE0F}\u{20E3}", digit))
+        .collect()
+}
+
+/// "⏰ Mon 30 10:00 📍 Sports Center A", in the venue's local time
+pub fn when_and_where(datetime: DateTime<Tz>, venue: &str) -> String {
+    format!("⏰ {} 📍 {}", datetime.format("%a %-d %H:%M"), venue)
+}
+
+/// "1️⃣ ⏰ Mon 30 10:00 📍 Sports Center A"
+pub fn headline(position: usize, card: &SessionCard) -> String {
+    format!(
+        "{} {}",
+        keycap(position),
+        when_and_where(card.starts_at(), &card.venue.name)
+    )
+}
+
+/// 👤 lines for confirmed players followed by 🎾 lines for substitutes,
+/// with the highlighted user in bold
+pub fn player_lines(card: &SessionCard, highlight: Option<Uuid>) -> Vec<String> {
+    let name = |user: &User| {
+        if highlight == Some(user.id) {
+            format!("*{}*", user.full_name())
+        } else {
+            user.full_name()
+        }
+    };
+
+    let mut lines: Vec<String> = card
+        .players
+        .iter()
+        .filter(|(r, _)| r.status == RegistrationStatus::Confirmed)
+        .map(|(_, user)| format!("👤 {}", name(user)))
+        .collect();
+
+    let substitutes: Vec<String> = card
+        .players
+        .iter()
+        .filter(|(r, _)| r.status == RegistrationStatus::Substitute)
+        .map(|(_, user)| format!("🎾 {}", name(user)))
+        .collect();
+    if !substitutes.is_empty() {
+        lines.push("    Substitutes:".to_string());
+        lines.extend(substitutes);
+    }
+    lines
+}
+
+/// Headline, 🎯 level (not shown for Mixed) and player list
+pub fn session_block(position: usize, card: &SessionCard, highlight: Option<Uuid>) -> String {
+    let mut lines = vec![headline(position, card)];
+    if let Some(level) = card.session.skill_level {
+        lines.push(format!("🎯 {}", level));
+    }
+    lines.extend(player_lines(card, highlight));
+    lines.join("\n")
+}
+
+/// The C/S/L/X listing. Sessions are drawn in full while they fit in
+/// `max_chars`; the rest are cut to their headline and "Read more".
+pub fn session_list(session_type: SessionType, cards: &[SessionCard], max_chars: usize) -> String {
+    if cards.is_empty() {
+        return messages::no_sessions(session_type);
+    }
+
+    let mut text = messages::sessions_header(session_type);
+    let mut truncating = false;
+    for (i, card) in cards.iter().enumerate() {
+        if !truncating {
+            let block = session_block(i + 1, card, None);
+            if fits(&text, &block, max_chars) {
+                text = format!("{}\n\n{}", text, block);
+                continue;
+            }
+            truncating = true;
+        }
+
+        let short = format!("{}\n{}", headline(i + 1, card), READ_MORE);
+        if !fits(&text, &short, max_chars) {
+            break;
+        }
+        text = format!("{}\n\n{}", text, short);
+    }
+    text
+}
+
+fn fits(text: &str, block: &str, max_chars: usize) -> bool {
+    text.chars().count() + 2 + block.chars().count() <= max_chars
+}
+
+/// The "0" view: upcoming sessions grouped by type, numbered within each group,
+/// with the user highlighted
+pub fn my_sessions(cards: &[SessionCard], user_id: Uuid) -> String {
+    if cards.is_empty() {
+        return messages::NO_UPCOMING_SESSIONS.to_string();
+    }
+
+    let mut blocks = vec!["Here are your upcoming events:".to_string()];
+    for session_type in [
+        SessionType::Coaching,
+        SessionType::Social,
+        SessionType::League,
+        SessionType::Mixed,
+    ] {
+        let of_type: Vec<&SessionCard> = cards
+            .iter()
+            .filter(|c| c.session.session_type == session_type)
+            .collect();
+        if of_type.is_empty() {
+            continue;
+        }
+
+        let mut group = vec![session_type_name(session_type).to_string()];
+        for (i, card) in of_type.iter().enumerate() {
+            group.push(session_block(i + 1, card, Some(user_id)));
+        }
+        blocks.push(group.join("\n"));
+    }
+    blocks.join("\n\n")
+}
+
+/// Confirmation after joining as a confirmed player
+pub fn signed_up(name: &str, card: &SessionCard, user_id: Uuid) -> String {
+    let mut lines = vec![
+        format!("✅ Congratulations, {}! You're signed up!", name),
+        String::new(),
+        session_type_name(card.session.session_type).to_string(),
+    ];
+    if let Some(level) = card.session.skill_level {
+        lines.push(format!("Level: {}", level));
+    }
+    lines.push(when_and_where(card.starts_at(), &card.venue.name));
+    lines.extend(player_lines(card, Some(user_id)));
+    lines.join("\n")
+}
+
+/// One line per field an organiser changed, e.g. "⏰ moved from 18:00 to 18:30".
+/// Venues are passed in since the session only holds the id; each time is
+/// shown on its own venue's clock.
+pub fn session_changes(
+    before: &Session,
+    before_venue: &Venue,
+    after: &Session,
+    after_venue: &Venue,
+) -> Vec<String> {
+    let mut lines = Vec::new();
+    if after.datetime != before.datetime {
+        let from = before_venue.local(before.datetime);
+        let to = after_venue.local(after.datetime);
+        // Only repeat the day when it changed
+        let format = if from.date_naive() == to.date_naive() {
+            "%H:%M"
+        } else {
+            "%a %-d %H:%M"
+        };
+        lines.push(format!(
+            "⏰ moved from {} to {}",
+            from.format(format),
+            to.format(format)
+        ));
+    }
+    if after.duration_minutes != before.duration_minutes {
+        lines.push(format!(
+            "⌛ now {} minutes instead of {}",
+            after.duration_minutes, before.duration_minutes
+        ));
+    }
+    if after.venue_id != before.venue_id {
+        lines.push(format!(
+            "📍 moved from {} to {}",
+            before_venue.name, after_venue.name
+        ));
+    }
+    if after.session_type != before.session_type {
+        lines.push(format!(
+            "🎾 now {} instead of {}",
+            session_type_name(after.session_type),
+            session_type_name(before.session_type)
+        ));
+    }
+    if after.skill_level != before.skill_level {
+        let level = |level: Option<SkillLevel>| {
+            level.map_or("all levels".to_string(), |level| level.to_string())
+        };
+        lines.push(format!(
+            "🎯 now {} instead of {}",
+            level(after.skill_level),
+            level(before.skill_level)
+        ));
+    }
+    lines
+}
+
+/// Sent to everyone signed up when an organiser edits the session
+pub fn session_changed(card: &SessionCard, changes: &[String]) -> String {
+    let mut lines = vec![
+        "✏️ An event you're signed up for has changed".to_string(),
+        String::new(),
+    ];
+    lines.extend(changes.iter().cloned());
+    lines.push(String::new());
+    lines.push(session_type_name(card.session.session_type).to_string());
+    if let Some(level) = card.session.skill_level {
+        lines.push(format!("Level: {}", level));
+    }
+    lines.push(when_and_where(card.starts_at(), &card.venue.name));
+    lines.extend(player_lines(card, None));
+    lines.join("\n")
+}
+
+/// Sent to everyone signed up when an organiser cancels the session
+pub fn session_cancelled(card: &SessionCard) -> String {
+    let mut lines = vec![
+        "❌ This event has been cancelled".to_string(),
+        String::new(),
+        session_type_name(card.session.session_type).to_string(),
+    ];
+    if let Some(level) = card.session.skill_level {
+        lines.push(format!("Level: {}", level));
+    }
+    lines.push(when_and_where(card.starts_at(), &card.venue.name));
+    if let Some(reason) = &card.session.cancellation_reason {
+        lines.push(format!("Reason: {}", reason));
+    }
+    lines.push(String::new());
+    lines.push("Sorry for the inconvenience. Press 🎾 to see other events".to_string());
+    lines.join("\n")
+}
+
+/// Sent to a substitute moved into a freed place. With a deadline they have
+/// to confirm by then or the spot goes to the next substitute.
+pub fn promoted(
+    name: &str,
+    card: &SessionCard,
+    user_id: Uuid,
+    confirm_by: Option<DateTime<Utc>>,
+) -> String {
+    let mut lines = vec![
+        format!("🎉 Good news, {}! A spot opened up and you're in!", name),
+        String::new(),
+        session_type_name(card.session.session_type).to_string(),
+    ];
+    if let Some(level) = card.session.skill_level {
+        lines.push(format!("Level: {}", level));
+    }
+    lines.push(when_and_where(card.starts_at(), &card.venue.name));
+    lines.extend(player_lines(card, Some(user_id)));
+    if let Some(confirm_by) = confirm_by {
+        lines.push(String::new());
+        lines.push(format!(
+            "⏳ Please confirm by {} to keep your spot, or it will go to the next substitute.",
+            card.venue.local(confirm_by).format("%a %-d %H:%M")
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Sent to a promoted substitute who didn't confirm in time
+pub fn promotion_lapsed(card: &SessionCard) -> String {
+    let mut lines = vec![
+        "⌛ You didn't confirm your spot in time, so it went to the next substitute".to_string(),
+        String::new(),
+        session_type_name(card.session.session_type).to_string(),
+    ];
+    if let Some(level) = card.session.skill_level {
+        lines.push(format!("Level: {}", level));
+    }
+    lines.push(when_and_where(card.starts_at(), &card.venue.name));
+    lines.push(String::new());
+    lines.push("Press 🎾 to see other events".to_string());
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone, Utc};
+    use rallybot_core::{Gender, LookingFor, PlayFrequency, PreferredSide, SessionUpdate};
+
+    fn user(first_name: &str, last_name: &str) -> User {
+        User::new(
+            first_name.to_string(),
+            last_name.to_string(),
+            "+351911111111".to_string(),
+            "player@example.com".to_string(),
+            "Lisbon".to_string(),
+            None,
+            "Engineer".to_string(),
+            "Rally".to_string(),
+            "Sports".to_string(),
+            "https://linkedin.com/in/player".to_string(),
+            Gender::Male,
+            vec![SkillLevel::UpperIntermediate],
+            PreferredSide::Flexible,
+            PlayFrequency::OnceWeek,
+            vec![LookingFor::SocialConnections],
+        )
+    }
+
+    fn venue(name: &str) -> Venue {
+        Venue::new(name.to_string(), "1 Court Road".to_string())
+    }
+
+    fn session(
+        session_type: SessionType,
+        datetime: DateTime<Utc>,
+        skill_level: Option<SkillLevel>,
+    ) -> Session {
+        Session::new(
+            session_type,
+            datetime,
+            90,
+            Uuid::new_v4(),
+            skill_level,
+            Session::DEFAULT_MAX_PLAYERS,
+            None,
+        )
+        .unwrap()
+    }
+
+    /// Players signed up one minute apart; the first `confirmed` are confirmed
+    fn players(
+        session: &Session,
+        names: &[(&str, &str)],
+        confirmed: usize,
+    ) -> Vec<(Registration, User)> {
+        names
+            .iter()
+            .enumerate()
+            .map(|(i, (first, last))| {
+                let user = user(first, last);
+                let status = if i < confirmed {
+                    RegistrationStatus::Confirmed
+                } else {
+                    RegistrationStatus::Substitute
+                };
+                let mut registration = Registration::new(user.id, session.id, status);
+                registration.created_at = Utc.with_ymd_and_hms(2024, 12, 20, 9, 0, 0).unwrap()
+                    + Duration::minutes(i as i64);
+                (registration, user)
+            })
+            .collect()
+    }
+
+    const SEVEN_PLAYERS: [(&str, &str); 7] = [
+        ("Ana", "Silva"),
+        ("Bruno", "Costa"),
+        ("Carla", "Mendes"),
+        ("Diogo", "Ferreira"),
+        ("Eva", "Santos"),
+        ("Filipe", "Rocha"),
+        ("Gabriela", "Lopes"),
+    ];
+
+    fn full_social_card() -> SessionCard {
+        let session = session(
+            SessionType::Social,
+            Utc.with_ymd_and_hms(2024, 12, 30, 10, 0, 0).unwrap(),
+            Some(SkillLevel::UpperIntermediate),
+        );
+        let players = players(&session, &SEVEN_PLAYERS, 4);
+        SessionCard::new(session, venue("Sports Center A"), players)
+    }
+
+    fn open_social_card() -> SessionCard {
+        let session = session(
+            SessionType::Social,
+            Utc.with_ymd_and_hms(2024, 12, 31, 11, 30, 0).unwrap(),
+            Some(SkillLevel::Intermediate),
+        );
+        let players = players(&session, &SEVEN_PLAYERS[4..], 4);
+        SessionCard::new(session, venue("Sports Center B"), players)
+    }
+
+    #[test]
+    fn keycap_numbers() {
+        assert_eq!(keycap(1), "1️⃣");
+        assert_eq!(keycap(9), "9️⃣");
+        assert_eq!(keycap(10), "🔟");
+        assert_eq!(keycap(12), "1️⃣2️⃣");
+    }
+
+    #[test]
+    fn when_and_where_format() {
+        let datetime = Utc.with_ymd_and_hms(2024, 12, 30, 10, 0, 0).unwrap();
+        assert_eq!(
+            when_and_where(
+                datetime.with_timezone(&Tz::Europe__Lisbon),
+                "Sports Center A"
+            ),
+            "⏰ Mon 30 10:00 📍 Sports Center A"
+        );
+    }
+
+    #[test]
+    fn times_are_shown_on_the_venue_clock() {
+        // The same 18:30 UTC start either side of the October clock change
+        let summer = Utc.with_ymd_and_hms(2025, 10, 21, 18, 30, 0).unwrap();
+        let winter = Utc.with_ymd_and_hms(2025, 10, 28, 18, 30, 0).unwrap();
+        let card = |datetime| {
+            SessionCard::new(
+                session(
+                    SessionType::Social,
+                    datetime,
+                    Some(SkillLevel::Intermediate),
+                ),
+                venue("Sports Center A"),
+                Vec::new(),
+            )
+        };
+
+        assert_eq!(
+            headline(1, &card(summer)),
+            "1️⃣ ⏰ Tue 21 19:30 📍 Sports Center A"
+        );
+        assert_eq!(
+            headline(1, &card(winter)),
+            "1️⃣ ⏰ Tue 28 18:30 📍 Sports Center A"
+        );
+
+        let tokyo = venue("Tokyo Court").with_timezone(Tz::Asia__Tokyo);
+        let card = SessionCard::new(card(winter).session, tokyo, Vec::new());
+        assert_eq!(headline(1, &card), "1️⃣ ⏰ Wed 29 03:30 📍 Tokyo Court");
+    }
+
+    #[test]
+    fn card_orders_players_by_sign_up() {
+        let mut card = full_social_card();
+        card.players.reverse();
+        let card = SessionCard::new(card.session, card.venue, card.players);
+        assert_eq!(card.players[0].1.first_name, "Ana");
+    }
+
+    #[test]
+    fn session_block_with_substitutes() {
+        insta::assert_snapshot!(session_block(1, &full_social_card(), None));
+    }
+
+    #[test]
+    fn mixed_session_block_has_no_level() {
+        let session = session(
+            SessionType::Mixed,
+            Utc.with_ymd_and_hms(2025, 1, 4, 18, 0, 0).unwrap(),
+            None,
+        );
+        let players = players(&session, &SEVEN_PLAYERS[..2], 4);
+        let card = SessionCard::new(session, venue("Sports Center C"), players);
+        insta::assert_snapshot!(session_block(3, &card, None));
+    }
+
+    #[test]
+    fn session_list_in_full() {
+        let cards = [full_social_card(), open_social_card()];
+        insta::assert_snapshot!(session_list(SessionType::Social, &cards, 1024));
+    }
+
+    #[test]
+    fn session_list_truncated_with_read_more() {
+        let cards = [full_social_card(), open_social_card()];
+        let text = session_list(SessionType::Social, &cards, 300);
+        assert!(text.chars().count() <= 300);
+        insta::assert_snapshot!(text);
+    }
+
+    #[test]
+    fn session_list_empty() {
+        insta::assert_snapshot!(session_list(SessionType::League, &[], 1024));
+    }
+
+    #[test]
+    fn my_sessions_grouped_and_highlighted() {
+        let social = full_social_card();
+        let highlighted = social.players[1].1.id;
+        let league_session = session(
+            SessionType::League,
+            Utc.with_ymd_and_hms(2024, 12, 28, 18, 0, 0).unwrap(),
+            Some(SkillLevel::Advanced),
+        );
+        let mut league_players = players(&league_session, &SEVEN_PLAYERS[2..5], 4);
+        league_players[0].1 = social.players[1].1.clone();
+        league_players[0].0.user_id = highlighted;
+        let league = SessionCard::new(league_session, venue("Sports Center C"), league_players);
+
+        insta::assert_snapshot!(my_sessions(&[social, league], highlighted));
+    }
+
+    #[test]
+    fn signed_up_confirmation() {
+        let card = open_social_card();
+        let user_id = card.players[0].1.id;
+        insta::assert_snapshot!(signed_up("Eva", &card, user_id));
+    }
+
+    #[test]
+    fn time_change_on_the_same_day() {
+        let before = full_social_card().session;
+        let after = before
+            .updated(&SessionUpdate {
+                datetime: Some(before.datetime + Duration::minutes(30)),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(
+            session_changes(&before, &venue("A"), &after, &venue("A")),
+            vec!["⏰ moved from 10:00 to 10:30"]
+        );
+    }
+
+    #[test]
+    fn change_notice_lists_every_change() {
+        let card = full_social_card();
+        let before = card.session.clone();
+        let after = before
+            .updated(&SessionUpdate {
+                datetime: Some(before.datetime + Duration::days(1)),
+                duration_minutes: Some(120),
+                venue_id: Some(Uuid::new_v4()),
+                skill_level: Some(Some(SkillLevel::Advanced)),
+                ..Default::default()
+            })
+            .unwrap();
+        let changes = session_changes(
+            &before,
+            &venue("Sports Center A"),
+            &after,
+            &venue("Sports Center B"),
+        );
+        let card = SessionCard::new(after, venue("Sports Center B"), card.players);
+        insta::assert_snapshot!(session_changed(&card, &changes));
+    }
+
+    #[test]
+    fn cancellation_notice() {
+        let mut card = full_social_card();
+        card.session.status = rallybot_core::SessionStatus::Cancelled;
+        card.session.cancellation_reason = Some("Courts closed for maintenance".to_string());
+        insta::assert_snapshot!(session_cancelled(&card));
+    }
+
+    #[test]
+    fn promotion_with_a_deadline() {
+        let card = open_social_card();
+        let user_id = card.players[1].1.id;
+        let confirm_by = card.session.datetime - Duration::hours(20);
+        insta::assert_snapshot!(promoted("Filipe", &card, user_id, Some(confirm_by)));
+    }
+
+    #[test]
+    fn lapsed_promotion_notice() {
+        insta::assert_snapshot!(promotion_lapsed(&open_social_card()));
+    }
+}
@@ -0,0 +1,85 @@
+use super::{MessageSender, OutboundMessage, SendError};
+
+/// Prints outbound messages to stdout, for local development
+pub struct ConsoleSender;
+
+impl ConsoleSender {
+    fn render(message: &OutboundMessage) -> String {
+        let mut lines = vec![message.body().to_string()];
+        match message {
+            OutboundMessage::Text(_) => {}
+            OutboundMessage::List {
+                button, sections, ..
+            } => {
+                lines.push(format!("[{}]", button));
+                for section in sections {
+                    lines.push(format!("  {}", section.title));
+                    for row in &section.rows {
+                        lines.push(match &row.description {
+                            Some(description) => {
+                                format!("    {}: {} - {}", row.id, row.title, description)
+                            }
+                            None => format!("    {}: {}", row.id, row.title),
+                        });
+                    }
+                }
+            }
+            OutboundMessage::Buttons { buttons, .. } => {
+                let labels: Vec<String> = buttons
+                    .iter()
+                    .map(|b| format!("[{}: {}]", b.id, b.title))
+                    .collect();
+                lines.push(labels.join(" "));
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+#[async_trait::async_trait]
+impl MessageSender for ConsoleSender {
+    async fn send(&self, to: &str, message: &OutboundMessage) -> Result<(), SendError> {
+        println!("--- to {} ---\n{}\n", to, Self::render(message));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sender::{ListRow, ListSection, ReplyButton};
+
+    #[test]
+    fn render_list_shows_rows() {
+        let message = OutboundMessage::List {
+            body: "Pick one".to_string(),
+            button: "Sessions".to_string(),
+            sections: vec![ListSection {
+                title: "Social Games".to_string(),
+                rows: vec![ListRow {
+                    id: "1".to_string(),
+                    title: "Mon 30 10:00".to_string(),
+                    description: Some("Sports Center A".to_string()),
+                }],
+            }],
+        };
+
+        assert_eq!(
+            ConsoleSender::render(&message),
+            "Pick one\n[Sessions]\n  Social Games\n    1: Mon 30 10:00 - Sports Center A"
+        );
+    }
+
+    #[test]
+    fn render_buttons_inline() {
+        let message = OutboundMessage::Buttons {
+            body: "Sorry?".to_string(),
+            buttons: vec![ReplyButton {
+                id: "🎾".to_string(),
+                title: "Menu".to_string(),
+            }],
+        };
+
+        assert_eq!(ConsoleSender::render(&message), "Sorry?\n[🎾: Menu]");
+    }
+}
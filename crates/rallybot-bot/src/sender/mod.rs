@@ -0,0 +1,103 @@
+mod console;
+mod recording;
+mod whatsapp;
+
+pub use console::ConsoleSender;
+pub use recording::RecordingSender;
+pub use whatsapp::WhatsAppClient;
+
+// WhatsApp Cloud API limits, in characters unless noted
+pub const MAX_TEXT_BODY: usize = 4096;
+pub const MAX_INTERACTIVE_BODY: usize = 1024;
+/// Buttons per reply-button message
+pub const MAX_BUTTONS: usize = 3;
+pub const MAX_BUTTON_TITLE: usize = 20;
+/// Rows across all sections of a list message
+pub const MAX_LIST_ROWS: usize = 10;
+pub const MAX_SECTION_TITLE: usize = 24;
+pub const MAX_ROW_TITLE: usize = 24;
+pub const MAX_ROW_DESCRIPTION: usize = 72;
+
+#[derive(Debug)]
+pub enum SendError {
+    /// The request never got a response
+    Transport(String),
+    /// The API answered with a non-success status
+    Api { status: u16, body: String },
+    /// The message breaks a WhatsApp limit (e.g. more than 3 buttons)
+    Invalid(String),
+}
+
+impl std::fmt::Display for SendError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SendError::Transport(e) => write!(f, "transport error: {}", e),
+            SendError::Api { status, body } => write!(f, "API error {}: {}", status, body),
+            SendError::Invalid(e) => write!(f, "invalid message: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SendError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListRow {
+    /// Sent back as the reply text when the row is picked
+    pub id: String,
+    pub title: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListSection {
+    pub title: String,
+    pub rows: Vec<ListRow>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplyButton {
+    /// Sent back as the reply text when the button is tapped
+    pub id: String,
+    pub title: String,
+}
+
+/// A bot reply. Interactive messages carry the full text in `body` so they
+/// still read correctly where native UI is unavailable.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OutboundMessage {
+    Text(String),
+    List {
+        body: String,
+        /// Label of the button that opens the list
+        button: String,
+        sections: Vec<ListSection>,
+    },
+    Buttons {
+        body: String,
+        buttons: Vec<ReplyButton>,
+    },
+}
+
+impl OutboundMessage {
+    pub fn text(body: impl Into<String>) -> Self {
+        OutboundMessage::Text(body.into())
+    }
+
+    pub fn body(&self) -> &str {
+        match self {
+            OutboundMessage::Text(body)
+            | OutboundMessage::List { body, .. }
+            | OutboundMessage::Buttons { body, .. } => body,
+        }
+    }
+}
+
+/// Delivers bot replies to players
+#[async_trait::async_trait]
+pub trait MessageSender: Send + Sync {
+    async fn send(&self, to: &str, message: &OutboundMessage) -> Result<(), SendError>;
+
+    async fn send_text(&self, to: &str, body: &str) -> Result<(), SendError> {
+        self.send(to, &OutboundMessage::text(body)).await
+    }
+}
@@ -0,0 +1,52 @@
+use super::{MessageSender, OutboundMessage, SendError};
+use std::sync::Mutex;
+
+/// Keeps every message it is asked to send, for tests
+#[derive(Default)]
+pub struct RecordingSender {
+    sent: Mutex<Vec<(String, OutboundMessage)>>,
+}
+
+impl RecordingSender {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Recipient and message of everything sent so far, oldest first
+    pub fn sent(&self) -> Vec<(String, OutboundMessage)> {
+        self.sent.lock().unwrap().clone()
+    }
+
+    pub fn sent_to(&self, to: &str) -> Vec<OutboundMessage> {
+        self.sent
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(recipient, _)| recipient == to)
+            .map(|(_, message)| message.clone())
+            .collect()
+    }
+
+    /// Like [`sent_to`](Self::sent_to), but first gives messages sent in the
+    /// background, e.g. by an event subscriber, up to a second to reach `count`
+    pub async fn wait_for(&self, to: &str, count: usize) -> Vec<OutboundMessage> {
+        for _ in 0..100 {
+            if self.sent_to(to).len() >= count {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        self.sent_to(to)
+    }
+}
+
+#[async_trait::async_trait]
+impl MessageSender for RecordingSender {
+    async fn send(&self, to: &str, message: &OutboundMessage) -> Result<(), SendError> {
+        self.sent
+            .lock()
+            .unwrap()
+            .push((to.to_string(), message.clone()));
+        Ok(())
+    }
+}
@@ -0,0 +1,354 @@
+use super::{
+    ListSection, MessageSender, OutboundMessage, ReplyButton, SendError, MAX_BUTTONS,
+    MAX_BUTTON_TITLE, MAX_INTERACTIVE_BODY, MAX_LIST_ROWS, MAX_ROW_DESCRIPTION, MAX_ROW_TITLE,
+    MAX_SECTION_TITLE, MAX_TEXT_BODY,
+};
+use serde_json::{json, Value};
+
+const GRAPH_API_URL: &str = "https://graph.facebook.com";
+const GRAPH_API_VERSION: &str = "v21.0";
+
+/// Sends messages through the WhatsApp Cloud (Graph) API
+pub struct WhatsAppClient {
+    http: reqwest::Client,
+    base_url: String,
+    phone_number_id: String,
+    access_token: String,
+}
+
+impl WhatsAppClient {
+    pub fn new(phone_number_id: String, access_token: String) -> Self {
+        Self::with_base_url(GRAPH_API_URL.to_string(), phone_number_id, access_token)
+    }
+
+    /// Point the client at another host, e.g. a local mock server
+    pub fn with_base_url(base_url: String, phone_number_id: String, access_token: String) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            phone_number_id,
+            access_token,
+        }
+    }
+
+    fn messages_url(&self) -> String {
+        format!(
+            "{}/{}/{}/messages",
+            self.base_url, GRAPH_API_VERSION, self.phone_number_id
+        )
+    }
+
+    /// Graph API request body for a message
+    pub fn payload(to: &str, message: &OutboundMessage) -> Result<Value, SendError> {
+        let content = match message {
+            OutboundMessage::Text(body) => {
+                check_len("text body", body, MAX_TEXT_BODY)?;
+                json!({
+                    "type": "text",
+                    "text": { "preview_url": false, "body": body },
+                })
+            }
+            OutboundMessage::List {
+                body,
+                button,
+                sections,
+            } => {
+                check_len("body", body, MAX_INTERACTIVE_BODY)?;
+                check_len("list button", button, MAX_BUTTON_TITLE)?;
+                json!({
+                    "type": "interactive",
+                    "interactive": {
+                        "type": "list",
+                        "body": { "text": body },
+                        "action": { "button": button, "sections": list_sections(sections)? },
+                    },
+                })
+            }
+            OutboundMessage::Buttons { body, buttons } => {
+                check_len("body", body, MAX_INTERACTIVE_BODY)?;
+                json!({
+                    "type": "interactive",
+                    "interactive": {
+                        "type": "button",
+                        "body": { "text": body },
+                        "action": { "buttons": reply_buttons(buttons)? },
+                    },
+                })
+            }
+        };
+
+        let mut payload = json!({
+            "messaging_product": "whatsapp",
+            "recipient_type": "individual",
+            // The API wants the bare international number
+            "to": to.trim_start_matches('+'),
+        });
+        if let (Some(payload), Value::Object(content)) = (payload.as_object_mut(), content) {
+            payload.extend(content);
+        }
+        Ok(payload)
+    }
+}
+
+fn check_len(field: &str, value: &str, max: usize) -> Result<(), SendError> {
+    let len = value.chars().count();
+    if len > max {
+        return Err(SendError::Invalid(format!(
+            "{} is {} characters, the limit is {}",
+            field, len, max
+        )));
+    }
+    Ok(())
+}
+
+fn list_sections(sections: &[ListSection]) -> Result<Value, SendError> {
+    let row_count: usize = sections.iter().map(|s| s.rows.len()).sum();
+    if row_count == 0 || row_count > MAX_LIST_ROWS {
+        return Err(SendError::Invalid(format!(
+            "a list needs 1 to {} rows, got {}",
+            MAX_LIST_ROWS, row_count
+        )));
+    }
+
+    let mut values = Vec::new();
+    for section in sections {
+        let mut rows = Vec::new();
+        for row in &section.rows {
+            check_len("row title", &row.title, MAX_ROW_TITLE)?;
+            let mut value = json!({ "id": row.id, "title": row.title });
+            if let Some(description) = &row.description {
+                check_len("row description", description, MAX_ROW_DESCRIPTION)?;
+                value["description"] = json!(description);
+            }
+            rows.push(value);
+        }
+        check_len("section title", &section.title, MAX_SECTION_TITLE)?;
+        values.push(json!({ "title": section.title, "rows": rows }));
+    }
+    Ok(Value::Array(values))
+}
+
+fn reply_buttons(buttons: &[ReplyButton]) -> Result<Value, SendError> {
+    if buttons.is_empty() || buttons.len() > MAX_BUTTONS {
+        return Err(SendError::Invalid(format!(
+            "a button message needs 1 to {} buttons, got {}",
+            MAX_BUTTONS,
+            buttons.len()
+        )));
+    }
+
+    let mut values = Vec::new();
+    for button in buttons {
+        check_len("button title", &button.title, MAX_BUTTON_TITLE)?;
+        values.push(json!({
+            "type": "reply",
+            "reply": { "id": button.id, "title": button.title },
+        }));
+    }
+    Ok(Value::Array(values))
+}
+
+#[async_trait::async_trait]
+impl MessageSender for WhatsAppClient {
+    async fn send(&self, to: &str, message: &OutboundMessage) -> Result<(), SendError> {
+        let payload = Self::payload(to, message)?;
+
+        let response = self
+            .http
+            .post(self.messages_url())
+            .bearer_auth(&self.access_token)
+            .json(&payload)
+            .send()
+            .await
+            .map_err(|e| SendError::Transport(e.to_string()))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(SendError::Api {
+                status: status.as_u16(),
+                body,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sender::ListRow;
+    use wiremock::{
+        matchers::{body_json, header, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    fn client(server: &MockServer) -> WhatsAppClient {
+        WhatsAppClient::with_base_url(
+            server.uri(),
+            "106540352242922".to_string(),
+            "test-token".to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn sends_text_with_bearer_token() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v21.0/106540352242922/messages"))
+            .and(header("authorization", "Bearer test-token"))
+            .and(body_json(json!({
+                "messaging_product": "whatsapp",
+                "recipient_type": "individual",
+                "to": "351911111111",
+                "type": "text",
+                "text": { "preview_url": false, "body": "Hello!" },
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "messages": [{ "id": "wamid.abc" }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        client(&server)
+            .send_text("+351911111111", "Hello!")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn sends_interactive_list() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v21.0/106540352242922/messages"))
+            .and(body_json(json!({
+                "messaging_product": "whatsapp",
+                "recipient_type": "individual",
+                "to": "351911111111",
+                "type": "interactive",
+                "interactive": {
+                    "type": "list",
+                    "body": { "text": "Pick a session" },
+                    "action": {
+                        "button": "Sessions",
+                        "sections": [{
+                            "title": "Social Games",
+                            "rows": [
+                                { "id": "1", "title": "Mon 30 10:00", "description": "Sports Center A" },
+                                { "id": "2", "title": "Tue 1 11:30" },
+                            ],
+                        }],
+                    },
+                },
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let message = OutboundMessage::List {
+            body: "Pick a session".to_string(),
+            button: "Sessions".to_string(),
+            sections: vec![ListSection {
+                title: "Social Games".to_string(),
+                rows: vec![
+                    ListRow {
+                        id: "1".to_string(),
+                        title: "Mon 30 10:00".to_string(),
+                        description: Some("Sports Center A".to_string()),
+                    },
+                    ListRow {
+                        id: "2".to_string(),
+                        title: "Tue 1 11:30".to_string(),
+                        description: None,
+                    },
+                ],
+            }],
+        };
+
+        client(&server)
+            .send("+351911111111", &message)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn sends_reply_buttons() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_json(json!({
+                "messaging_product": "whatsapp",
+                "recipient_type": "individual",
+                "to": "351911111111",
+                "type": "interactive",
+                "interactive": {
+                    "type": "button",
+                    "body": { "text": "Sorry?" },
+                    "action": {
+                        "buttons": [{ "type": "reply", "reply": { "id": "🎾", "title": "Menu" } }],
+                    },
+                },
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let message = OutboundMessage::Buttons {
+            body: "Sorry?".to_string(),
+            buttons: vec![ReplyButton {
+                id: "🎾".to_string(),
+                title: "Menu".to_string(),
+            }],
+        };
+
+        client(&server)
+            .send("+351911111111", &message)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn api_error_is_surfaced() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(401).set_body_string("invalid token"))
+            .mount(&server)
+            .await;
+
+        let result = client(&server).send_text("+351911111111", "Hello!").await;
+
+        match result {
+            Err(SendError::Api { status, body }) => {
+                assert_eq!(status, 401);
+                assert_eq!(body, "invalid token");
+            }
+            other => panic!("expected API error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn too_many_buttons_is_rejected_before_sending() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let buttons = (0..4)
+            .map(|i| ReplyButton {
+                id: i.to_string(),
+                title: format!("Option {}", i),
+            })
+            .collect();
+        let message = OutboundMessage::Buttons {
+            body: "Choose".to_string(),
+            buttons,
+        };
+
+        let result = client(&server).send("+351911111111", &message).await;
+        assert!(matches!(result, Err(SendError::Invalid(_))));
+    }
+}
@@ -0,0 +1,336 @@
+use crate::user::{Gender, LookingFor, PlayFrequency, PreferredSide, SkillLevel, User, UserStatus};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Where a membership application is. Approving one creates the member.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "application_status")]
+pub enum ApplicationStatus {
+    #[default]
+    #[sqlx(rename = "Pending")]
+    Pending,
+    #[sqlx(rename = "Approved")]
+    Approved,
+    #[sqlx(rename = "Rejected")]
+    Rejected,
+}
+
+impl ApplicationStatus {
+    /// Pending applications can be approved or rejected, and a rejection
+    /// reconsidered. An approved application is final.
+    pub fn can_become(self, to: ApplicationStatus) -> bool {
+        matches!(
+            (self, to),
+            (ApplicationStatus::Pending, ApplicationStatus::Approved)
+                | (ApplicationStatus::Pending, ApplicationStatus::Rejected)
+                | (ApplicationStatus::Rejected, ApplicationStatus::Approved)
+        )
+    }
+}
+
+/// A field that failed validation, e.g. `{"field": "email", "reason": ...}`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InvalidField {
+    pub field: &'static str,
+    pub reason: &'static str,
+}
+
+/// Someone asking to join. Carries the details the member will be created
+/// with once an admin approves it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Application {
+    pub id: Uuid,
+    pub first_name: String,
+    pub last_name: String,
+    pub phone_number: String,
+    pub email: String,
+    pub city: String,
+    pub photo_url: Option<String>,
+    pub occupation: String,
+    pub company: String,
+    pub industry: String,
+    pub linkedin_url: String,
+    pub gender: Gender,
+    pub skill_levels: Vec<SkillLevel>,
+    pub preferred_side: PreferredSide,
+    pub play_frequency: PlayFrequency,
+    pub looking_for: Vec<LookingFor>,
+    pub status: ApplicationStatus,
+    /// The member created on approval
+    pub user_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Application {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        first_name: String,
+        last_name: String,
+        phone_number: String,
+        email: String,
+        city: String,
+        photo_url: Option<String>,
+        occupation: String,
+        company: String,
+        industry: String,
+        linkedin_url: String,
+        gender: Gender,
+        skill_levels: Vec<SkillLevel>,
+        preferred_side: PreferredSide,
+        play_frequency: PlayFrequency,
+        looking_for: Vec<LookingFor>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            first_name,
+            last_name,
+            phone_number,
+            email,
+            city,
+            photo_url,
+            occupation,
+            company,
+            industry,
+            linkedin_url,
+            gender,
+            skill_levels,
+            preferred_side,
+            play_frequency,
+            looking_for,
+            status: ApplicationStatus::Pending,
+            user_id: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Every field that is not acceptable, in form order
+    pub fn validate(&self) -> Result<(), Vec<InvalidField>> {
+        let mut invalid = Vec::new();
+        let mut check = |field, ok: bool, reason| {
+            if !ok {
+                invalid.push(InvalidField { field, reason });
+            }
+        };
+        check(
+            "phone_number",
+            is_phone_number(&self.phone_number),
+            "must be in international format, e.g. +351912345678",
+        );
+        check("email", is_email(&self.email), "must be an email address");
+        check(
+            "linkedin_url",
+            is_linkedin_profile(&self.linkedin_url),
+            "must be a LinkedIn profile, e.g. https://www.linkedin.com/in/name",
+        );
+        check(
+            "skill_levels",
+            !self.skill_levels.is_empty(),
+            "must include at least one level",
+        );
+
+        if invalid.is_empty() {
+            Ok(())
+        } else {
+            Err(invalid)
+        }
+    }
+
+    /// The approved member this application becomes
+    pub fn to_user(&self) -> User {
+        let mut user = User::new(
+            self.first_name.clone(),
+            self.last_name.clone(),
+            self.phone_number.clone(),
+            self.email.clone(),
+            self.city.clone(),
+            self.photo_url.clone(),
+            self.occupation.clone(),
+            self.company.clone(),
+            self.industry.clone(),
+            self.linkedin_url.clone(),
+            self.gender,
+            self.skill_levels.clone(),
+            self.preferred_side,
+            self.play_frequency,
+            self.looking_for.clone(),
+        );
+        user.status = UserStatus::Approved;
+        user
+    }
+}
+
+/// Audit record of an admin deciding on an application
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApplicationStatusChange {
+    pub id: Uuid,
+    pub application_id: Uuid,
+    pub from_status: ApplicationStatus,
+    pub to_status: ApplicationStatus,
+    /// Who decided, e.g. the admin's email
+    pub changed_by: String,
+    pub reason: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
+
+impl ApplicationStatusChange {
+    pub fn new(
+        application: &Application,
+        to_status: ApplicationStatus,
+        changed_by: String,
+        reason: Option<String>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            application_id: application.id,
+            from_status: application.status,
+            to_status,
+            changed_by,
+            reason,
+            changed_at: Utc::now(),
+        }
+    }
+}
+
+/// E.164: a plus sign and 8 to 15 digits, the first not zero
+fn is_phone_number(phone: &str) -> bool {
+    phone.strip_prefix('+').is_some_and(|digits| {
+        (8..=15).contains(&digits.len())
+            && digits.chars().all(|c| c.is_ascii_digit())
+            && !digits.starts_with('0')
+    })
+}
+
+/// One `@` with something before it and a dotted domain after it
+fn is_email(email: &str) -> bool {
+    let Some((local, domain)) = email.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !email.chars().any(char::is_whitespace)
+        && !domain.contains('@')
+        && domain.split('.').count() >= 2
+        && domain.split('.').all(|label| !label.is_empty())
+}
+
+/// `https://[country.|www.]linkedin.com/in/<handle>`, optionally with a
+/// trailing slash
+fn is_linkedin_profile(url: &str) -> bool {
+    let Some(rest) = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+    else {
+        return false;
+    };
+    let Some((host, path)) = rest.split_once('/') else {
+        return false;
+    };
+    let host_ok = host == "linkedin.com"
+        || host
+            .strip_suffix(".linkedin.com")
+            .is_some_and(|sub| !sub.is_empty() && sub.chars().all(|c| c.is_ascii_alphabetic()));
+    let handle = path
+        .strip_prefix("in/")
+        .map(|handle| handle.strip_suffix('/').unwrap_or(handle));
+    host_ok
+        && handle.is_some_and(|handle| {
+            !handle.is_empty()
+                && handle
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '%'))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn application() -> Application {
+        Application::new(
+            "Ana".to_string(),
+            "Silva".to_string(),
+            "+351912345678".to_string(),
+            "ana@example.com".to_string(),
+            "Lisbon".to_string(),
+            None,
+            "Engineer".to_string(),
+            "Acme".to_string(),
+            "Software".to_string(),
+            "https://www.linkedin.com/in/ana-silva".to_string(),
+            Gender::Female,
+            vec![SkillLevel::Intermediate],
+            PreferredSide::Left,
+            PlayFrequency::OnceWeek,
+            vec![LookingFor::SocialConnections],
+        )
+    }
+
+    #[test]
+    fn valid_application_passes() {
+        assert_eq!(application().validate(), Ok(()));
+    }
+
+    #[test]
+    fn every_invalid_field_is_reported() {
+        let mut bad = application();
+        bad.phone_number = "912 345 678".to_string();
+        bad.email = "ana@localhost".to_string();
+        bad.linkedin_url = "https://linkedin.com/company/acme".to_string();
+        bad.skill_levels.clear();
+
+        let fields: Vec<_> = bad.validate().unwrap_err().iter().map(|f| f.field).collect();
+        assert_eq!(fields, vec!["phone_number", "email", "linkedin_url", "skill_levels"]);
+    }
+
+    #[test]
+    fn phone_numbers() {
+        assert!(is_phone_number("+351912345678"));
+        assert!(is_phone_number("+14155552671"));
+        assert!(!is_phone_number("351912345678"));
+        assert!(!is_phone_number("+0351912345"));
+        assert!(!is_phone_number("+3519"));
+        assert!(!is_phone_number("+351 912 345 678"));
+    }
+
+    #[test]
+    fn emails() {
+        assert!(is_email("ana.silva+padel@mail.example.pt"));
+        assert!(!is_email("ana"));
+        assert!(!is_email("@example.com"));
+        assert!(!is_email("ana@example"));
+        assert!(!is_email("ana@example..com"));
+        assert!(!is_email("ana silva@example.com"));
+        assert!(!is_email("ana@b@example.com"));
+    }
+
+    #[test]
+    fn linkedin_urls() {
+        assert!(is_linkedin_profile("https://www.linkedin.com/in/ana-silva"));
+        assert!(is_linkedin_profile("https://linkedin.com/in/ana_silva/"));
+        assert!(is_linkedin_profile("https://pt.linkedin.com/in/ana"));
+        assert!(!is_linkedin_profile("linkedin.com/in/ana"));
+        assert!(!is_linkedin_profile("https://linkedin.com/in/"));
+        assert!(!is_linkedin_profile("https://evil-linkedin.com/in/ana"));
+        assert!(!is_linkedin_profile("https://linkedin.com/in/ana/details"));
+    }
+
+    #[test]
+    fn approval_is_final() {
+        use ApplicationStatus::*;
+        assert!(Pending.can_become(Approved));
+        assert!(Pending.can_become(Rejected));
+        assert!(Rejected.can_become(Approved));
+        assert!(!Approved.can_become(Rejected));
+        assert!(!Rejected.can_become(Rejected));
+    }
+
+    #[test]
+    fn approved_application_becomes_an_approved_member() {
+        let application = application();
+        let user = application.to_user();
+        assert!(user.is_approved());
+        assert_eq!(user.phone_number, application.phone_number);
+        assert_eq!(user.skill_levels, application.skill_levels);
+    }
+}
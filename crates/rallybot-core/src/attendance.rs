@@ -0,0 +1,113 @@
+use crate::{models::Session, registration::Registration};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// How long before the start players can check in
+pub const CHECK_IN_OPENS_MINUTES: i64 = 30;
+
+/// Whether a confirmed player turned up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "attendance_status")]
+pub enum AttendanceStatus {
+    #[sqlx(rename = "Attended")]
+    Attended,
+    #[sqlx(rename = "NoShow")]
+    NoShow,
+    /// Missed it with the organiser's blessing; doesn't count against them
+    #[sqlx(rename = "Excused")]
+    Excused,
+}
+
+/// Attendance of one registration. Recording it again replaces it, so an
+/// organiser can correct a check-in.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Attendance {
+    pub registration_id: Uuid,
+    pub session_id: Uuid,
+    pub user_id: Uuid,
+    pub status: AttendanceStatus,
+    /// The organiser who marked it, or the player's phone number when they
+    /// checked in themselves
+    pub recorded_by: String,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl Attendance {
+    pub fn new(registration: &Registration, status: AttendanceStatus, recorded_by: String) -> Self {
+        Self {
+            registration_id: registration.id,
+            session_id: registration.session_id,
+            user_id: registration.user_id,
+            status,
+            recorded_by,
+            recorded_at: Utc::now(),
+        }
+    }
+}
+
+/// How often a player turned up to the sessions they were marked for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct AttendanceStats {
+    pub user_id: Uuid,
+    pub attended: usize,
+    pub no_show: usize,
+    pub excused: usize,
+}
+
+impl AttendanceStats {
+    pub fn new(user_id: Uuid, records: &[Attendance]) -> Self {
+        let count = |status| records.iter().filter(|a| a.status == status).count();
+        Self {
+            user_id,
+            attended: count(AttendanceStatus::Attended),
+            no_show: count(AttendanceStatus::NoShow),
+            excused: count(AttendanceStatus::Excused),
+        }
+    }
+}
+
+/// Players can check in from shortly before the start until the end
+pub fn check_in_open(session: &Session, now: DateTime<Utc>) -> bool {
+    let opens = session.datetime - Duration::minutes(CHECK_IN_OPENS_MINUTES);
+    session.is_scheduled() && opens <= now && now < session.ends_at()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{models::SessionType, registration::RegistrationStatus};
+
+    #[test]
+    fn check_in_window() {
+        let start = Utc::now();
+        let session = Session::new(SessionType::Mixed, start, 90, Uuid::new_v4(), None, Session::DEFAULT_MAX_PLAYERS, None).unwrap();
+
+        assert!(!check_in_open(&session, start - Duration::minutes(31)));
+        assert!(check_in_open(&session, start - Duration::minutes(30)));
+        assert!(check_in_open(&session, start + Duration::minutes(89)));
+        assert!(!check_in_open(&session, start + Duration::minutes(90)));
+    }
+
+    #[test]
+    fn stats_count_each_status() {
+        let user_id = Uuid::new_v4();
+        let records: Vec<_> = [
+            AttendanceStatus::Attended,
+            AttendanceStatus::NoShow,
+            AttendanceStatus::Attended,
+            AttendanceStatus::Excused,
+        ]
+        .into_iter()
+        .map(|status| {
+            let registration =
+                Registration::new(user_id, Uuid::new_v4(), RegistrationStatus::Confirmed);
+            Attendance::new(&registration, status, "admin".to_string())
+        })
+        .collect();
+
+        let stats = AttendanceStats::new(user_id, &records);
+        assert_eq!((stats.attended, stats.no_show, stats.excused), (2, 1, 1));
+    }
+}
@@ -0,0 +1,74 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::ops::Range;
+
+/// The instant a wall-clock time happens in `tz`. When the clocks go back the
+/// earlier of the two is used; a time skipped when they go forward happens an
+/// hour later, as it does on the wall clock.
+pub fn at_local(tz: Tz, local: NaiveDateTime) -> DateTime<Utc> {
+    tz.from_local_datetime(&local)
+        .earliest()
+        .or_else(|| tz.from_local_datetime(&(local + Duration::hours(1))).earliest())
+        .map(|datetime| datetime.with_timezone(&Utc))
+        // No zone skips more than an hour at a time
+        .unwrap_or_else(|| local.and_utc())
+}
+
+fn start_of_day(tz: Tz, date: NaiveDate) -> DateTime<Utc> {
+    at_local(tz, date.and_hms_opt(0, 0, 0).unwrap())
+}
+
+/// Monday 00:00 to the next Monday 00:00 in `tz`, for the week containing
+/// `now` (`weeks_ahead` 0) or one after it. A week with a DST change is an
+/// hour shorter or longer.
+pub fn local_week(tz: Tz, now: DateTime<Utc>, weeks_ahead: u32) -> Range<DateTime<Utc>> {
+    let today = now.with_timezone(&tz).date_naive();
+    let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64)
+        + Duration::weeks(weeks_ahead as i64);
+    start_of_day(tz, monday)..start_of_day(tz, monday + Duration::weeks(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LISBON: Tz = Tz::Europe__Lisbon;
+
+    #[test]
+    fn week_runs_monday_to_monday_local_time() {
+        // Sunday 7 September 2025, 23:30 in Lisbon (UTC+1)
+        let now = Utc.with_ymd_and_hms(2025, 9, 7, 22, 30, 0).unwrap();
+        let week = local_week(LISBON, now, 0);
+
+        assert_eq!(week.start, Utc.with_ymd_and_hms(2025, 8, 31, 23, 0, 0).unwrap());
+        assert_eq!(week.end, Utc.with_ymd_and_hms(2025, 9, 7, 23, 0, 0).unwrap());
+        assert!(week.contains(&now));
+
+        let next = local_week(LISBON, now, 1);
+        assert_eq!(next.start, week.end);
+    }
+
+    #[test]
+    fn week_with_dst_change_is_an_hour_longer() {
+        // Clocks go back on Sunday 26 October 2025
+        let now = Utc.with_ymd_and_hms(2025, 10, 22, 12, 0, 0).unwrap();
+        let week = local_week(LISBON, now, 0);
+
+        assert_eq!(week.start, Utc.with_ymd_and_hms(2025, 10, 19, 23, 0, 0).unwrap());
+        assert_eq!(week.end, Utc.with_ymd_and_hms(2025, 10, 27, 0, 0, 0).unwrap());
+        assert_eq!(week.end - week.start, Duration::hours(7 * 24 + 1));
+    }
+
+    #[test]
+    fn skipped_local_time_moves_forward() {
+        // 01:30 doesn't exist in Lisbon on 30 March 2025
+        let local = NaiveDate::from_ymd_opt(2025, 3, 30)
+            .unwrap()
+            .and_hms_opt(1, 30, 0)
+            .unwrap();
+        assert_eq!(
+            at_local(LISBON, local),
+            Utc.with_ymd_and_hms(2025, 3, 30, 1, 30, 0).unwrap()
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use crate::models::{Session, SessionType};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// How many late cancellations a player may have before they can no longer
+/// sign up themselves
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LateCancelLimit {
+    /// Sign-ups are refused once the player has this many
+    pub max: usize,
+    /// Only late cancellations this recent count
+    pub within: Duration,
+}
+
+/// How close to the start a player can still drop out without it counting
+/// against them, by session type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CancellationPolicy {
+    pub coaching: Duration,
+    pub social: Duration,
+    pub league: Duration,
+    pub mixed: Duration,
+    /// No limit unless set
+    pub limit: Option<LateCancelLimit>,
+}
+
+impl Default for CancellationPolicy {
+    /// Coaching and league groups are hard to fill at short notice; a social
+    /// game usually finds someone
+    fn default() -> Self {
+        Self {
+            coaching: Duration::hours(12),
+            social: Duration::hours(2),
+            league: Duration::hours(12),
+            mixed: Duration::hours(2),
+            limit: None,
+        }
+    }
+}
+
+impl CancellationPolicy {
+    pub fn cutoff_for(&self, session_type: SessionType) -> Duration {
+        match session_type {
+            SessionType::Coaching => self.coaching,
+            SessionType::Social => self.social,
+            SessionType::League => self.league,
+            SessionType::Mixed => self.mixed,
+        }
+    }
+
+    /// Dropping out of `session` at `now` is past its cutoff. Only scheduled
+    /// sessions count; leaving one that was cancelled is never late.
+    pub fn is_late(&self, session: &Session, now: DateTime<Utc>) -> bool {
+        session.is_scheduled() && now > session.datetime - self.cutoff_for(session.session_type)
+    }
+}
+
+/// A confirmed player dropping out after the session's cutoff
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LateCancellation {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub session_id: Uuid,
+    pub session_starts_at: DateTime<Utc>,
+    pub cancelled_at: DateTime<Utc>,
+}
+
+impl LateCancellation {
+    pub fn new(user_id: Uuid, session: &Session, cancelled_at: DateTime<Utc>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            session_id: session.id,
+            session_starts_at: session.datetime,
+            cancelled_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(session_type: SessionType, starts_in: Duration) -> Session {
+        let level = Some(crate::user::SkillLevel::Intermediate);
+        Session::new(session_type, Utc::now() + starts_in, 90, Uuid::new_v4(), level, Session::DEFAULT_MAX_PLAYERS, None).unwrap()
+    }
+
+    #[test]
+    fn cutoff_depends_on_session_type() {
+        let policy = CancellationPolicy::default();
+        let now = Utc::now();
+
+        let coaching = session(SessionType::Coaching, Duration::hours(6));
+        assert!(policy.is_late(&coaching, now));
+        let social = session(SessionType::Social, Duration::hours(6));
+        assert!(!policy.is_late(&social, now));
+        let social = session(SessionType::Social, Duration::hours(1));
+        assert!(policy.is_late(&social, now));
+    }
+
+    #[test]
+    fn leaving_a_cancelled_session_is_never_late() {
+        let policy = CancellationPolicy::default();
+        let mut league = session(SessionType::League, Duration::hours(1));
+        league.status = crate::models::SessionStatus::Cancelled;
+        assert!(!policy.is_late(&league, Utc::now()));
+    }
+}
@@ -0,0 +1,104 @@
+use crate::models::SessionType;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Which bot screen was last shown to a user
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "conversation_menu")]
+pub enum ConversationMenu {
+    #[sqlx(rename = "MainMenu")]
+    MainMenu,
+    #[sqlx(rename = "SessionList")]
+    SessionList,
+    #[sqlx(rename = "MySessions")]
+    MySessions,
+}
+
+/// What the bot last showed a user, so a numbered reply can be resolved to a session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationState {
+    pub phone_number: String,
+    pub menu: ConversationMenu,
+    pub session_type: Option<SessionType>,
+    /// Session ids in the order they were numbered (1️⃣ is index 0)
+    pub session_ids: Vec<Uuid>,
+    pub expires_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ConversationState {
+    pub fn new(
+        phone_number: String,
+        menu: ConversationMenu,
+        session_type: Option<SessionType>,
+        session_ids: Vec<Uuid>,
+        ttl: Duration,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            phone_number,
+            menu,
+            session_type,
+            session_ids,
+            expires_at: now + ttl,
+            updated_at: now,
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= Utc::now()
+    }
+
+    /// Session id behind the 1-based number shown to the user
+    pub fn session_at(&self, number: usize) -> Option<Uuid> {
+        number
+            .checked_sub(1)
+            .and_then(|i| self.session_ids.get(i))
+            .copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_at_is_one_based() {
+        let ids = vec![Uuid::new_v4(), Uuid::new_v4()];
+        let state = ConversationState::new(
+            "+351911111111".to_string(),
+            ConversationMenu::SessionList,
+            Some(SessionType::Social),
+            ids.clone(),
+            Duration::hours(1),
+        );
+
+        assert_eq!(state.session_at(0), None);
+        assert_eq!(state.session_at(1), Some(ids[0]));
+        assert_eq!(state.session_at(2), Some(ids[1]));
+        assert_eq!(state.session_at(3), None);
+    }
+
+    #[test]
+    fn expiry() {
+        let state = ConversationState::new(
+            "+351911111111".to_string(),
+            ConversationMenu::MainMenu,
+            None,
+            vec![],
+            Duration::hours(1),
+        );
+        assert!(!state.is_expired());
+
+        let expired = ConversationState::new(
+            "+351911111111".to_string(),
+            ConversationMenu::MainMenu,
+            None,
+            vec![],
+            Duration::seconds(-1),
+        );
+        assert!(expired.is_expired());
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{
+    models::{Session, SessionType},
+    user::{SkillLevel, User},
+};
+
+/// How close a player's level has to be to the session's
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkillRule {
+    /// One of the player's levels is the session's
+    Exact,
+    /// One of the player's levels is at most this many levels either side
+    Within(u8),
+    /// Anyone can join
+    Any,
+}
+
+impl SkillRule {
+    fn allows(self, player: SkillLevel, session: SkillLevel) -> bool {
+        match self {
+            SkillRule::Exact => player == session,
+            SkillRule::Within(levels) => player.distance(session) <= levels,
+            SkillRule::Any => true,
+        }
+    }
+}
+
+/// Which players may sign up for which sessions, by session type. Mixed
+/// sessions have no skill level and are open to everyone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EligibilityPolicy {
+    pub coaching: SkillRule,
+    pub social: SkillRule,
+    pub league: SkillRule,
+}
+
+impl Default for EligibilityPolicy {
+    /// Coaching and league groups are built around one level; social games
+    /// tolerate a level either way
+    fn default() -> Self {
+        Self {
+            coaching: SkillRule::Exact,
+            social: SkillRule::Within(1),
+            league: SkillRule::Exact,
+        }
+    }
+}
+
+impl EligibilityPolicy {
+    pub fn rule_for(&self, session_type: SessionType) -> SkillRule {
+        match session_type {
+            SessionType::Coaching => self.coaching,
+            SessionType::Social => self.social,
+            SessionType::League => self.league,
+            SessionType::Mixed => SkillRule::Any,
+        }
+    }
+
+    pub fn allows(&self, user: &User, session: &Session) -> bool {
+        let Some(level) = session.skill_level else {
+            return true;
+        };
+        let rule = self.rule_for(session.session_type);
+        rule == SkillRule::Any || user.skill_levels.iter().any(|&own| rule.allows(own, level))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::user::{Gender, LookingFor, PlayFrequency, PreferredSide};
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn player(levels: Vec<SkillLevel>) -> User {
+        User::new(
+            "Ana".to_string(),
+            "Silva".to_string(),
+            "+351911111111".to_string(),
+            "ana@example.com".to_string(),
+            "Lisbon".to_string(),
+            None,
+            "Engineer".to_string(),
+            "Acme".to_string(),
+            "Software".to_string(),
+            "https://linkedin.com/in/ana".to_string(),
+            Gender::Female,
+            levels,
+            PreferredSide::Left,
+            PlayFrequency::OnceWeek,
+            vec![LookingFor::SocialConnections],
+        )
+    }
+
+    fn session(session_type: SessionType, level: Option<SkillLevel>) -> Session {
+        Session::new(session_type, Utc::now(), 90, Uuid::new_v4(), level, Session::DEFAULT_MAX_PLAYERS, None).unwrap()
+    }
+
+    #[test]
+    fn default_policy_by_session_type() {
+        let policy = EligibilityPolicy::default();
+        let intermediate = player(vec![SkillLevel::Intermediate]);
+
+        let coaching = session(SessionType::Coaching, Some(SkillLevel::UpperIntermediate));
+        assert!(!policy.allows(&intermediate, &coaching));
+
+        let social = session(SessionType::Social, Some(SkillLevel::UpperIntermediate));
+        assert!(policy.allows(&intermediate, &social));
+        let social = session(SessionType::Social, Some(SkillLevel::Advanced));
+        assert!(!policy.allows(&intermediate, &social));
+
+        let mixed = session(SessionType::Mixed, None);
+        assert!(policy.allows(&intermediate, &mixed));
+    }
+
+    #[test]
+    fn any_of_the_players_levels_counts() {
+        let policy = EligibilityPolicy::default();
+        let league = session(SessionType::League, Some(SkillLevel::Advanced));
+
+        assert!(!policy.allows(&player(vec![SkillLevel::Beginner]), &league));
+        assert!(policy.allows(
+            &player(vec![SkillLevel::Beginner, SkillLevel::Advanced]),
+            &league
+        ));
+        assert!(!policy.allows(&player(Vec::new()), &league));
+    }
+
+    #[test]
+    fn within_rule_counts_levels_either_side() {
+        let policy = EligibilityPolicy {
+            social: SkillRule::Within(2),
+            ..EligibilityPolicy::default()
+        };
+        let social = session(SessionType::Social, Some(SkillLevel::Advanced));
+
+        assert!(policy.allows(&player(vec![SkillLevel::Intermediate]), &social));
+        assert!(policy.allows(&player(vec![SkillLevel::Expert]), &social));
+        assert!(!policy.allows(&player(vec![SkillLevel::LowIntermediate]), &social));
+        assert!(!policy.allows(&player(vec![SkillLevel::Elite]), &social));
+    }
+}
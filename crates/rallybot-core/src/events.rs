@@ -0,0 +1,146 @@
+use crate::{models::Session, registration::Registration, user::User};
+use serde::Serialize;
+use std::sync::{Arc, RwLock};
+use tokio::sync::mpsc;
+
+/// Something that happened to the club's sessions or members, published by
+/// the services once the change is stored
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DomainEvent {
+    /// A player signed up, as confirmed or as a substitute
+    UserRegistered { registration: Registration },
+    /// A player dropped out; `late` when it was recorded as a late
+    /// cancellation against them
+    UserUnregistered { registration: Registration, late: bool },
+    /// A substitute moved into a freed place
+    SubstitutePromoted { registration: Registration },
+    /// A promoted substitute didn't confirm in time and lost the place
+    PromotionLapsed { registration: Registration },
+    SessionCreated { session: Session },
+    /// An organiser edited a scheduled session
+    SessionUpdated { before: Session, after: Session },
+    SessionCancelled { session: Session },
+    /// A member was approved, directly or by accepting their application
+    UserApproved { user: Box<User> },
+}
+
+/// Reacts to published events. Each subscriber sees events in the order they
+/// were published, on its own task, so a slow one holds up neither the
+/// services nor the other subscribers.
+#[async_trait::async_trait]
+pub trait EventSubscriber: Send + Sync {
+    async fn handle(&self, event: &DomainEvent);
+}
+
+/// Fans events out to the subscribers. Clones share the subscriber list, so
+/// subscribing through any clone reaches every service holding one.
+#[derive(Clone, Default)]
+pub struct EventBus {
+    subscribers: Arc<RwLock<Vec<mpsc::UnboundedSender<DomainEvent>>>>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Deliver every event published from now on to `subscriber`. Must be
+    /// called from within a Tokio runtime.
+    pub fn subscribe(&self, subscriber: Arc<dyn EventSubscriber>) {
+        let (sender, mut receiver) = mpsc::unbounded_channel::<DomainEvent>();
+        tokio::spawn(async move {
+            while let Some(event) = receiver.recv().await {
+                // Run each event on its own task so a panicking handler loses
+                // that event rather than the subscription
+                let subscriber = subscriber.clone();
+                let _ = tokio::spawn(async move { subscriber.handle(&event).await }).await;
+            }
+        });
+        self.subscribers.write().unwrap().push(sender);
+    }
+
+    /// Hand `event` to every subscriber without waiting for them
+    pub fn publish(&self, event: DomainEvent) {
+        let mut subscribers = self.subscribers.write().unwrap();
+        // Drop subscribers whose task has gone with its runtime
+        subscribers.retain(|subscriber| subscriber.send(event.clone()).is_ok());
+    }
+}
+
+impl std::fmt::Debug for EventBus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventBus")
+            .field("subscribers", &self.subscribers.read().unwrap().len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registration::RegistrationStatus;
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    struct Forward(mpsc::UnboundedSender<DomainEvent>);
+
+    #[async_trait::async_trait]
+    impl EventSubscriber for Forward {
+        async fn handle(&self, event: &DomainEvent) {
+            let _ = self.0.send(event.clone());
+        }
+    }
+
+    struct Panics;
+
+    #[async_trait::async_trait]
+    impl EventSubscriber for Panics {
+        async fn handle(&self, _: &DomainEvent) {
+            panic!("subscriber failed");
+        }
+    }
+
+    fn registered() -> DomainEvent {
+        let registration =
+            Registration::new(Uuid::new_v4(), Uuid::new_v4(), RegistrationStatus::Confirmed);
+        DomainEvent::UserRegistered { registration }
+    }
+
+    fn registration_id(event: &DomainEvent) -> Uuid {
+        match event {
+            DomainEvent::UserRegistered { registration } => registration.id,
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn every_subscriber_gets_events_in_order() {
+        let bus = EventBus::new();
+        let (first, mut first_events) = mpsc::unbounded_channel();
+        let (second, mut second_events) = mpsc::unbounded_channel();
+        bus.subscribe(Arc::new(Forward(first)));
+        bus.subscribe(Arc::new(Panics));
+        bus.subscribe(Arc::new(Forward(second)));
+
+        let events: Vec<_> = (0..3).map(|_| registered()).collect();
+        for event in &events {
+            bus.publish(event.clone());
+        }
+
+        for received in [&mut first_events, &mut second_events] {
+            for event in &events {
+                let got = tokio::time::timeout(Duration::from_secs(1), received.recv())
+                    .await
+                    .unwrap()
+                    .unwrap();
+                assert_eq!(registration_id(&got), registration_id(event));
+            }
+        }
+    }
+
+    #[test]
+    fn publishing_without_subscribers_is_a_no_op() {
+        EventBus::new().publish(registered());
+    }
+}
@@ -1,16 +1,42 @@
+pub mod application;
+pub mod attendance;
+pub mod calendar;
+pub mod cancellation;
+pub mod conversation;
+pub mod eligibility;
+pub mod events;
 pub mod models;
 pub mod registration;
 pub mod repository;
 pub mod services;
 pub mod storage;
+pub mod template;
 pub mod user;
 
-pub use models::{Session, SessionType, Venue};
-pub use registration::{Registration, RegistrationStatus};
+pub use application::{Application, ApplicationStatus, ApplicationStatusChange, InvalidField};
+pub use attendance::{Attendance, AttendanceStats, AttendanceStatus};
+pub use cancellation::{CancellationPolicy, LateCancelLimit, LateCancellation};
+pub use conversation::{ConversationMenu, ConversationState};
+pub use eligibility::{EligibilityPolicy, SkillRule};
+pub use events::{DomainEvent, EventBus, EventSubscriber};
+pub use models::{
+    Session, SessionQuery, SessionStatus, SessionSummary, SessionType, SessionUpdate, SortOrder,
+    Venue,
+};
+pub use registration::{
+    LapsedPromotion, Registration, RegistrationActor, RegistrationAttempt, RegistrationEvent,
+    RegistrationEventKind, RegistrationStatus, SessionEdit, Withdrawal,
+};
 pub use repository::{
-    RegistrationError, Repository, SessionError, SessionRepository,
-    UserRepository, VenueRepository,
+    ApplicationError, ApplicationRepository, AttendanceError, AttendanceRepository, ConversationRepository, RegistrationError, Repository, SessionError, SessionRepository,
+    TemplateError, TemplateRepository, UserError, UserRepository, VenueRepository,
+};
+pub use services::{
+    ApplicationService, AttendanceService, RegistrationService, TemplateService, UserService,
 };
-pub use services::RegistrationService;
-pub use storage::{InMemoryStorage, PostgresStorage, Storage};
-pub use user::{Gender, LookingFor, PlayFrequency, PreferredSide, SkillLevel, User};
\ No newline at end of file
+pub use storage::{InMemoryStorage, PostgresStorage, Storage, StorageError, StorageResult};
+pub use template::SessionTemplate;
+pub use user::{
+    Gender, LookingFor, ParseSkillLevelError, PlayFrequency, PreferredSide, SkillLevel, User,
+    UserStatus, UserStatusChange,
+};
\ No newline at end of file
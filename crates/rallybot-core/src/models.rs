@@ -1,4 +1,5 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use crate::user::SkillLevel;
@@ -21,21 +22,50 @@ pub enum SessionType {
     Mixed,
 }
 
+/// Where a session is in its lifecycle. Only scheduled sessions take sign-ups.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "session_status")]
+pub enum SessionStatus {
+    #[default]
+    #[sqlx(rename = "Scheduled")]
+    Scheduled,
+    #[sqlx(rename = "Cancelled")]
+    Cancelled,
+    #[sqlx(rename = "Completed")]
+    Completed,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Venue {
     pub id: Uuid,
     pub name: String,
     pub address: String,
+    /// Sessions here are shown in this zone's local time, e.g. "Europe/Lisbon"
+    pub timezone: Tz,
 }
 
 impl Venue {
+    pub const DEFAULT_TIMEZONE: Tz = Tz::Europe__Lisbon;
+
     pub fn new(name: String, address: String) -> Self {
         Self {
             id: Uuid::new_v4(),
             name,
             address,
+            timezone: Self::DEFAULT_TIMEZONE,
         }
     }
+
+    pub fn with_timezone(mut self, timezone: Tz) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// `datetime` on the venue's wall clock
+    pub fn local(&self, datetime: DateTime<Utc>) -> DateTime<Tz> {
+        datetime.with_timezone(&self.timezone)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,17 +76,111 @@ pub struct Session {
     pub duration_minutes: i32,
     pub venue_id: Uuid,
     pub skill_level: Option<SkillLevel>,
+    /// Confirmed places; later sign-ups become substitutes
+    pub max_players: i32,
+    /// Length of the substitutes list, or `None` for no limit
+    pub max_substitutes: Option<i32>,
+    pub status: SessionStatus,
+    /// Why the organiser cancelled; only set on cancelled sessions
+    pub cancellation_reason: Option<String>,
+    /// The weekly template this session was generated from, if any
+    pub template_id: Option<Uuid>,
+    /// For a generated session, the date on the venue's calendar it was
+    /// generated for. Moving the session doesn't change it.
+    pub occurrence_date: Option<NaiveDate>,
+}
+
+/// Fields an organiser can change on a scheduled session; `None` leaves the
+/// field as it is. `skill_level` and `max_substitutes` are doubly optional so
+/// they can be cleared.
+#[derive(Debug, Clone, Default)]
+pub struct SessionUpdate {
+    pub session_type: Option<SessionType>,
+    pub datetime: Option<DateTime<Utc>>,
+    pub duration_minutes: Option<i32>,
+    pub venue_id: Option<Uuid>,
+    pub skill_level: Option<Option<SkillLevel>>,
+    pub max_players: Option<i32>,
+    pub max_substitutes: Option<Option<i32>>,
+}
+
+/// Which sessions to list. The default is every session that wasn't
+/// cancelled, earliest first.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionQuery {
+    pub session_type: Option<SessionType>,
+    /// Starting at or after
+    pub from: Option<DateTime<Utc>>,
+    /// Starting before
+    pub to: Option<DateTime<Utc>>,
+    pub venue_id: Option<Uuid>,
+    /// Sessions at any of these levels; empty for any level. Mixed sessions
+    /// have no level so only show up when this is empty.
+    pub skill_levels: Vec<SkillLevel>,
+    /// Only sessions with a confirmed place left
+    pub has_open_slots: bool,
+    pub include_cancelled: bool,
+    pub order: SortOrder,
+    pub limit: Option<u32>,
+    pub offset: u32,
+}
+
+/// A session with what a listing shows about it: where it is, how full it
+/// is and who's playing
+#[derive(Debug, Clone)]
+pub struct SessionSummary {
+    pub session: Session,
+    pub venue: Venue,
+    pub confirmed_count: usize,
+    pub substitute_count: usize,
+    /// First names of the confirmed players, in sign-up order
+    pub confirmed_first_names: Vec<String>,
+}
+
+impl SessionSummary {
+    /// Confirmed places left
+    pub fn free_slots(&self) -> usize {
+        (self.session.max_players as usize).saturating_sub(self.confirmed_count)
+    }
+}
+
+/// By start time; ties are broken by id so pages don't overlap
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortOrder {
+    #[default]
+    #[serde(rename = "asc")]
+    Ascending,
+    #[serde(rename = "desc")]
+    Descending,
+}
+
+impl SessionQuery {
+    pub fn of_type(session_type: SessionType) -> Self {
+        Self {
+            session_type: Some(session_type),
+            ..Default::default()
+        }
+    }
 }
 
 impl Session {
+    /// One court of doubles
+    pub const DEFAULT_MAX_PLAYERS: i32 = 4;
+    pub const MIN_PLAYERS: i32 = 2;
+    pub const MAX_PLAYERS: i32 = 16;
+
+    /// A scheduled session taking `max_players` confirmed players and up to
+    /// `max_substitutes` substitutes, `None` for no limit
     pub fn new(
         session_type: SessionType,
         datetime: DateTime<Utc>,
         duration_minutes: i32,
         venue_id: Uuid,
         skill_level: Option<SkillLevel>,
+        max_players: i32,
+        max_substitutes: Option<i32>,
     ) -> Result<Self, &'static str> {
-        if duration_minutes < 60 || duration_minutes > 120 {
+        if !(60..=120).contains(&duration_minutes) {
             return Err("Duration must be between 60 and 120 minutes");
         }
         if duration_minutes % 30 != 0 {
@@ -76,6 +200,13 @@ impl Session {
                 }
             }
         }
+
+        if !(Self::MIN_PLAYERS..=Self::MAX_PLAYERS).contains(&max_players) {
+            return Err("Max players must be between 2 and 16");
+        }
+        if max_substitutes.is_some_and(|n| n < 0) {
+            return Err("Max substitutes cannot be negative");
+        }
         
         Ok(Self {
             id: Uuid::new_v4(),
@@ -84,23 +215,148 @@ impl Session {
             duration_minutes,
             venue_id,
             skill_level,
+            max_players,
+            max_substitutes,
+            status: SessionStatus::Scheduled,
+            cancellation_reason: None,
+            template_id: None,
+            occurrence_date: None,
+        })
+    }
+
+    /// The session with `update` applied, checked against the same rules as
+    /// [`Session::new`]. Identity and status are kept.
+    pub fn updated(&self, update: &SessionUpdate) -> Result<Self, &'static str> {
+        let checked = Self::new(
+            update.session_type.unwrap_or(self.session_type),
+            update.datetime.unwrap_or(self.datetime),
+            update.duration_minutes.unwrap_or(self.duration_minutes),
+            update.venue_id.unwrap_or(self.venue_id),
+            update.skill_level.unwrap_or(self.skill_level),
+            update.max_players.unwrap_or(self.max_players),
+            update.max_substitutes.unwrap_or(self.max_substitutes),
+        )?;
+
+        Ok(Self {
+            session_type: checked.session_type,
+            datetime: checked.datetime,
+            duration_minutes: checked.duration_minutes,
+            venue_id: checked.venue_id,
+            skill_level: checked.skill_level,
+            max_players: checked.max_players,
+            max_substitutes: checked.max_substitutes,
+            ..self.clone()
         })
     }
+
+    pub fn ends_at(&self) -> DateTime<Utc> {
+        self.datetime + chrono::Duration::minutes(self.duration_minutes as i64)
+    }
+
+    pub fn is_scheduled(&self) -> bool {
+        self.status == SessionStatus::Scheduled
+    }
+
+    /// Whether another confirmed player fits
+    pub fn has_open_slot(&self, confirmed_count: usize) -> bool {
+        confirmed_count < self.max_players as usize
+    }
+
+    /// Whether the substitutes list can take another player
+    pub fn has_substitute_slot(&self, substitute_count: usize) -> bool {
+        self.max_substitutes
+            .is_none_or(|max| substitute_count < max as usize)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn social_session() -> Session {
+        Session::new(
+            SessionType::Social,
+            Utc::now(),
+            90,
+            Uuid::new_v4(),
+            Some(SkillLevel::Intermediate),
+            8,
+            Some(2),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn update_keeps_identity_and_capacity() {
+        let session = social_session();
+        let later = session.datetime + chrono::Duration::minutes(30);
+
+        let updated = session
+            .updated(&SessionUpdate {
+                datetime: Some(later),
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert_eq!(updated.id, session.id);
+        assert_eq!(updated.datetime, later);
+        assert_eq!(updated.duration_minutes, 90);
+        assert_eq!(updated.max_players, 8);
+        assert_eq!(updated.max_substitutes, Some(2));
+    }
+
+    #[test]
+    fn update_rechecks_invariants() {
+        let session = social_session();
+
+        let too_long = SessionUpdate {
+            duration_minutes: Some(150),
+            ..Default::default()
+        };
+        assert_eq!(
+            session.updated(&too_long).unwrap_err(),
+            "Duration must be between 60 and 120 minutes"
+        );
+
+        // Switching to Mixed must also clear the level
+        let mixed = SessionUpdate {
+            session_type: Some(SessionType::Mixed),
+            ..Default::default()
+        };
+        assert!(session.updated(&mixed).is_err());
+        let mixed = SessionUpdate {
+            session_type: Some(SessionType::Mixed),
+            skill_level: Some(None),
+            ..Default::default()
+        };
+        assert_eq!(session.updated(&mixed).unwrap().skill_level, None);
+
+        let one_player = SessionUpdate {
+            max_players: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(
+            session.updated(&one_player).unwrap_err(),
+            "Max players must be between 2 and 16"
+        );
+        let bigger = SessionUpdate {
+            max_players: Some(12),
+            max_substitutes: Some(None),
+            ..Default::default()
+        };
+        let bigger = session.updated(&bigger).unwrap();
+        assert_eq!((bigger.max_players, bigger.max_substitutes), (12, None));
+    }
+
     #[test]
     fn session_valid_durations() {
         let venue_id = Uuid::new_v4();
         let datetime = Utc::now();
         
         // Valid durations: 60, 90, 120
-        assert!(Session::new(SessionType::Social, datetime, 60, venue_id, Some(SkillLevel::Intermediate)).is_ok());
-        assert!(Session::new(SessionType::Social, datetime, 90, venue_id, Some(SkillLevel::Advanced)).is_ok());
-        assert!(Session::new(SessionType::Social, datetime, 120, venue_id, Some(SkillLevel::Beginner)).is_ok());
+        assert!(Session::new(SessionType::Social, datetime, 60, venue_id, Some(SkillLevel::Intermediate), Session::DEFAULT_MAX_PLAYERS, None).is_ok());
+        assert!(Session::new(SessionType::Social, datetime, 90, venue_id, Some(SkillLevel::Advanced), Session::DEFAULT_MAX_PLAYERS, None).is_ok());
+        assert!(Session::new(SessionType::Social, datetime, 120, venue_id, Some(SkillLevel::Beginner), Session::DEFAULT_MAX_PLAYERS, None).is_ok());
     }
 
     #[test]
@@ -108,7 +364,7 @@ mod tests {
         let venue_id = Uuid::new_v4();
         let datetime = Utc::now();
         
-        let result = Session::new(SessionType::Social, datetime, 30, venue_id, Some(SkillLevel::Intermediate));
+        let result = Session::new(SessionType::Social, datetime, 30, venue_id, Some(SkillLevel::Intermediate), Session::DEFAULT_MAX_PLAYERS, None);
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), "Duration must be between 60 and 120 minutes");
     }
@@ -118,7 +374,7 @@ mod tests {
         let venue_id = Uuid::new_v4();
         let datetime = Utc::now();
         
-        let result = Session::new(SessionType::Social, datetime, 150, venue_id, Some(SkillLevel::Intermediate));
+        let result = Session::new(SessionType::Social, datetime, 150, venue_id, Some(SkillLevel::Intermediate), Session::DEFAULT_MAX_PLAYERS, None);
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), "Duration must be between 60 and 120 minutes");
     }
@@ -128,7 +384,7 @@ mod tests {
         let venue_id = Uuid::new_v4();
         let datetime = Utc::now();
         
-        let result = Session::new(SessionType::Social, datetime, 75, venue_id, Some(SkillLevel::Intermediate));
+        let result = Session::new(SessionType::Social, datetime, 75, venue_id, Some(SkillLevel::Intermediate), Session::DEFAULT_MAX_PLAYERS, None);
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), "Duration must be in 30-minute increments");
     }
@@ -139,12 +395,12 @@ mod tests {
         let datetime = Utc::now();
         
         // Coaching session without skill level should fail
-        let result = Session::new(SessionType::Coaching, datetime, 90, venue_id, None);
+        let result = Session::new(SessionType::Coaching, datetime, 90, venue_id, None, Session::DEFAULT_MAX_PLAYERS, None);
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), "Coaching, Social, and League sessions must have a skill level");
         
         // Coaching session with skill level should succeed
-        assert!(Session::new(SessionType::Coaching, datetime, 90, venue_id, Some(SkillLevel::Intermediate)).is_ok());
+        assert!(Session::new(SessionType::Coaching, datetime, 90, venue_id, Some(SkillLevel::Intermediate), Session::DEFAULT_MAX_PLAYERS, None).is_ok());
     }
 
     #[test]
@@ -153,12 +409,12 @@ mod tests {
         let datetime = Utc::now();
         
         // Social session without skill level should fail
-        let result = Session::new(SessionType::Social, datetime, 90, venue_id, None);
+        let result = Session::new(SessionType::Social, datetime, 90, venue_id, None, Session::DEFAULT_MAX_PLAYERS, None);
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), "Coaching, Social, and League sessions must have a skill level");
         
         // Social session with skill level should succeed
-        assert!(Session::new(SessionType::Social, datetime, 90, venue_id, Some(SkillLevel::Advanced)).is_ok());
+        assert!(Session::new(SessionType::Social, datetime, 90, venue_id, Some(SkillLevel::Advanced), Session::DEFAULT_MAX_PLAYERS, None).is_ok());
     }
 
     #[test]
@@ -167,12 +423,12 @@ mod tests {
         let datetime = Utc::now();
         
         // League session without skill level should fail
-        let result = Session::new(SessionType::League, datetime, 90, venue_id, None);
+        let result = Session::new(SessionType::League, datetime, 90, venue_id, None, Session::DEFAULT_MAX_PLAYERS, None);
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), "Coaching, Social, and League sessions must have a skill level");
         
         // League session with skill level should succeed
-        assert!(Session::new(SessionType::League, datetime, 90, venue_id, Some(SkillLevel::UpperIntermediate)).is_ok());
+        assert!(Session::new(SessionType::League, datetime, 90, venue_id, Some(SkillLevel::UpperIntermediate), Session::DEFAULT_MAX_PLAYERS, None).is_ok());
     }
 
     #[test]
@@ -181,12 +437,12 @@ mod tests {
         let datetime = Utc::now();
         
         // Mixed session with skill level should fail
-        let result = Session::new(SessionType::Mixed, datetime, 90, venue_id, Some(SkillLevel::Intermediate));
+        let result = Session::new(SessionType::Mixed, datetime, 90, venue_id, Some(SkillLevel::Intermediate), Session::DEFAULT_MAX_PLAYERS, None);
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), "Mixed sessions cannot have a skill level");
         
         // Mixed session without skill level should succeed
-        assert!(Session::new(SessionType::Mixed, datetime, 90, venue_id, None).is_ok());
+        assert!(Session::new(SessionType::Mixed, datetime, 90, venue_id, None, Session::DEFAULT_MAX_PLAYERS, None).is_ok());
     }
 
     #[test]
@@ -195,13 +451,50 @@ mod tests {
         let datetime = Utc::now();
         
         // Test all skill levels work with non-Mixed sessions
-        assert!(Session::new(SessionType::Social, datetime, 90, venue_id, Some(SkillLevel::Beginner)).is_ok());
-        assert!(Session::new(SessionType::Social, datetime, 90, venue_id, Some(SkillLevel::LowIntermediate)).is_ok());
-        assert!(Session::new(SessionType::Social, datetime, 90, venue_id, Some(SkillLevel::Intermediate)).is_ok());
-        assert!(Session::new(SessionType::Social, datetime, 90, venue_id, Some(SkillLevel::UpperIntermediate)).is_ok());
-        assert!(Session::new(SessionType::Social, datetime, 90, venue_id, Some(SkillLevel::Advanced)).is_ok());
-        assert!(Session::new(SessionType::Social, datetime, 90, venue_id, Some(SkillLevel::HighAdvanced)).is_ok());
-        assert!(Session::new(SessionType::Social, datetime, 90, venue_id, Some(SkillLevel::Expert)).is_ok());
-        assert!(Session::new(SessionType::Social, datetime, 90, venue_id, Some(SkillLevel::Elite)).is_ok());
-    }
-}
\ No newline at end of file
+        assert!(Session::new(SessionType::Social, datetime, 90, venue_id, Some(SkillLevel::Beginner), Session::DEFAULT_MAX_PLAYERS, None).is_ok());
+        assert!(Session::new(SessionType::Social, datetime, 90, venue_id, Some(SkillLevel::LowIntermediate), Session::DEFAULT_MAX_PLAYERS, None).is_ok());
+        assert!(Session::new(SessionType::Social, datetime, 90, venue_id, Some(SkillLevel::Intermediate), Session::DEFAULT_MAX_PLAYERS, None).is_ok());
+        assert!(Session::new(SessionType::Social, datetime, 90, venue_id, Some(SkillLevel::UpperIntermediate), Session::DEFAULT_MAX_PLAYERS, None).is_ok());
+        assert!(Session::new(SessionType::Social, datetime, 90, venue_id, Some(SkillLevel::Advanced), Session::DEFAULT_MAX_PLAYERS, None).is_ok());
+        assert!(Session::new(SessionType::Social, datetime, 90, venue_id, Some(SkillLevel::HighAdvanced), Session::DEFAULT_MAX_PLAYERS, None).is_ok());
+        assert!(Session::new(SessionType::Social, datetime, 90, venue_id, Some(SkillLevel::Expert), Session::DEFAULT_MAX_PLAYERS, None).is_ok());
+        assert!(Session::new(SessionType::Social, datetime, 90, venue_id, Some(SkillLevel::Elite), Session::DEFAULT_MAX_PLAYERS, None).is_ok());
+    }
+
+    #[test]
+    fn session_default_capacity() {
+        let session = Session::new(SessionType::Social, Utc::now(), 90, Uuid::new_v4(), Some(SkillLevel::Intermediate), Session::DEFAULT_MAX_PLAYERS, None).unwrap();
+        assert_eq!(session.max_players, 4);
+        assert_eq!(session.max_substitutes, None);
+        assert!(session.has_open_slot(3));
+        assert!(!session.has_open_slot(4));
+        assert!(session.has_substitute_slot(100));
+    }
+
+    #[test]
+    fn session_custom_capacity() {
+        let session = Session::new(SessionType::Coaching, Utc::now(), 90, Uuid::new_v4(), Some(SkillLevel::Beginner), 8, Some(2))
+            .unwrap();
+        assert_eq!(session.max_players, 8);
+        assert!(session.has_open_slot(7));
+        assert!(!session.has_open_slot(8));
+        assert!(session.has_substitute_slot(1));
+        assert!(!session.has_substitute_slot(2));
+    }
+
+    #[test]
+    fn session_invalid_capacity() {
+        let session = |max_players, max_substitutes| {
+            Session::new(SessionType::Mixed, Utc::now(), 90, Uuid::new_v4(), None, max_players, max_substitutes)
+        };
+
+        let result = session(1, None);
+        assert_eq!(result.unwrap_err(), "Max players must be between 2 and 16");
+
+        let result = session(17, None);
+        assert_eq!(result.unwrap_err(), "Max players must be between 2 and 16");
+
+        let result = session(4, Some(-1));
+        assert_eq!(result.unwrap_err(), "Max substitutes cannot be negative");
+    }
+}
@@ -19,6 +19,9 @@ pub struct Registration {
     pub session_id: Uuid,
     pub status: RegistrationStatus,
     pub created_at: DateTime<Utc>,
+    /// Set when a substitute is promoted and has to confirm the spot by this
+    /// time or lose it to the next substitute
+    pub confirm_by: Option<DateTime<Utc>>,
 }
 
 impl Registration {
@@ -29,6 +32,143 @@ impl Registration {
             session_id,
             status,
             created_at: Utc::now(),
+            confirm_by: None,
         }
     }
-}
\ No newline at end of file
+
+    /// Promoted and still to confirm at `now`
+    pub fn awaits_confirmation(&self, now: DateTime<Utc>) -> bool {
+        self.confirm_by.is_some_and(|confirm_by| confirm_by > now)
+    }
+}
+
+/// What happened to a registration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "registration_event_kind")]
+pub enum RegistrationEventKind {
+    #[sqlx(rename = "Registered")]
+    Registered,
+    /// Moved from the substitutes list into a confirmed place
+    #[sqlx(rename = "Promoted")]
+    Promoted,
+    /// Moved from a confirmed place back to the substitutes list
+    #[sqlx(rename = "Demoted")]
+    Demoted,
+    #[sqlx(rename = "Cancelled")]
+    Cancelled,
+    /// Cancelled after the session's cancellation cutoff
+    #[sqlx(rename = "LateCancelled")]
+    LateCancelled,
+}
+
+/// Who caused a registration event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "registration_actor")]
+pub enum RegistrationActor {
+    /// The player themselves
+    #[sqlx(rename = "Player")]
+    Player,
+    #[sqlx(rename = "Organiser")]
+    Organiser,
+    /// Rallybot, e.g. promoting a substitute or expiring an unconfirmed spot
+    #[sqlx(rename = "System")]
+    System,
+}
+
+/// Entry in a session's append-only registration log. Events are never
+/// changed or removed, so the log outlives the registrations it describes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegistrationEvent {
+    pub id: Uuid,
+    pub registration_id: Uuid,
+    pub session_id: Uuid,
+    pub user_id: Uuid,
+    pub kind: RegistrationEventKind,
+    /// The registration's status after the event; `None` once it is cancelled
+    pub status: Option<RegistrationStatus>,
+    pub actor: RegistrationActor,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl RegistrationEvent {
+    /// `registration` as it is after the event. Sign-ups are dated by the
+    /// registration itself, so the log agrees with the substitutes order.
+    pub fn new(
+        registration: &Registration,
+        kind: RegistrationEventKind,
+        actor: RegistrationActor,
+    ) -> Self {
+        let (status, occurred_at) = match kind {
+            RegistrationEventKind::Registered => (Some(registration.status), registration.created_at),
+            RegistrationEventKind::Promoted | RegistrationEventKind::Demoted => {
+                (Some(registration.status), Utc::now())
+            }
+            RegistrationEventKind::Cancelled | RegistrationEventKind::LateCancelled => {
+                (None, Utc::now())
+            }
+        };
+        Self {
+            id: Uuid::new_v4(),
+            registration_id: registration.id,
+            session_id: registration.session_id,
+            user_id: registration.user_id,
+            kind,
+            status,
+            actor,
+            occurred_at,
+        }
+    }
+}
+
+/// Outcome of taking a player off a session
+#[derive(Debug, Clone)]
+pub enum Withdrawal {
+    Removed {
+        registration: Registration,
+        /// Substitutes moved into the freed place
+        promoted: Vec<Registration>,
+        /// A late cancellation was recorded against the player
+        late_cancelled: bool,
+    },
+    NotRegistered,
+    /// A late cancellation was refused because no substitute could take the
+    /// player's place
+    NoReplacement,
+    /// The player's attendance is already recorded, so the registration stays
+    AttendanceRecorded,
+}
+
+/// Outcome of saving an organiser's edit to a session
+#[derive(Debug, Clone)]
+pub enum SessionEdit {
+    Saved {
+        /// Substitutes moved into places the edit added
+        promoted: Vec<Registration>,
+    },
+    /// There is no scheduled session with that id
+    NotScheduled,
+    /// More players or substitutes are signed up than the edit leaves room for
+    OverCapacity,
+}
+
+/// A promoted substitute who didn't confirm in time, with the substitutes
+/// promoted into the spot they gave up
+#[derive(Debug, Clone)]
+pub struct LapsedPromotion {
+    pub lapsed: Registration,
+    pub promoted: Vec<Registration>,
+}
+
+/// Outcome of a sign-up checked against the session's capacity
+#[derive(Debug, Clone)]
+pub enum RegistrationAttempt {
+    Created(Registration),
+    AlreadyRegistered,
+    /// Both the confirmed places and the substitutes list are taken
+    Full,
+    SessionNotFound,
+    /// The session was cancelled or has already been played
+    SessionClosed,
+}
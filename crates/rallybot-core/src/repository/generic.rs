@@ -1,50 +1,160 @@
 use crate::{
-    models::{Session, SessionType, Venue},
-    registration::{Registration, RegistrationStatus},
-    services::{RegistrationService, SessionService},
-    storage::Storage,
-    user::User,
+    application::{Application, ApplicationStatus, ApplicationStatusChange},
+    attendance::{Attendance, AttendanceStats, AttendanceStatus},
+    cancellation::{CancellationPolicy, LateCancellation},
+    conversation::ConversationState,
+    eligibility::EligibilityPolicy,
+    events::EventBus,
+    models::{Session, SessionQuery, SessionStatus, SessionSummary, SessionUpdate, Venue},
+    registration::{LapsedPromotion, Registration, RegistrationEvent, RegistrationStatus},
+    services::{
+        session::page, ApplicationService, AttendanceService, RegistrationService, SessionService,
+        TemplateService, UserService,
+    },
+    storage::{Storage, StorageResult},
+    template::SessionTemplate,
+    user::{User, UserStatusChange},
 };
+use chrono::{DateTime, NaiveDate, Utc};
 use std::sync::Arc;
 use uuid::Uuid;
 
-use super::{RegistrationError, SessionError, SessionRepository, UserRepository, VenueRepository};
+use super::{
+    ApplicationError, ApplicationRepository, AttendanceError, AttendanceRepository, ConversationRepository, RegistrationError, SessionError, SessionRepository, TemplateError,
+    TemplateRepository, UserError, UserRepository, VenueRepository,
+};
+
+impl From<crate::services::session::SessionError> for SessionError {
+    fn from(error: crate::services::session::SessionError) -> Self {
+        use crate::services::session::SessionError as ServiceError;
+        match error {
+            ServiceError::VenueNotFound => SessionError::VenueNotFound,
+            ServiceError::SessionNotFound => SessionError::SessionNotFound,
+            ServiceError::NotScheduled => SessionError::NotScheduled,
+            ServiceError::Invalid(reason) => SessionError::Invalid(reason),
+            ServiceError::OverCapacity => SessionError::OverCapacity,
+            ServiceError::Storage(e) => SessionError::Storage(e),
+        }
+    }
+}
 
 pub struct Repository<S: Storage> {
     storage: Arc<S>,
     registration_service: RegistrationService<Arc<S>>,
     session_service: SessionService<S>,
+    template_service: TemplateService<S>,
+    user_service: UserService<S>,
+    application_service: ApplicationService<S>,
+    attendance_service: AttendanceService<S>,
+    events: EventBus,
 }
 
 impl<S: Storage> Repository<S> {
     pub fn new(storage: Arc<S>) -> Self {
-        let registration_service = RegistrationService::new(storage.clone());
-        let session_service = SessionService::new(storage.clone());
+        Self::with_policy(storage, EligibilityPolicy::default())
+    }
+
+    /// A repository enforcing `policy` when players sign up
+    pub fn with_policy(storage: Arc<S>, policy: EligibilityPolicy) -> Self {
+        let events = EventBus::new();
+        let registration_service = RegistrationService::with_policy(storage.clone(), policy)
+            .with_events(events.clone());
+        let session_service = SessionService::new(storage.clone()).with_events(events.clone());
+        let template_service = TemplateService::new(storage.clone()).with_events(events.clone());
+        let user_service = UserService::new(storage.clone()).with_events(events.clone());
+        let application_service =
+            ApplicationService::new(storage.clone()).with_events(events.clone());
+        let attendance_service = AttendanceService::new(storage.clone());
         Self {
             storage,
             registration_service,
             session_service,
+            template_service,
+            user_service,
+            application_service,
+            attendance_service,
+            events,
         }
     }
+
+    /// The bus the services publish on; subscribe here to react to changes
+    pub fn events(&self) -> &EventBus {
+        &self.events
+    }
+
+    /// Make substitutes promoted into a freed place confirm within `window`,
+    /// or lose it to the next substitute
+    pub fn with_confirmation_window(mut self, window: Option<chrono::Duration>) -> Self {
+        self.registration_service = self.registration_service.with_confirmation_window(window);
+        self
+    }
+
+    /// Apply `policy`'s cancellation cutoffs and late-cancellation limit
+    pub fn with_cancellation_policy(mut self, policy: CancellationPolicy) -> Self {
+        self.registration_service = self.registration_service.with_cancellation_policy(policy);
+        self
+    }
 }
 
 #[async_trait::async_trait]
 impl<S: Storage> SessionRepository for Repository<S> {
-    async fn list(&self, session_type: Option<SessionType>) -> Vec<Session> {
-        self.storage.list_sessions(session_type).await
+    async fn list(&self, query: &SessionQuery) -> StorageResult<Vec<Session>> {
+        self.session_service.list_sessions(query).await
+    }
+
+    async fn list_in_week(
+        &self,
+        query: &SessionQuery,
+        now: DateTime<Utc>,
+        weeks_ahead: u32,
+    ) -> StorageResult<Vec<Session>> {
+        self.session_service
+            .list_sessions_in_week(query, now, weeks_ahead)
+            .await
     }
 
-    async fn get(&self, id: Uuid) -> Option<Session> {
+    async fn list_summaries(&self, query: &SessionQuery) -> StorageResult<Vec<SessionSummary>> {
+        self.session_service.list_summaries(query).await
+    }
+
+    async fn list_summaries_in_week(
+        &self,
+        query: &SessionQuery,
+        now: DateTime<Utc>,
+        weeks_ahead: u32,
+    ) -> StorageResult<Vec<SessionSummary>> {
+        self.session_service
+            .list_summaries_in_week(query, now, weeks_ahead)
+            .await
+    }
+
+    async fn get(&self, id: Uuid) -> StorageResult<Option<Session>> {
         self.storage.get_session(id).await
     }
 
     async fn create(&self, session: Session) -> Result<Session, SessionError> {
-        match self.session_service.create_session(session).await {
-            Ok(session) => Ok(session),
-            Err(crate::services::session::SessionError::VenueNotFound) => {
-                Err(SessionError::VenueNotFound)
-            }
-        }
+        self.session_service
+            .create_session(session)
+            .await
+            .map_err(SessionError::from)
+    }
+
+    async fn cancel(&self, id: Uuid, reason: &str) -> Result<Session, SessionError> {
+        self.session_service
+            .cancel_session(id, reason)
+            .await
+            .map_err(SessionError::from)
+    }
+
+    async fn update(
+        &self,
+        id: Uuid,
+        update: &SessionUpdate,
+    ) -> Result<(Session, Session), SessionError> {
+        self.session_service
+            .update_session(id, update)
+            .await
+            .map_err(SessionError::from)
     }
 
     async fn register_user(
@@ -57,62 +167,280 @@ impl<S: Storage> SessionRepository for Repository<S> {
             .await
     }
 
+    async fn place_player(
+        &self,
+        session_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<RegistrationStatus, RegistrationError> {
+        self.registration_service
+            .place_player(session_id, user_id)
+            .await
+    }
+
+    async fn list_eligible(&self, query: &SessionQuery, user: &User) -> StorageResult<Vec<Session>> {
+        self.registration_service.eligible_sessions(query, user).await
+    }
+
+    async fn list_eligible_in_week(
+        &self,
+        query: &SessionQuery,
+        user: &User,
+        now: DateTime<Utc>,
+        weeks_ahead: u32,
+    ) -> StorageResult<Vec<Session>> {
+        let candidates = SessionQuery {
+            limit: None,
+            offset: 0,
+            ..query.clone()
+        };
+        let sessions = self
+            .session_service
+            .list_sessions_in_week(&candidates, now, weeks_ahead)
+            .await?;
+        let policy = self.registration_service.policy();
+        let eligible = sessions
+            .into_iter()
+            .filter(|session| policy.allows(user, session));
+        Ok(page(eligible, query))
+    }
+
     async fn unregister_user(
         &self,
         session_id: Uuid,
         user_id: Uuid,
-    ) -> Result<(), RegistrationError> {
+    ) -> Result<Vec<Registration>, RegistrationError> {
         self.registration_service
             .unregister_user(session_id, user_id)
             .await
     }
 
-    async fn get_registrations(&self, session_id: Uuid) -> Vec<Registration> {
+    async fn confirm_spot(
+        &self,
+        session_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Registration, RegistrationError> {
+        self.registration_service
+            .confirm_spot(session_id, user_id)
+            .await
+    }
+
+    async fn pending_confirmations(&self, user_id: Uuid) -> StorageResult<Vec<Registration>> {
+        self.registration_service.pending_confirmations(user_id).await
+    }
+
+    async fn expire_lapsed_promotions(&self, now: DateTime<Utc>) -> StorageResult<Vec<LapsedPromotion>> {
+        self.registration_service.expire_lapsed_promotions(now).await
+    }
+
+    async fn get_registrations(&self, session_id: Uuid) -> StorageResult<Vec<Registration>> {
         self.registration_service
             .get_session_registrations(session_id)
             .await
     }
 
-    async fn get_user_sessions(&self, user_id: Uuid) -> Vec<Session> {
-        let session_ids = self.registration_service.get_user_sessions(user_id).await;
+    async fn registration_history(&self, session_id: Uuid) -> StorageResult<Vec<RegistrationEvent>> {
+        self.registration_service.registration_history(session_id).await
+    }
+
+    async fn get_user_sessions(&self, user_id: Uuid) -> StorageResult<Vec<Session>> {
+        let session_ids = self.registration_service.get_user_sessions(user_id).await?;
         let mut sessions = Vec::new();
         for id in session_ids {
-            if let Some(session) = self.storage.get_session(id).await {
-                sessions.push(session);
+            // Cancelled sessions drop out of the player's list
+            if let Some(session) = self.storage.get_session(id).await? {
+                if session.status != SessionStatus::Cancelled {
+                    sessions.push(session);
+                }
             }
         }
-        sessions
+        Ok(sessions)
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: Storage> TemplateRepository for Repository<S> {
+    async fn list(&self) -> StorageResult<Vec<SessionTemplate>> {
+        self.template_service.list_templates().await
+    }
+
+    async fn get(&self, id: Uuid) -> StorageResult<Option<SessionTemplate>> {
+        self.template_service.get_template(id).await
+    }
+
+    async fn create(&self, template: SessionTemplate) -> Result<SessionTemplate, TemplateError> {
+        self.template_service.create_template(template).await
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<(), TemplateError> {
+        self.template_service.delete_template(id).await
+    }
+
+    async fn add_exception(
+        &self,
+        id: Uuid,
+        date: NaiveDate,
+    ) -> Result<SessionTemplate, TemplateError> {
+        self.template_service.add_exception(id, date).await
+    }
+
+    async fn remove_exception(
+        &self,
+        id: Uuid,
+        date: NaiveDate,
+    ) -> Result<SessionTemplate, TemplateError> {
+        self.template_service.remove_exception(id, date).await
+    }
+
+    async fn generate(
+        &self,
+        id: Uuid,
+        from: DateTime<Utc>,
+        weeks: u32,
+    ) -> Result<Vec<Session>, TemplateError> {
+        self.template_service.generate(id, from, weeks).await
+    }
+
+    async fn generate_all(
+        &self,
+        from: DateTime<Utc>,
+        weeks: u32,
+    ) -> Result<Vec<Session>, TemplateError> {
+        self.template_service.generate_all(from, weeks).await
     }
 }
 
 #[async_trait::async_trait]
 impl<S: Storage> UserRepository for Repository<S> {
-    async fn get(&self, id: Uuid) -> Option<User> {
+    async fn get(&self, id: Uuid) -> StorageResult<Option<User>> {
         self.storage.get_user(id).await
     }
 
-    async fn get_by_phone(&self, phone: &str) -> Option<User> {
+    async fn get_by_phone(&self, phone: &str) -> StorageResult<Option<User>> {
         self.storage.get_user_by_phone(phone).await
     }
 
-    async fn create(&self, user: User) -> User {
-        self.storage.create_user(user.clone()).await;
-        user
+    async fn create(&self, user: User) -> StorageResult<User> {
+        self.storage.create_user(user.clone()).await?;
+        Ok(user)
+    }
+
+    async fn list_pending(&self) -> StorageResult<Vec<User>> {
+        self.user_service.list_pending().await
+    }
+
+    async fn approve(&self, id: Uuid, by: &str, reason: Option<String>) -> Result<User, UserError> {
+        self.user_service.approve(id, by, reason).await
+    }
+
+    async fn reject(&self, id: Uuid, by: &str, reason: Option<String>) -> Result<User, UserError> {
+        self.user_service.reject(id, by, reason).await
+    }
+
+    async fn suspend(&self, id: Uuid, by: &str, reason: Option<String>) -> Result<User, UserError> {
+        self.user_service.suspend(id, by, reason).await
+    }
+
+    async fn status_history(&self, id: Uuid) -> StorageResult<Vec<UserStatusChange>> {
+        self.user_service.status_history(id).await
+    }
+
+    async fn late_cancellations(&self, id: Uuid) -> StorageResult<Vec<LateCancellation>> {
+        self.registration_service.late_cancellations(id).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: Storage> ApplicationRepository for Repository<S> {
+    async fn submit(&self, application: Application) -> Result<(Application, bool), ApplicationError> {
+        self.application_service.submit(application).await
+    }
+
+    async fn get(&self, id: Uuid) -> StorageResult<Option<Application>> {
+        self.storage.get_application(id).await
+    }
+
+    async fn list(&self, status: ApplicationStatus) -> StorageResult<Vec<Application>> {
+        self.application_service.list(status).await
+    }
+
+    async fn approve(
+        &self,
+        id: Uuid,
+        by: &str,
+        reason: Option<String>,
+    ) -> Result<(Application, User), ApplicationError> {
+        self.application_service.approve(id, by, reason).await
+    }
+
+    async fn reject(
+        &self,
+        id: Uuid,
+        by: &str,
+        reason: Option<String>,
+    ) -> Result<Application, ApplicationError> {
+        self.application_service.reject(id, by, reason).await
+    }
+
+    async fn history(&self, id: Uuid) -> StorageResult<Vec<ApplicationStatusChange>> {
+        self.application_service.history(id).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: Storage> AttendanceRepository for Repository<S> {
+    async fn mark(
+        &self,
+        session_id: Uuid,
+        user_id: Uuid,
+        status: AttendanceStatus,
+        by: &str,
+    ) -> Result<Attendance, AttendanceError> {
+        self.attendance_service
+            .mark(session_id, user_id, status, by, Utc::now())
+            .await
+    }
+
+    async fn check_in(&self, user: &User, now: DateTime<Utc>) -> Result<Attendance, AttendanceError> {
+        self.attendance_service.check_in(user, now).await
+    }
+
+    async fn for_session(&self, session_id: Uuid) -> StorageResult<Vec<Attendance>> {
+        self.attendance_service.for_session(session_id).await
+    }
+
+    async fn stats(&self, user_id: Uuid) -> StorageResult<AttendanceStats> {
+        self.attendance_service.stats(user_id).await
     }
 }
 
 #[async_trait::async_trait]
 impl<S: Storage> VenueRepository for Repository<S> {
-    async fn get(&self, id: Uuid) -> Option<Venue> {
+    async fn get(&self, id: Uuid) -> StorageResult<Option<Venue>> {
         self.storage.get_venue(id).await
     }
 
-    async fn list(&self) -> Vec<Venue> {
+    async fn list(&self) -> StorageResult<Vec<Venue>> {
         self.storage.list_venues().await
     }
 
-    async fn create(&self, venue: Venue) -> Venue {
-        self.storage.create_venue(venue.clone()).await;
-        venue
+    async fn create(&self, venue: Venue) -> StorageResult<Venue> {
+        self.storage.create_venue(venue.clone()).await?;
+        Ok(venue)
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: Storage> ConversationRepository for Repository<S> {
+    async fn get(&self, phone: &str) -> StorageResult<Option<ConversationState>> {
+        self.storage.get_conversation_state(phone).await
+    }
+
+    async fn save(&self, state: ConversationState) -> StorageResult<()> {
+        self.storage.save_conversation_state(state).await
+    }
+
+    async fn clear(&self, phone: &str) -> StorageResult<()> {
+        self.storage.delete_conversation_state(phone).await?;
+        Ok(())
     }
-}
\ No newline at end of file
+}
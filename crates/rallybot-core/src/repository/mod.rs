@@ -3,6 +3,6 @@ mod traits;
 
 pub use generic::Repository;
 pub use traits::{
-    RegistrationError, SessionError, SessionRepository, UserRepository,
-    VenueRepository,
+    ApplicationError, ApplicationRepository, AttendanceError, AttendanceRepository, ConversationRepository, RegistrationError, SessionError, SessionRepository,
+    TemplateError, TemplateRepository, UserError, UserRepository, VenueRepository,
 };
\ No newline at end of file
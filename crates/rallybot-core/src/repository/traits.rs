@@ -1,8 +1,15 @@
 use crate::{
-    models::{Session, SessionType, Venue},
-    registration::{Registration, RegistrationStatus},
-    user::User,
+    application::{Application, ApplicationStatus, ApplicationStatusChange, InvalidField},
+    attendance::{Attendance, AttendanceStats, AttendanceStatus},
+    cancellation::LateCancellation,
+    conversation::ConversationState,
+    models::{Session, SessionQuery, SessionSummary, SessionUpdate, Venue},
+    registration::{LapsedPromotion, Registration, RegistrationEvent, RegistrationStatus},
+    storage::{StorageError, StorageResult},
+    template::SessionTemplate,
+    user::{User, UserStatus, UserStatusChange},
 };
+use chrono::{DateTime, NaiveDate, Utc};
 use uuid::Uuid;
 
 #[derive(Debug)]
@@ -12,42 +19,295 @@ pub enum RegistrationError {
     UserNotApproved,
     AlreadyRegistered,
     NotRegistered,
+    /// Both the confirmed places and the substitutes list are taken
+    SessionFull,
+    /// The session was cancelled or has already been played
+    SessionClosed,
+    /// None of the player's levels fits the session's under the eligibility
+    /// policy
+    SkillLevelMismatch,
+    /// The player has no promotion waiting to be confirmed, or it lapsed
+    NoPendingConfirmation,
+    /// Past the session's cancellation cutoff with no substitute to take the
+    /// place
+    TooLateToCancel,
+    /// The session has started, or the player's attendance is already
+    /// recorded, so they can no longer withdraw
+    SessionStarted,
+    /// The player's recent late cancellations bar them from signing up
+    TooManyLateCancellations,
+    Storage(StorageError),
+}
+
+impl From<StorageError> for RegistrationError {
+    fn from(error: StorageError) -> Self {
+        RegistrationError::Storage(error)
+    }
 }
 
 #[derive(Debug)]
 pub enum SessionError {
     VenueNotFound,
+    SessionNotFound,
+    /// Only scheduled sessions can be changed or cancelled
+    NotScheduled,
+    /// The change breaks one of the rules checked by `Session::new`
+    Invalid(&'static str),
+    /// Fewer places than players or substitutes already signed up
+    OverCapacity,
+    Storage(StorageError),
+}
+
+impl From<StorageError> for SessionError {
+    fn from(error: StorageError) -> Self {
+        SessionError::Storage(error)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TemplateError {
+    TemplateNotFound,
+    VenueNotFound,
+    /// The template breaks one of the rules checked by `Session::new`
+    Invalid(&'static str),
+    Storage(StorageError),
+}
+
+impl From<StorageError> for TemplateError {
+    fn from(error: StorageError) -> Self {
+        TemplateError::Storage(error)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UserError {
+    UserNotFound,
+    /// The user's current status can't move to the requested one, e.g.
+    /// suspending someone who was never approved
+    InvalidTransition { from: UserStatus, to: UserStatus },
+    Storage(StorageError),
+}
+
+impl From<StorageError> for UserError {
+    fn from(error: StorageError) -> Self {
+        UserError::Storage(error)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ApplicationError {
+    ApplicationNotFound,
+    /// Every field that failed validation
+    Invalid(Vec<InvalidField>),
+    /// A member with this phone number or email already exists
+    AlreadyMember,
+    InvalidTransition {
+        from: ApplicationStatus,
+        to: ApplicationStatus,
+    },
+    Storage(StorageError),
+}
+
+impl From<StorageError> for ApplicationError {
+    fn from(error: StorageError) -> Self {
+        ApplicationError::Storage(error)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum AttendanceError {
+    SessionNotFound,
+    /// Nobody plays a cancelled session
+    SessionCancelled,
+    /// Organisers mark attendance once the session has ended
+    SessionNotOver,
+    /// The user has no confirmed place in the session
+    NotRegistered,
+    /// None of the player's sessions is open for check-in right now
+    NothingToCheckIn,
+    Storage(StorageError),
+}
+
+impl From<StorageError> for AttendanceError {
+    fn from(error: StorageError) -> Self {
+        AttendanceError::Storage(error)
+    }
 }
 
 #[async_trait::async_trait]
 pub trait SessionRepository: Send + Sync {
-    async fn list(&self, session_type: Option<SessionType>) -> Vec<Session>;
-    async fn get(&self, id: Uuid) -> Option<Session>;
+    async fn list(&self, query: &SessionQuery) -> StorageResult<Vec<Session>>;
+    /// Sessions matching `query` in the local week containing `now`, or
+    /// `weeks_ahead` weeks later
+    async fn list_in_week(
+        &self,
+        query: &SessionQuery,
+        now: DateTime<Utc>,
+        weeks_ahead: u32,
+    ) -> StorageResult<Vec<Session>>;
+    /// Sessions with their venue and sign-up counts, for listings
+    async fn list_summaries(&self, query: &SessionQuery) -> StorageResult<Vec<SessionSummary>>;
+    async fn list_summaries_in_week(
+        &self,
+        query: &SessionQuery,
+        now: DateTime<Utc>,
+        weeks_ahead: u32,
+    ) -> StorageResult<Vec<SessionSummary>>;
+    async fn get(&self, id: Uuid) -> StorageResult<Option<Session>>;
     async fn create(&self, session: Session) -> Result<Session, SessionError>;
+    async fn cancel(&self, id: Uuid, reason: &str) -> Result<Session, SessionError>;
+    /// Returns the session before and after the change
+    async fn update(
+        &self,
+        id: Uuid,
+        update: &SessionUpdate,
+    ) -> Result<(Session, Session), SessionError>;
     async fn register_user(
         &self,
         session_id: Uuid,
         user_id: Uuid,
     ) -> Result<RegistrationStatus, RegistrationError>;
+    /// An organiser signing a player up by hand, skipping both the skill level
+    /// check and the late-cancellation limit
+    async fn place_player(
+        &self,
+        session_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<RegistrationStatus, RegistrationError>;
+    /// Sessions matching `query` that `user`'s skill levels let them join
+    async fn list_eligible(&self, query: &SessionQuery, user: &User) -> StorageResult<Vec<Session>>;
+    /// [`list_eligible`](Self::list_eligible) for a local week, as in
+    /// [`list_in_week`](Self::list_in_week)
+    async fn list_eligible_in_week(
+        &self,
+        query: &SessionQuery,
+        user: &User,
+        now: DateTime<Utc>,
+        weeks_ahead: u32,
+    ) -> StorageResult<Vec<Session>>;
+    /// Returns the substitutes promoted into the freed place
     async fn unregister_user(
         &self,
         session_id: Uuid,
         user_id: Uuid,
-    ) -> Result<(), RegistrationError>;
-    async fn get_registrations(&self, session_id: Uuid) -> Vec<Registration>;
-    async fn get_user_sessions(&self, user_id: Uuid) -> Vec<Session>;
+    ) -> Result<Vec<Registration>, RegistrationError>;
+    /// Keep a spot the player was promoted into before the deadline
+    async fn confirm_spot(
+        &self,
+        session_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Registration, RegistrationError>;
+    async fn pending_confirmations(&self, user_id: Uuid) -> StorageResult<Vec<Registration>>;
+    /// Pass every spot not confirmed by `now` to the next substitute
+    async fn expire_lapsed_promotions(&self, now: DateTime<Utc>) -> StorageResult<Vec<LapsedPromotion>>;
+    async fn get_registrations(&self, session_id: Uuid) -> StorageResult<Vec<Registration>>;
+    /// The session's append-only registration log, oldest first
+    async fn registration_history(&self, session_id: Uuid) -> StorageResult<Vec<RegistrationEvent>>;
+    async fn get_user_sessions(&self, user_id: Uuid) -> StorageResult<Vec<Session>>;
+}
+
+#[async_trait::async_trait]
+pub trait TemplateRepository: Send + Sync {
+    async fn list(&self) -> StorageResult<Vec<SessionTemplate>>;
+    async fn get(&self, id: Uuid) -> StorageResult<Option<SessionTemplate>>;
+    async fn create(&self, template: SessionTemplate) -> Result<SessionTemplate, TemplateError>;
+    /// Sessions already generated from the template are kept
+    async fn delete(&self, id: Uuid) -> Result<(), TemplateError>;
+    async fn add_exception(
+        &self,
+        id: Uuid,
+        date: NaiveDate,
+    ) -> Result<SessionTemplate, TemplateError>;
+    async fn remove_exception(
+        &self,
+        id: Uuid,
+        date: NaiveDate,
+    ) -> Result<SessionTemplate, TemplateError>;
+    /// Create the template's sessions from `from` up to `weeks` weeks ahead.
+    /// Returns only the sessions that didn't exist yet.
+    async fn generate(
+        &self,
+        id: Uuid,
+        from: DateTime<Utc>,
+        weeks: u32,
+    ) -> Result<Vec<Session>, TemplateError>;
+    async fn generate_all(
+        &self,
+        from: DateTime<Utc>,
+        weeks: u32,
+    ) -> Result<Vec<Session>, TemplateError>;
 }
 
 #[async_trait::async_trait]
 pub trait UserRepository: Send + Sync {
-    async fn get(&self, id: Uuid) -> Option<User>;
-    async fn get_by_phone(&self, phone: &str) -> Option<User>;
-    async fn create(&self, user: User) -> User;
+    async fn get(&self, id: Uuid) -> StorageResult<Option<User>>;
+    async fn get_by_phone(&self, phone: &str) -> StorageResult<Option<User>>;
+    async fn create(&self, user: User) -> StorageResult<User>;
+    /// Applicants waiting for a decision, oldest first
+    async fn list_pending(&self) -> StorageResult<Vec<User>>;
+    /// Let the user sign up for sessions. `by` names the admin and is kept
+    /// in the user's status history along with the reason.
+    async fn approve(&self, id: Uuid, by: &str, reason: Option<String>) -> Result<User, UserError>;
+    async fn reject(&self, id: Uuid, by: &str, reason: Option<String>) -> Result<User, UserError>;
+    async fn suspend(&self, id: Uuid, by: &str, reason: Option<String>) -> Result<User, UserError>;
+    async fn status_history(&self, id: Uuid) -> StorageResult<Vec<UserStatusChange>>;
+    /// Sessions the user dropped out of after the cutoff, oldest first
+    async fn late_cancellations(&self, id: Uuid) -> StorageResult<Vec<LateCancellation>>;
+}
+
+#[async_trait::async_trait]
+pub trait ApplicationRepository: Send + Sync {
+    /// File an application. Submitting again with the same phone number or
+    /// email returns the existing application; the flag says whether this
+    /// call created it.
+    async fn submit(&self, application: Application) -> Result<(Application, bool), ApplicationError>;
+    async fn get(&self, id: Uuid) -> StorageResult<Option<Application>>;
+    /// Applications in `status`, oldest first
+    async fn list(&self, status: ApplicationStatus) -> StorageResult<Vec<Application>>;
+    /// Accept the application and create the approved member from it
+    async fn approve(
+        &self,
+        id: Uuid,
+        by: &str,
+        reason: Option<String>,
+    ) -> Result<(Application, User), ApplicationError>;
+    async fn reject(
+        &self,
+        id: Uuid,
+        by: &str,
+        reason: Option<String>,
+    ) -> Result<Application, ApplicationError>;
+    async fn history(&self, id: Uuid) -> StorageResult<Vec<ApplicationStatusChange>>;
+}
+
+#[async_trait::async_trait]
+pub trait AttendanceRepository: Send + Sync {
+    /// Record whether a confirmed player turned up to a session that has
+    /// ended. `by` names the organiser.
+    async fn mark(
+        &self,
+        session_id: Uuid,
+        user_id: Uuid,
+        status: AttendanceStatus,
+        by: &str,
+    ) -> Result<Attendance, AttendanceError>;
+    /// Mark the player as attending the session open for check-in at `now`
+    async fn check_in(&self, user: &User, now: DateTime<Utc>) -> Result<Attendance, AttendanceError>;
+    async fn for_session(&self, session_id: Uuid) -> StorageResult<Vec<Attendance>>;
+    async fn stats(&self, user_id: Uuid) -> StorageResult<AttendanceStats>;
 }
 
 #[async_trait::async_trait]
 pub trait VenueRepository: Send + Sync {
-    async fn get(&self, id: Uuid) -> Option<Venue>;
-    async fn list(&self) -> Vec<Venue>;
-    async fn create(&self, venue: Venue) -> Venue;
-}
\ No newline at end of file
+    async fn get(&self, id: Uuid) -> StorageResult<Option<Venue>>;
+    async fn list(&self) -> StorageResult<Vec<Venue>>;
+    async fn create(&self, venue: Venue) -> StorageResult<Venue>;
+}
+
+#[async_trait::async_trait]
+pub trait ConversationRepository: Send + Sync {
+    /// The caller's unexpired conversation state, if any
+    async fn get(&self, phone: &str) -> StorageResult<Option<ConversationState>>;
+    async fn save(&self, state: ConversationState) -> StorageResult<()>;
+    async fn clear(&self, phone: &str) -> StorageResult<()>;
+}
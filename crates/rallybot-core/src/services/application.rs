@@ -0,0 +1,318 @@
+use crate::{
+    application::{Application, ApplicationStatus, ApplicationStatusChange},
+    events::{DomainEvent, EventBus},
+    repository::ApplicationError,
+    storage::{Storage, StorageError, StorageResult},
+    user::User,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub struct ApplicationService<S> {
+    storage: Arc<S>,
+    events: EventBus,
+}
+
+impl<S: Storage> ApplicationService<S> {
+    pub fn new(storage: Arc<S>) -> Self {
+        Self {
+            storage,
+            events: EventBus::new(),
+        }
+    }
+
+    /// Publish the members created by approvals on `events`
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Validate and file an application. Applying again with a phone number
+    /// or email of a pending application returns that application, so a
+    /// resubmitted form never creates a second one; after a rejection a new
+    /// one is filed. The flag is true if this call created it.
+    pub async fn submit(
+        &self,
+        application: Application,
+    ) -> Result<(Application, bool), ApplicationError> {
+        application.validate().map_err(ApplicationError::Invalid)?;
+        if self
+            .storage
+            .get_user_by_phone(&application.phone_number)
+            .await?
+            .is_some()
+        {
+            return Err(ApplicationError::AlreadyMember);
+        }
+        if let Some(existing) = self.existing(&application).await? {
+            return Ok((existing, false));
+        }
+
+        match self.storage.create_application(application.clone()).await {
+            Ok(()) => Ok((application, true)),
+            // The same form submitted twice at once
+            Err(StorageError::Conflict(_)) => match self.existing(&application).await? {
+                Some(existing) => Ok((existing, false)),
+                None => Err(StorageError::Conflict("applications".to_string()).into()),
+            },
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub async fn get(&self, id: Uuid) -> StorageResult<Option<Application>> {
+        self.storage.get_application(id).await
+    }
+
+    pub async fn list(&self, status: ApplicationStatus) -> StorageResult<Vec<Application>> {
+        self.storage.list_applications_by_status(status).await
+    }
+
+    /// Accept the application and create the approved member in the same step
+    pub async fn approve(
+        &self,
+        id: Uuid,
+        by: &str,
+        reason: Option<String>,
+    ) -> Result<(Application, User), ApplicationError> {
+        let mut application = self.find(id).await?;
+        let user = application.to_user();
+        self.decide(&application, ApplicationStatus::Approved, Some(&user), by, reason)
+            .await?;
+        application.status = ApplicationStatus::Approved;
+        application.user_id = Some(user.id);
+        self.events.publish(DomainEvent::UserApproved {
+            user: Box::new(user.clone()),
+        });
+        Ok((application, user))
+    }
+
+    pub async fn reject(
+        &self,
+        id: Uuid,
+        by: &str,
+        reason: Option<String>,
+    ) -> Result<Application, ApplicationError> {
+        let mut application = self.find(id).await?;
+        self.decide(&application, ApplicationStatus::Rejected, None, by, reason)
+            .await?;
+        application.status = ApplicationStatus::Rejected;
+        Ok(application)
+    }
+
+    pub async fn history(&self, id: Uuid) -> StorageResult<Vec<ApplicationStatusChange>> {
+        self.storage.get_application_status_changes(id).await
+    }
+
+    async fn find(&self, id: Uuid) -> Result<Application, ApplicationError> {
+        self.storage
+            .get_application(id)
+            .await?
+            .ok_or(ApplicationError::ApplicationNotFound)
+    }
+
+    /// The pending application filed under this phone number, or failing
+    /// that this email
+    async fn existing(&self, application: &Application) -> StorageResult<Option<Application>> {
+        let mut found = self
+            .storage
+            .find_applications(&application.phone_number, &application.email)
+            .await?;
+        found.retain(|a| a.status == ApplicationStatus::Pending);
+        let by_phone = found
+            .iter()
+            .position(|a| a.phone_number == application.phone_number);
+        Ok(match by_phone {
+            Some(index) => Some(found.swap_remove(index)),
+            None => found.into_iter().next(),
+        })
+    }
+
+    async fn decide(
+        &self,
+        application: &Application,
+        to: ApplicationStatus,
+        member: Option<&User>,
+        by: &str,
+        reason: Option<String>,
+    ) -> Result<(), ApplicationError> {
+        if !application.status.can_become(to) {
+            return Err(ApplicationError::InvalidTransition {
+                from: application.status,
+                to,
+            });
+        }
+
+        let change = ApplicationStatusChange::new(application, to, by.to_string(), reason);
+        match self.storage.decide_application(&change, member).await {
+            Ok(true) => Ok(()),
+            // Another admin got there first; report what they left it as
+            Ok(false) => Err(ApplicationError::InvalidTransition {
+                from: self.find(application.id).await?.status,
+                to,
+            }),
+            // The phone number or email was taken by a member meanwhile
+            Err(StorageError::Conflict(constraint)) if constraint.starts_with("users_") => {
+                Err(ApplicationError::AlreadyMember)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        storage::InMemoryStorage,
+        user::{Gender, LookingFor, PlayFrequency, PreferredSide, SkillLevel},
+    };
+
+    fn application(phone: &str, email: &str) -> Application {
+        Application::new(
+            "Ana".to_string(),
+            "Silva".to_string(),
+            phone.to_string(),
+            email.to_string(),
+            "Lisbon".to_string(),
+            None,
+            "Engineer".to_string(),
+            "Acme".to_string(),
+            "Software".to_string(),
+            "https://www.linkedin.com/in/ana-silva".to_string(),
+            Gender::Female,
+            vec![SkillLevel::Intermediate],
+            PreferredSide::Left,
+            PlayFrequency::OnceWeek,
+            vec![LookingFor::SocialConnections],
+        )
+    }
+
+    fn setup() -> (Arc<InMemoryStorage>, ApplicationService<InMemoryStorage>) {
+        let storage = Arc::new(InMemoryStorage::new());
+        (storage.clone(), ApplicationService::new(storage))
+    }
+
+    #[tokio::test]
+    async fn resubmitting_returns_the_same_application() {
+        let (_, service) = setup();
+        let (first, created) = service
+            .submit(application("+351911111111", "ana@example.com"))
+            .await
+            .unwrap();
+        assert!(created);
+
+        let (again, created) = service
+            .submit(application("+351911111111", "ana.silva@example.com"))
+            .await
+            .unwrap();
+        assert!(!created);
+        assert_eq!(again.id, first.id);
+
+        let (by_email, _) = service
+            .submit(application("+351922222222", "ana@example.com"))
+            .await
+            .unwrap();
+        assert_eq!(by_email.id, first.id);
+        assert_eq!(service.list(ApplicationStatus::Pending).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn rejected_applicants_can_apply_again() {
+        let (_, service) = setup();
+        let (first, _) = service
+            .submit(application("+351911111111", "ana@example.com"))
+            .await
+            .unwrap();
+        service.reject(first.id, "admin", None).await.unwrap();
+
+        let (second, created) = service
+            .submit(application("+351911111111", "ana@example.com"))
+            .await
+            .unwrap();
+        assert!(created);
+        assert_ne!(second.id, first.id);
+        assert_eq!(second.status, ApplicationStatus::Pending);
+
+        // The new application is the one a resubmitted form finds
+        let (again, created) = service
+            .submit(application("+351911111111", "ana@example.com"))
+            .await
+            .unwrap();
+        assert!(!created);
+        assert_eq!(again.id, second.id);
+        assert_eq!(service.list(ApplicationStatus::Rejected).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn approving_creates_the_member() {
+        let (storage, service) = setup();
+        let (submitted, _) = service
+            .submit(application("+351911111111", "ana@example.com"))
+            .await
+            .unwrap();
+
+        let (approved, user) = service
+            .approve(submitted.id, "admin@rally.pt", Some("Plays at Rally".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(approved.user_id, Some(user.id));
+        let member = storage.get_user_by_phone("+351911111111").await.unwrap().unwrap();
+        assert_eq!(member.id, user.id);
+        assert!(member.is_approved());
+
+        let history = service.history(submitted.id).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].to_status, ApplicationStatus::Approved);
+        assert_eq!(history[0].reason.as_deref(), Some("Plays at Rally"));
+
+        // Approval is final, and members can't apply again
+        assert_eq!(
+            service.reject(submitted.id, "admin", None).await.unwrap_err(),
+            ApplicationError::InvalidTransition {
+                from: ApplicationStatus::Approved,
+                to: ApplicationStatus::Rejected
+            }
+        );
+        assert_eq!(
+            service
+                .submit(application("+351911111111", "ana@example.com"))
+                .await
+                .unwrap_err(),
+            ApplicationError::AlreadyMember
+        );
+    }
+
+    #[tokio::test]
+    async fn approval_fails_cleanly_when_the_email_is_taken() {
+        let (storage, service) = setup();
+        let (submitted, _) = service
+            .submit(application("+351911111111", "ana@example.com"))
+            .await
+            .unwrap();
+        let mut other = application("+351922222222", "ana@example.com").to_user();
+        other.first_name = "Another".to_string();
+        storage.create_user(other).await.unwrap();
+
+        assert_eq!(
+            service.approve(submitted.id, "admin", None).await.unwrap_err(),
+            ApplicationError::AlreadyMember
+        );
+        let unchanged = service.get(submitted.id).await.unwrap().unwrap();
+        assert_eq!(unchanged.status, ApplicationStatus::Pending);
+        assert!(service.history(submitted.id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn invalid_applications_are_not_filed() {
+        let (_, service) = setup();
+        let error = service
+            .submit(application("912345678", "ana@example.com"))
+            .await
+            .unwrap_err();
+        let ApplicationError::Invalid(fields) = error else {
+            panic!("expected a validation error, got {:?}", error);
+        };
+        assert_eq!(fields[0].field, "phone_number");
+        assert!(service.list(ApplicationStatus::Pending).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,247 @@
+use crate::{
+    attendance::{check_in_open, Attendance, AttendanceStats, AttendanceStatus},
+    registration::{Registration, RegistrationStatus},
+    repository::AttendanceError,
+    storage::{Storage, StorageResult},
+    user::User,
+};
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub struct AttendanceService<S> {
+    storage: Arc<S>,
+}
+
+impl<S: Storage> AttendanceService<S> {
+    pub fn new(storage: Arc<S>) -> Self {
+        Self { storage }
+    }
+
+    pub async fn mark(
+        &self,
+        session_id: Uuid,
+        user_id: Uuid,
+        status: AttendanceStatus,
+        by: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Attendance, AttendanceError> {
+        let session = self
+            .storage
+            .get_session(session_id)
+            .await?
+            .ok_or(AttendanceError::SessionNotFound)?;
+        if !session.is_scheduled() {
+            return Err(AttendanceError::SessionCancelled);
+        }
+        if now < session.ends_at() {
+            return Err(AttendanceError::SessionNotOver);
+        }
+
+        let registration = self
+            .confirmed(session_id, user_id)
+            .await?
+            .ok_or(AttendanceError::NotRegistered)?;
+        let attendance = Attendance::new(&registration, status, by.to_string());
+        self.storage.record_attendance(&attendance).await?;
+        Ok(attendance)
+    }
+
+    /// Check the player in to the earliest of their sessions open for
+    /// check-in at `now`
+    pub async fn check_in(
+        &self,
+        user: &User,
+        now: DateTime<Utc>,
+    ) -> Result<Attendance, AttendanceError> {
+        let mut open = Vec::new();
+        for registration in self.storage.get_user_registrations(user.id).await? {
+            if registration.status != RegistrationStatus::Confirmed {
+                continue;
+            }
+            if let Some(session) = self.storage.get_session(registration.session_id).await? {
+                if check_in_open(&session, now) {
+                    open.push((session.datetime, registration));
+                }
+            }
+        }
+        let (_, registration) = open
+            .into_iter()
+            .min_by_key(|(starts_at, _)| *starts_at)
+            .ok_or(AttendanceError::NothingToCheckIn)?;
+
+        let attendance = Attendance::new(
+            &registration,
+            AttendanceStatus::Attended,
+            user.phone_number.clone(),
+        );
+        self.storage.record_attendance(&attendance).await?;
+        Ok(attendance)
+    }
+
+    pub async fn for_session(&self, session_id: Uuid) -> StorageResult<Vec<Attendance>> {
+        self.storage.get_session_attendance(session_id).await
+    }
+
+    pub async fn stats(&self, user_id: Uuid) -> StorageResult<AttendanceStats> {
+        let records = self.storage.get_user_attendance(user_id).await?;
+        Ok(AttendanceStats::new(user_id, &records))
+    }
+
+    async fn confirmed(
+        &self,
+        session_id: Uuid,
+        user_id: Uuid,
+    ) -> StorageResult<Option<Registration>> {
+        let registrations = self.storage.get_registrations(session_id).await?;
+        Ok(registrations
+            .into_iter()
+            .find(|r| r.user_id == user_id && r.status == RegistrationStatus::Confirmed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::{Session, SessionType, Venue},
+        registration::RegistrationActor,
+        storage::InMemoryStorage,
+        user::{Gender, LookingFor, PlayFrequency, PreferredSide, UserStatus},
+    };
+    use chrono::Duration;
+
+    fn player(phone: &str) -> User {
+        let mut user = User::new(
+            "Ana".to_string(),
+            "Silva".to_string(),
+            phone.to_string(),
+            format!("{}@example.com", phone.trim_start_matches('+')),
+            "Lisbon".to_string(),
+            None,
+            "Engineer".to_string(),
+            "Acme".to_string(),
+            "Software".to_string(),
+            "https://linkedin.com/in/ana".to_string(),
+            Gender::Female,
+            Vec::new(),
+            PreferredSide::Left,
+            PlayFrequency::OnceWeek,
+            vec![LookingFor::SocialConnections],
+        );
+        user.status = UserStatus::Approved;
+        user
+    }
+
+    /// A two-player mixed session starting at `starts_at`, with a confirmed
+    /// player, a second confirmed player and a substitute
+    async fn setup(
+        starts_at: DateTime<Utc>,
+    ) -> (AttendanceService<InMemoryStorage>, Session, Vec<User>) {
+        let storage = Arc::new(InMemoryStorage::new());
+        let venue = Venue::new("Rally Padel".to_string(), "1 Court Road".to_string());
+        storage.create_venue(venue.clone()).await.unwrap();
+        let session = Session::new(SessionType::Mixed, starts_at, 90, venue.id, None, 2, None).unwrap();
+        storage.create_session(session.clone()).await.unwrap();
+
+        let mut users = Vec::new();
+        for phone in ["+351911111111", "+351922222222", "+351933333333"] {
+            let user = player(phone);
+            storage.create_user(user.clone()).await.unwrap();
+            storage
+                .register_within_capacity(session.id, user.id, RegistrationActor::Player)
+                .await
+                .unwrap();
+            users.push(user);
+        }
+        (AttendanceService::new(storage), session, users)
+    }
+
+    #[tokio::test]
+    async fn organiser_marks_attendance_once_the_session_is_over() {
+        let (service, session, users) = setup(Utc::now() - Duration::hours(3)).await;
+        let now = Utc::now();
+
+        service
+            .mark(session.id, users[0].id, AttendanceStatus::Attended, "admin", now)
+            .await
+            .unwrap();
+        service
+            .mark(session.id, users[1].id, AttendanceStatus::Attended, "admin", now)
+            .await
+            .unwrap();
+        // A correction replaces the earlier mark
+        service
+            .mark(session.id, users[1].id, AttendanceStatus::NoShow, "admin", now)
+            .await
+            .unwrap();
+        assert_eq!(
+            service
+                .mark(session.id, users[2].id, AttendanceStatus::Attended, "admin", now)
+                .await,
+            Err(AttendanceError::NotRegistered)
+        );
+
+        assert_eq!(service.for_session(session.id).await.unwrap().len(), 2);
+        let stats = service.stats(users[1].id).await.unwrap();
+        assert_eq!((stats.attended, stats.no_show, stats.excused), (0, 1, 0));
+
+        let before_the_end = session.ends_at() - Duration::minutes(1);
+        assert_eq!(
+            service
+                .mark(session.id, users[0].id, AttendanceStatus::NoShow, "admin", before_the_end)
+                .await,
+            Err(AttendanceError::SessionNotOver)
+        );
+    }
+
+    #[tokio::test]
+    async fn players_check_in_around_the_start() {
+        let starts_at = Utc::now() + Duration::minutes(10);
+        let (service, session, users) = setup(starts_at).await;
+
+        let attendance = service.check_in(&users[0], Utc::now()).await.unwrap();
+        assert_eq!(attendance.session_id, session.id);
+        assert_eq!(attendance.status, AttendanceStatus::Attended);
+        assert_eq!(attendance.recorded_by, users[0].phone_number);
+
+        // Substitutes aren't playing, and check-in closes when the session ends
+        assert_eq!(
+            service.check_in(&users[2], Utc::now()).await,
+            Err(AttendanceError::NothingToCheckIn)
+        );
+        assert_eq!(
+            service.check_in(&users[1], session.ends_at()).await,
+            Err(AttendanceError::NothingToCheckIn)
+        );
+    }
+
+    #[tokio::test]
+    async fn recorded_attendance_stops_the_player_withdrawing() {
+        use crate::{repository::RegistrationError, services::RegistrationService};
+
+        // Checked in ahead of the start
+        let (service, session, users) = setup(Utc::now() + Duration::minutes(10)).await;
+        let registrations = RegistrationService::new(service.storage.clone());
+        service.check_in(&users[0], Utc::now()).await.unwrap();
+        assert!(matches!(
+            registrations.unregister_user(session.id, users[0].id).await,
+            Err(RegistrationError::SessionStarted)
+        ));
+        assert_eq!(service.for_session(session.id).await.unwrap().len(), 1);
+
+        // Marked a no-show after the session
+        let (service, session, users) = setup(Utc::now() - Duration::hours(3)).await;
+        let registrations = RegistrationService::new(service.storage.clone());
+        service
+            .mark(session.id, users[0].id, AttendanceStatus::NoShow, "admin", Utc::now())
+            .await
+            .unwrap();
+        assert!(matches!(
+            registrations.unregister_user(session.id, users[0].id).await,
+            Err(RegistrationError::SessionStarted)
+        ));
+        let stats = service.stats(users[0].id).await.unwrap();
+        assert_eq!(stats.no_show, 1);
+    }
+}
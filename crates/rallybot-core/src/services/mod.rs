@@ -1,5 +1,13 @@
+pub mod application;
+pub mod attendance;
 pub mod registration;
 pub mod session;
+pub mod template;
+pub mod user;
 
+pub use application::ApplicationService;
+pub use attendance::AttendanceService;
 pub use registration::RegistrationService;
-pub use session::SessionService;
\ No newline at end of file
+pub use session::SessionService;
+pub use template::TemplateService;
+pub use user::UserService;
@@ -1,17 +1,64 @@
 use crate::{
-    registration::{Registration, RegistrationStatus},
+    cancellation::{CancellationPolicy, LateCancellation},
+    eligibility::EligibilityPolicy,
+    events::{DomainEvent, EventBus},
+    models::{Session, SessionQuery},
+    registration::{
+        LapsedPromotion, Registration, RegistrationActor, RegistrationAttempt, RegistrationEvent,
+        RegistrationStatus, Withdrawal,
+    },
     repository::RegistrationError,
-    storage::Storage,
+    services::session::page,
+    storage::{Storage, StorageResult},
+    user::User,
 };
+use chrono::{DateTime, Duration, Utc};
 use uuid::Uuid;
 
 pub struct RegistrationService<S> {
     storage: S,
+    policy: EligibilityPolicy,
+    /// How long a promoted substitute has to confirm; `None` confirms them
+    /// straight away
+    confirmation_window: Option<Duration>,
+    cancellation: CancellationPolicy,
+    events: EventBus,
 }
 
 impl<S> RegistrationService<S> {
     pub fn new(storage: S) -> Self {
-        Self { storage }
+        Self::with_policy(storage, EligibilityPolicy::default())
+    }
+
+    pub fn with_policy(storage: S, policy: EligibilityPolicy) -> Self {
+        Self {
+            storage,
+            policy,
+            confirmation_window: None,
+            cancellation: CancellationPolicy::default(),
+            events: EventBus::new(),
+        }
+    }
+
+    /// Make promoted substitutes confirm within `window` or pass the spot on
+    pub fn with_confirmation_window(mut self, window: Option<Duration>) -> Self {
+        self.confirmation_window = window;
+        self
+    }
+
+    pub fn with_cancellation_policy(mut self, cancellation: CancellationPolicy) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    /// Publish sign-ups, withdrawals and promotions on `events`
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = events;
+        self
+    }
+
+    pub fn policy(&self) -> &EligibilityPolicy {
+        &self.policy
     }
 }
 
@@ -21,92 +68,255 @@ impl<S: Storage> RegistrationService<S> {
         session_id: Uuid,
         user_id: Uuid,
     ) -> Result<RegistrationStatus, RegistrationError> {
-        // Check session exists
-        if self.storage.get_session(session_id).await.is_none() {
-            return Err(RegistrationError::SessionNotFound);
+        self.register(session_id, user_id, true).await
+    }
+
+    /// Sign a player up whatever their skill level or late cancellations, for
+    /// organisers placing someone by hand
+    pub async fn place_player(
+        &self,
+        session_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<RegistrationStatus, RegistrationError> {
+        self.register(session_id, user_id, false).await
+    }
+
+    /// Sessions matching `query` that `user` is eligible for. Paging applies
+    /// to the eligible sessions.
+    pub async fn eligible_sessions(
+        &self,
+        query: &SessionQuery,
+        user: &User,
+    ) -> StorageResult<Vec<Session>> {
+        let candidates = SessionQuery {
+            limit: None,
+            offset: 0,
+            ..query.clone()
+        };
+        let sessions = self.storage.list_sessions(&candidates).await?;
+        let eligible = sessions
+            .into_iter()
+            .filter(|session| self.policy.allows(user, session));
+        Ok(page(eligible, query))
+    }
+
+    async fn register(
+        &self,
+        session_id: Uuid,
+        user_id: Uuid,
+        by_player: bool,
+    ) -> Result<RegistrationStatus, RegistrationError> {
+        // Check session exists and still takes sign-ups
+        let session = self
+            .storage
+            .get_session(session_id)
+            .await?
+            .ok_or(RegistrationError::SessionNotFound)?;
+        if !session.is_scheduled() {
+            return Err(RegistrationError::SessionClosed);
         }
 
         // Check user exists and is approved
         let user = self
             .storage
             .get_user(user_id)
-            .await
+            .await?
             .ok_or(RegistrationError::UserNotFound)?;
 
-        if !user.is_approved {
+        if !user.is_approved() {
             return Err(RegistrationError::UserNotApproved);
         }
-
-        // Check if already registered
-        if self.storage.registration_exists(session_id, user_id).await {
-            return Err(RegistrationError::AlreadyRegistered);
+        if by_player && !self.policy.allows(&user, &session) {
+            return Err(RegistrationError::SkillLevelMismatch);
+        }
+        if by_player && self.too_many_late_cancellations(user_id).await? {
+            return Err(RegistrationError::TooManyLateCancellations);
         }
 
-        // Count confirmed registrations
-        let registrations = self.storage.get_registrations(session_id).await;
-        let confirmed_count = registrations
-            .iter()
-            .filter(|r| r.status == RegistrationStatus::Confirmed)
-            .count();
-
-        // Determine status based on capacity
-        let status = if confirmed_count < 4 {
-            RegistrationStatus::Confirmed
+        let actor = if by_player {
+            RegistrationActor::Player
         } else {
-            RegistrationStatus::Substitute
+            RegistrationActor::Organiser
         };
-
-        // Create registration
-        let registration = Registration::new(user_id, session_id, status);
-        self.storage.create_registration(registration).await;
-
-        Ok(status)
+        // Capacity check and insert happen atomically in storage
+        match self
+            .storage
+            .register_within_capacity(session_id, user_id, actor)
+            .await?
+        {
+            RegistrationAttempt::Created(registration) => {
+                let status = registration.status;
+                self.events.publish(DomainEvent::UserRegistered { registration });
+                Ok(status)
+            }
+            RegistrationAttempt::AlreadyRegistered => Err(RegistrationError::AlreadyRegistered),
+            RegistrationAttempt::Full => Err(RegistrationError::SessionFull),
+            RegistrationAttempt::SessionNotFound => Err(RegistrationError::SessionNotFound),
+            RegistrationAttempt::SessionClosed => Err(RegistrationError::SessionClosed),
+        }
     }
 
-    pub async fn get_session_registrations(&self, session_id: Uuid) -> Vec<Registration> {
+    pub async fn get_session_registrations(&self, session_id: Uuid) -> StorageResult<Vec<Registration>> {
         self.storage.get_registrations(session_id).await
     }
 
-    pub async fn get_user_sessions(&self, user_id: Uuid) -> Vec<Uuid> {
-        let registrations = self.storage.get_user_registrations(user_id).await;
-        registrations.into_iter().map(|r| r.session_id).collect()
+    /// Everything that happened to the session's registrations, in order
+    pub async fn registration_history(
+        &self,
+        session_id: Uuid,
+    ) -> StorageResult<Vec<RegistrationEvent>> {
+        self.storage.get_registration_events(session_id).await
+    }
+
+    pub async fn get_user_sessions(&self, user_id: Uuid) -> StorageResult<Vec<Uuid>> {
+        let registrations = self.storage.get_user_registrations(user_id).await?;
+        Ok(registrations.into_iter().map(|r| r.session_id).collect())
     }
 
+    /// Returns the substitutes promoted into the freed place. After the
+    /// session's cancellation cutoff a confirmed player can only drop out if a
+    /// substitute takes their place, and it is recorded against them.
     pub async fn unregister_user(
         &self,
         session_id: Uuid,
         user_id: Uuid,
-    ) -> Result<(), RegistrationError> {
-        // Get all registrations for this session
-        let registrations = self.storage.get_registrations(session_id).await;
-        
-        // Find the user's registration
-        let user_registration = registrations
-            .iter()
-            .find(|r| r.user_id == user_id)
+    ) -> Result<Vec<Registration>, RegistrationError> {
+        let now = Utc::now();
+        let session = self
+            .storage
+            .get_session(session_id)
+            .await?
             .ok_or(RegistrationError::NotRegistered)?;
-        
-        let was_confirmed = user_registration.status == RegistrationStatus::Confirmed;
-        
-        // Delete the registration
-        if !self.storage.delete_registration(session_id, user_id).await {
-            return Err(RegistrationError::NotRegistered);
+        if now >= session.datetime {
+            return Err(RegistrationError::SessionStarted);
         }
-        
-        // If user was confirmed, promote the oldest substitute
-        if was_confirmed {
-            if let Some(first_substitute) = registrations
-                .iter()
-                .filter(|r| r.status == RegistrationStatus::Substitute)
-                .min_by_key(|r| r.created_at)
-            {
-                let mut promoted = first_substitute.clone();
-                promoted.status = RegistrationStatus::Confirmed;
-                self.storage.update_registration(promoted).await;
+        let confirm_by = self.confirm_by(&session, now);
+        let late = self
+            .cancellation
+            .is_late(&session, now)
+            .then(|| LateCancellation::new(user_id, &session, now));
+
+        // Removal, substitute promotion and the late record happen atomically
+        // in storage
+        match self
+            .storage
+            .unregister_and_promote(
+                session_id,
+                user_id,
+                confirm_by,
+                late.as_ref(),
+                RegistrationActor::Player,
+            )
+            .await?
+        {
+            Withdrawal::Removed {
+                registration,
+                promoted,
+                late_cancelled,
+            } => {
+                self.events.publish(DomainEvent::UserUnregistered {
+                    registration,
+                    late: late_cancelled,
+                });
+                self.publish_promotions(&promoted);
+                Ok(promoted)
             }
+            Withdrawal::NotRegistered => Err(RegistrationError::NotRegistered),
+            Withdrawal::NoReplacement => Err(RegistrationError::TooLateToCancel),
+            Withdrawal::AttendanceRecorded => Err(RegistrationError::SessionStarted),
+        }
+    }
+
+    pub async fn late_cancellations(&self, user_id: Uuid) -> StorageResult<Vec<LateCancellation>> {
+        self.storage.list_late_cancellations(user_id).await
+    }
+
+    /// Keep a spot the player was promoted into
+    pub async fn confirm_spot(
+        &self,
+        session_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Registration, RegistrationError> {
+        if let Some(registration) = self
+            .storage
+            .confirm_registration(session_id, user_id, Utc::now())
+            .await?
+        {
+            return Ok(registration);
+        }
+        if self.storage.registration_exists(session_id, user_id).await? {
+            Err(RegistrationError::NoPendingConfirmation)
+        } else {
+            Err(RegistrationError::NotRegistered)
+        }
+    }
+
+    /// The user's promotions still waiting to be confirmed
+    pub async fn pending_confirmations(&self, user_id: Uuid) -> StorageResult<Vec<Registration>> {
+        let now = Utc::now();
+        let mut registrations = self.storage.get_user_registrations(user_id).await?;
+        registrations.retain(|r| r.awaits_confirmation(now));
+        Ok(registrations)
+    }
+
+    /// Drop every promotion not confirmed by `now` and promote the next
+    /// substitutes in their place, who get a fresh deadline
+    pub async fn expire_lapsed_promotions(
+        &self,
+        now: DateTime<Utc>,
+    ) -> StorageResult<Vec<LapsedPromotion>> {
+        let mut expired = Vec::new();
+        for lapsed in self.storage.list_lapsed_promotions(now).await? {
+            let Some(session) = self.storage.get_session(lapsed.session_id).await? else {
+                continue;
+            };
+            let confirm_by = self.confirm_by(&session, now);
+            // Confirmed or withdrawn since it was listed
+            let Some(promoted) = self
+                .storage
+                .expire_and_promote(lapsed.session_id, lapsed.user_id, now, confirm_by)
+                .await?
+            else {
+                continue;
+            };
+            self.events.publish(DomainEvent::PromotionLapsed {
+                registration: lapsed.clone(),
+            });
+            self.publish_promotions(&promoted);
+            expired.push(LapsedPromotion { lapsed, promoted });
+        }
+        Ok(expired)
+    }
+
+    /// Deadline for a substitute promoted at `now`, never after the session
+    /// starts
+    fn confirm_by(&self, session: &Session, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.confirmation_window
+            .map(|window| (now + window).min(session.datetime))
+    }
+
+    fn publish_promotions(&self, promoted: &[Registration]) {
+        for registration in promoted {
+            self.events.publish(DomainEvent::SubstitutePromoted {
+                registration: registration.clone(),
+            });
         }
-        
-        Ok(())
+    }
+
+    /// The user has reached the policy's limit of recent late cancellations
+    async fn too_many_late_cancellations(&self, user_id: Uuid) -> StorageResult<bool> {
+        let Some(limit) = self.cancellation.limit else {
+            return Ok(false);
+        };
+        let since = Utc::now() - limit.within;
+        let recent = self
+            .storage
+            .list_late_cancellations(user_id)
+            .await?
+            .iter()
+            .filter(|c| c.cancelled_at > since)
+            .count();
+        Ok(recent >= limit.max)
     }
 }
 
@@ -114,9 +324,9 @@ impl<S: Storage> RegistrationService<S> {
 mod tests {
     use super::*;
     use crate::{
-        models::{Session, SessionType, Venue},
+        models::{Session, SessionQuery, SessionType, Venue},
         storage::InMemoryStorage,
-        user::{Gender, SkillLevel, PreferredSide, PlayFrequency, LookingFor, User},
+        user::{Gender, SkillLevel, PreferredSide, PlayFrequency, LookingFor, User, UserStatus},
     };
     use std::sync::Arc;
     use chrono::Utc;
@@ -134,7 +344,7 @@ mod tests {
                 address::en::CityName().fake::<String>()
             ),
         );
-        storage.create_venue(venue.clone()).await;
+        storage.create_venue(venue.clone()).await.unwrap();
         
         // Create a test session
         let session = Session::new(
@@ -143,8 +353,10 @@ mod tests {
             90,
             venue.id,
             Some(SkillLevel::Intermediate),
+            Session::DEFAULT_MAX_PLAYERS,
+            None,
         ).expect("Valid session");
-        storage.create_session(session).await;
+        storage.create_session(session).await.unwrap();
         
         storage
     }
@@ -164,12 +376,8 @@ mod tests {
             company::en::Industry().fake(),
             format!("https://linkedin.com/in/{}", internet::en::Username().fake::<String>()),
             if rng.gen_bool(0.5) { Gender::Male } else { Gender::Female },
-            vec![
-                *[SkillLevel::Beginner, SkillLevel::LowIntermediate, SkillLevel::Intermediate, 
-                  SkillLevel::UpperIntermediate, SkillLevel::Advanced]
-                    .choose(&mut rng)
-                    .unwrap()
-            ],
+            // Eligible for every session these tests create
+            vec![SkillLevel::Intermediate],
             *[PreferredSide::Right, PreferredSide::Left, PreferredSide::Flexible]
                 .choose(&mut rng)
                 .unwrap(),
@@ -182,8 +390,10 @@ mod tests {
                     .unwrap()
             ],
         );
-        user.is_approved = approved;
-        storage.create_user(user.clone()).await;
+        if approved {
+            user.status = UserStatus::Approved;
+        }
+        storage.create_user(user.clone()).await.unwrap();
         user
     }
 
@@ -195,7 +405,7 @@ mod tests {
         let service = RegistrationService::new(storage.clone());
         
         // Get the session we created
-        let sessions = storage.list_sessions(None).await;
+        let sessions = storage.list_sessions(&SessionQuery::default()).await.unwrap();
         let session = &sessions[0];
         
         let result = service.register_user(session.id, user.id).await;
@@ -211,7 +421,7 @@ mod tests {
         
         let service = RegistrationService::new(storage.clone());
         
-        let sessions = storage.list_sessions(None).await;
+        let sessions = storage.list_sessions(&SessionQuery::default()).await.unwrap();
         let session = &sessions[0];
         
         let result = service.register_user(session.id, user.id).await;
@@ -225,7 +435,7 @@ mod tests {
         let storage = create_test_storage().await;
         let service = RegistrationService::new(storage.clone());
         
-        let sessions = storage.list_sessions(None).await;
+        let sessions = storage.list_sessions(&SessionQuery::default()).await.unwrap();
         let session = &sessions[0];
         
         // Register 4 users (all should be confirmed)
@@ -250,7 +460,7 @@ mod tests {
         
         let service = RegistrationService::new(storage.clone());
         
-        let sessions = storage.list_sessions(None).await;
+        let sessions = storage.list_sessions(&SessionQuery::default()).await.unwrap();
         let session = &sessions[0];
         
         // First registration
@@ -277,12 +487,25 @@ mod tests {
         assert!(matches!(result.unwrap_err(), RegistrationError::SessionNotFound));
     }
 
+    #[tokio::test]
+    async fn register_for_cancelled_session_fails() {
+        let storage = create_test_storage().await;
+        let user = create_test_user(&storage, true).await;
+        let session_id = storage.list_sessions(&SessionQuery::default()).await.unwrap()[0].id;
+        storage.cancel_session(session_id, "Court closed").await.unwrap();
+
+        let service = RegistrationService::new(storage.clone());
+        let result = service.register_user(session_id, user.id).await;
+
+        assert!(matches!(result.unwrap_err(), RegistrationError::SessionClosed));
+    }
+
     #[tokio::test]
     async fn register_nonexistent_user_fails() {
         let storage = create_test_storage().await;
         let service = RegistrationService::new(storage.clone());
         
-        let sessions = storage.list_sessions(None).await;
+        let sessions = storage.list_sessions(&SessionQuery::default()).await.unwrap();
         let session = &sessions[0];
         
         let fake_user_id = Uuid::new_v4();
@@ -291,4 +514,427 @@ mod tests {
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), RegistrationError::UserNotFound));
     }
-}
\ No newline at end of file
+
+    async fn create_session_with_capacity(
+        storage: &Arc<InMemoryStorage>,
+        max_players: i32,
+        max_substitutes: Option<i32>,
+    ) -> Session {
+        let venue = storage.list_venues().await.unwrap().remove(0);
+        let session = Session::new(
+            SessionType::Coaching,
+            Utc::now() + chrono::Duration::days(2),
+            90,
+            venue.id,
+            Some(SkillLevel::Intermediate),
+            max_players,
+            max_substitutes,
+        )
+        .expect("Valid session");
+        storage.create_session(session.clone()).await.unwrap();
+        session
+    }
+
+    #[tokio::test]
+    async fn larger_session_confirms_up_to_max_players() {
+        let storage = create_test_storage().await;
+        let service = RegistrationService::new(storage.clone());
+        let session = create_session_with_capacity(&storage, 8, None).await;
+
+        for _ in 0..8 {
+            let user = create_test_user(&storage, true).await;
+            let result = service.register_user(session.id, user.id).await;
+            assert_eq!(result.unwrap(), RegistrationStatus::Confirmed);
+        }
+
+        let user9 = create_test_user(&storage, true).await;
+        let result = service.register_user(session.id, user9.id).await;
+        assert_eq!(result.unwrap(), RegistrationStatus::Substitute);
+    }
+
+    #[tokio::test]
+    async fn full_substitutes_list_rejects_registration() {
+        let storage = create_test_storage().await;
+        let service = RegistrationService::new(storage.clone());
+        let session = create_session_with_capacity(&storage, 2, Some(1)).await;
+
+        for expected in [
+            RegistrationStatus::Confirmed,
+            RegistrationStatus::Confirmed,
+            RegistrationStatus::Substitute,
+        ] {
+            let user = create_test_user(&storage, true).await;
+            assert_eq!(service.register_user(session.id, user.id).await.unwrap(), expected);
+        }
+
+        let user = create_test_user(&storage, true).await;
+        let result = service.register_user(session.id, user.id).await;
+        assert!(matches!(result.unwrap_err(), RegistrationError::SessionFull));
+    }
+
+    #[tokio::test]
+    async fn unregister_promotes_oldest_substitute_up_to_capacity() {
+        let storage = create_test_storage().await;
+        let service = RegistrationService::new(storage.clone());
+        let session = create_session_with_capacity(&storage, 2, None).await;
+
+        let mut users = Vec::new();
+        for _ in 0..4 {
+            let user = create_test_user(&storage, true).await;
+            service.register_user(session.id, user.id).await.unwrap();
+            users.push(user);
+            tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+        }
+
+        service.unregister_user(session.id, users[0].id).await.unwrap();
+
+        let registrations = service.get_session_registrations(session.id).await.unwrap();
+        let status_of = |user: &User| {
+            registrations
+                .iter()
+                .find(|r| r.user_id == user.id)
+                .map(|r| r.status)
+        };
+        assert_eq!(status_of(&users[1]), Some(RegistrationStatus::Confirmed));
+        assert_eq!(status_of(&users[2]), Some(RegistrationStatus::Confirmed));
+        assert_eq!(status_of(&users[3]), Some(RegistrationStatus::Substitute));
+    }
+
+    #[tokio::test]
+    async fn unconfirmed_promotion_passes_to_the_next_substitute() {
+        let storage = create_test_storage().await;
+        let service = RegistrationService::new(storage.clone())
+            .with_confirmation_window(Some(Duration::hours(2)));
+        let session = create_session_with_capacity(&storage, 2, None).await;
+
+        let mut users = Vec::new();
+        for _ in 0..4 {
+            let user = create_test_user(&storage, true).await;
+            service.register_user(session.id, user.id).await.unwrap();
+            users.push(user);
+            tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+        }
+
+        let promoted = service.unregister_user(session.id, users[0].id).await.unwrap();
+        assert_eq!(promoted.len(), 1);
+        assert_eq!(promoted[0].user_id, users[2].id);
+        let confirm_by = promoted[0].confirm_by.unwrap();
+        assert_eq!(
+            service.pending_confirmations(users[2].id).await.unwrap().len(),
+            1
+        );
+
+        // Nothing lapses before the deadline
+        let before = confirm_by - Duration::minutes(1);
+        assert!(service.expire_lapsed_promotions(before).await.unwrap().is_empty());
+
+        let expired = service
+            .expire_lapsed_promotions(confirm_by)
+            .await
+            .unwrap();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].lapsed.user_id, users[2].id);
+        assert_eq!(expired[0].promoted[0].user_id, users[3].id);
+        assert!(expired[0].promoted[0].confirm_by.is_some());
+
+        // The next substitute confirms in time and keeps the spot
+        let confirmed = service.confirm_spot(session.id, users[3].id).await.unwrap();
+        assert_eq!(confirmed.confirm_by, None);
+        assert!(matches!(
+            service.confirm_spot(session.id, users[3].id).await,
+            Err(RegistrationError::NoPendingConfirmation)
+        ));
+        assert!(matches!(
+            service.confirm_spot(session.id, users[2].id).await,
+            Err(RegistrationError::NotRegistered)
+        ));
+        let far_future = Utc::now() + Duration::days(30);
+        assert!(service.expire_lapsed_promotions(far_future).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn promotion_needs_no_confirmation_by_default() {
+        let storage = create_test_storage().await;
+        let service = RegistrationService::new(storage.clone());
+        let session = create_session_with_capacity(&storage, 2, None).await;
+
+        let mut users = Vec::new();
+        for _ in 0..3 {
+            let user = create_test_user(&storage, true).await;
+            service.register_user(session.id, user.id).await.unwrap();
+            users.push(user);
+            tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+        }
+
+        let promoted = service.unregister_user(session.id, users[0].id).await.unwrap();
+        assert_eq!(promoted[0].user_id, users[2].id);
+        assert_eq!(promoted[0].confirm_by, None);
+    }
+
+    #[tokio::test]
+    async fn summary_counts_sign_ups_in_order() {
+        let storage = create_test_storage().await;
+        let service = RegistrationService::new(storage.clone());
+        let session = create_session_with_capacity(&storage, 2, None).await;
+
+        let mut users = Vec::new();
+        for _ in 0..3 {
+            let user = create_test_user(&storage, true).await;
+            service.register_user(session.id, user.id).await.unwrap();
+            users.push(user);
+            tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+        }
+
+        let summaries = storage
+            .list_session_summaries(&SessionQuery::of_type(SessionType::Coaching))
+            .await
+            .unwrap();
+        assert_eq!(summaries.len(), 1);
+        let summary = &summaries[0];
+        assert_eq!(summary.venue.id, session.venue_id);
+        assert_eq!(summary.confirmed_count, 2);
+        assert_eq!(summary.substitute_count, 1);
+        assert_eq!(summary.free_slots(), 0);
+        assert_eq!(
+            summary.confirmed_first_names,
+            vec![users[0].first_name.clone(), users[1].first_name.clone()]
+        );
+    }
+
+    #[tokio::test]
+    async fn level_mismatch_is_rejected_unless_an_organiser_places_them() {
+        let storage = create_test_storage().await;
+        let service = RegistrationService::new(storage.clone());
+        let venue = storage.list_venues().await.unwrap().remove(0);
+        let elite = Session::new(
+            SessionType::League,
+            Utc::now() + chrono::Duration::days(2),
+            90,
+            venue.id,
+            Some(SkillLevel::Elite),
+            Session::DEFAULT_MAX_PLAYERS,
+            None,
+        )
+        .unwrap();
+        storage.create_session(elite.clone()).await.unwrap();
+        let user = create_test_user(&storage, true).await;
+
+        let result = service.register_user(elite.id, user.id).await;
+        assert!(matches!(result.unwrap_err(), RegistrationError::SkillLevelMismatch));
+
+        let status = service.place_player(elite.id, user.id).await.unwrap();
+        assert_eq!(status, RegistrationStatus::Confirmed);
+    }
+
+    #[tokio::test]
+    async fn eligible_sessions_leave_out_other_levels() {
+        let storage = create_test_storage().await;
+        let service = RegistrationService::new(storage.clone());
+        let user = create_test_user(&storage, true).await;
+        let venue = storage.list_venues().await.unwrap().remove(0);
+        for level in [SkillLevel::Beginner, SkillLevel::UpperIntermediate] {
+            let session = Session::new(SessionType::Social, Utc::now(), 90, venue.id, Some(level), Session::DEFAULT_MAX_PLAYERS, None).unwrap();
+            storage.create_session(session).await.unwrap();
+        }
+
+        // The fixture's Intermediate session and the one a level above
+        let eligible = service
+            .eligible_sessions(&SessionQuery::of_type(SessionType::Social), &user)
+            .await
+            .unwrap();
+        let mut levels: Vec<_> = eligible.iter().filter_map(|s| s.skill_level).collect();
+        levels.sort();
+        assert_eq!(levels, vec![SkillLevel::Intermediate, SkillLevel::UpperIntermediate]);
+
+        let query = SessionQuery { limit: Some(1), offset: 1, ..SessionQuery::of_type(SessionType::Social) };
+        assert_eq!(service.eligible_sessions(&query, &user).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn late_cancellation_needs_a_substitute_and_is_counted() {
+        use crate::cancellation::{CancellationPolicy, LateCancelLimit};
+
+        let storage = create_test_storage().await;
+        // Sessions here start in two days, so a three day cutoff makes every
+        // cancellation late
+        let policy = CancellationPolicy {
+            coaching: Duration::days(3),
+            limit: Some(LateCancelLimit {
+                max: 1,
+                within: Duration::days(30),
+            }),
+            ..CancellationPolicy::default()
+        };
+        let service = RegistrationService::new(storage.clone()).with_cancellation_policy(policy);
+        let session = create_session_with_capacity(&storage, 2, None).await;
+
+        let mut users = Vec::new();
+        for _ in 0..3 {
+            let user = create_test_user(&storage, true).await;
+            service.register_user(session.id, user.id).await.unwrap();
+            users.push(user);
+            tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+        }
+
+        // A substitute takes the place, so the player may go
+        let promoted = service.unregister_user(session.id, users[0].id).await.unwrap();
+        assert_eq!(promoted[0].user_id, users[2].id);
+        let late = service.late_cancellations(users[0].id).await.unwrap();
+        assert_eq!(late.len(), 1);
+        assert_eq!(late[0].session_id, session.id);
+
+        // Nobody left to take the place
+        assert!(matches!(
+            service.unregister_user(session.id, users[1].id).await,
+            Err(RegistrationError::TooLateToCancel)
+        ));
+        assert!(storage.registration_exists(session.id, users[1].id).await.unwrap());
+        assert!(service.late_cancellations(users[1].id).await.unwrap().is_empty());
+
+        // The limit stops the player signing up, but not an organiser adding them
+        let other = create_session_with_capacity(&storage, 4, None).await;
+        assert!(matches!(
+            service.register_user(other.id, users[0].id).await,
+            Err(RegistrationError::TooManyLateCancellations)
+        ));
+        assert_eq!(
+            service
+                .place_player(other.id, users[0].id)
+                .await
+                .unwrap(),
+            RegistrationStatus::Confirmed
+        );
+    }
+
+    #[tokio::test]
+    async fn registration_history_keeps_every_transition() {
+        use crate::registration::RegistrationEventKind as Kind;
+
+        let storage = create_test_storage().await;
+        let service = RegistrationService::new(storage.clone());
+        let session = create_session_with_capacity(&storage, 2, None).await;
+
+        let mut users = Vec::new();
+        for _ in 0..3 {
+            let user = create_test_user(&storage, true).await;
+            service.register_user(session.id, user.id).await.unwrap();
+            users.push(user);
+            tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+        }
+        service.unregister_user(session.id, users[0].id).await.unwrap();
+
+        // An organiser moves a player back to the substitutes list by hand
+        let mut demoted = storage.get_registrations(session.id).await.unwrap()[0].clone();
+        demoted.status = RegistrationStatus::Substitute;
+        assert!(storage.update_registration(demoted).await.unwrap());
+
+        let history = service.registration_history(session.id).await.unwrap();
+        let entries: Vec<_> = history.iter().map(|e| (e.user_id, e.kind, e.actor)).collect();
+        assert_eq!(
+            entries,
+            vec![
+                (users[0].id, Kind::Registered, RegistrationActor::Player),
+                (users[1].id, Kind::Registered, RegistrationActor::Player),
+                (users[2].id, Kind::Registered, RegistrationActor::Player),
+                (users[0].id, Kind::Cancelled, RegistrationActor::Player),
+                (users[2].id, Kind::Promoted, RegistrationActor::System),
+                (users[1].id, Kind::Demoted, RegistrationActor::Organiser),
+            ]
+        );
+        assert_eq!(history[2].status, Some(RegistrationStatus::Substitute));
+        assert_eq!(history[3].status, None);
+    }
+
+    struct Forward(tokio::sync::mpsc::UnboundedSender<DomainEvent>);
+
+    #[async_trait::async_trait]
+    impl crate::events::EventSubscriber for Forward {
+        async fn handle(&self, event: &DomainEvent) {
+            let _ = self.0.send(event.clone());
+        }
+    }
+
+    #[tokio::test]
+    async fn publishes_sign_ups_withdrawals_and_promotions() {
+        let storage = create_test_storage().await;
+        let events = EventBus::new();
+        let (sender, mut received) = tokio::sync::mpsc::unbounded_channel();
+        events.subscribe(Arc::new(Forward(sender)));
+        let service = RegistrationService::new(storage.clone()).with_events(events);
+        let session = create_session_with_capacity(&storage, 2, None).await;
+
+        let mut users = Vec::new();
+        for _ in 0..3 {
+            let user = create_test_user(&storage, true).await;
+            service.register_user(session.id, user.id).await.unwrap();
+            users.push(user);
+            tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+        }
+        service.unregister_user(session.id, users[0].id).await.unwrap();
+        // Refused, so nothing is published
+        service.unregister_user(session.id, users[0].id).await.unwrap_err();
+
+        let mut seen = Vec::new();
+        while let Ok(Some(event)) =
+            tokio::time::timeout(std::time::Duration::from_millis(100), received.recv()).await
+        {
+            seen.push(match event {
+                DomainEvent::UserRegistered { registration } => ("registered", registration.user_id),
+                DomainEvent::UserUnregistered { registration, late } => {
+                    assert!(!late);
+                    ("unregistered", registration.user_id)
+                }
+                DomainEvent::SubstitutePromoted { registration } => ("promoted", registration.user_id),
+                other => panic!("unexpected event {:?}", other),
+            });
+        }
+        assert_eq!(
+            seen,
+            vec![
+                ("registered", users[0].id),
+                ("registered", users[1].id),
+                ("registered", users[2].id),
+                ("unregistered", users[0].id),
+                ("promoted", users[2].id),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn only_confirmed_players_cancel_late() {
+        let storage = create_test_storage().await;
+        let events = EventBus::new();
+        let (sender, mut received) = tokio::sync::mpsc::unbounded_channel();
+        events.subscribe(Arc::new(Forward(sender)));
+        // Every cancellation of these sessions is past the cutoff
+        let policy = CancellationPolicy {
+            coaching: Duration::days(3),
+            ..CancellationPolicy::default()
+        };
+        let service = RegistrationService::new(storage.clone())
+            .with_cancellation_policy(policy)
+            .with_events(events);
+        let session = create_session_with_capacity(&storage, 2, None).await;
+
+        let mut users = Vec::new();
+        for _ in 0..4 {
+            let user = create_test_user(&storage, true).await;
+            service.register_user(session.id, user.id).await.unwrap();
+            users.push(user);
+            tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+        }
+        service.unregister_user(session.id, users[3].id).await.unwrap();
+        service.unregister_user(session.id, users[0].id).await.unwrap();
+
+        let mut withdrawals = Vec::new();
+        while let Ok(Some(event)) =
+            tokio::time::timeout(std::time::Duration::from_millis(100), received.recv()).await
+        {
+            if let DomainEvent::UserUnregistered { registration, late } = event {
+                withdrawals.push((registration.user_id, late));
+            }
+        }
+        assert_eq!(withdrawals, vec![(users[3].id, false), (users[0].id, true)]);
+        assert!(service.late_cancellations(users[3].id).await.unwrap().is_empty());
+    }
+}
@@ -1,48 +1,209 @@
 use crate::{
-    models::{Session, SessionType},
-    storage::Storage,
+    calendar,
+    events::{DomainEvent, EventBus},
+    models::{Session, SessionQuery, SessionSummary, SessionUpdate, Venue},
+    registration::SessionEdit,
+    storage::{Storage, StorageError, StorageResult},
 };
-use std::sync::Arc;
+use chrono::{DateTime, Duration, Utc};
+use std::{collections::HashMap, sync::Arc};
 use uuid::Uuid;
 
 #[derive(Debug, PartialEq)]
 pub enum SessionError {
     VenueNotFound,
+    SessionNotFound,
+    NotScheduled,
+    Invalid(&'static str),
+    OverCapacity,
+    Storage(StorageError),
+}
+
+impl From<StorageError> for SessionError {
+    fn from(error: StorageError) -> Self {
+        SessionError::Storage(error)
+    }
 }
 
 pub struct SessionService<S> {
     storage: Arc<S>,
+    events: EventBus,
 }
 
 impl<S: Storage> SessionService<S> {
     pub fn new(storage: Arc<S>) -> Self {
-        Self { storage }
+        Self {
+            storage,
+            events: EventBus::new(),
+        }
+    }
+
+    /// Publish created, edited and cancelled sessions, and substitutes an edit
+    /// promotes, on `events`
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = events;
+        self
     }
 
     pub async fn create_session(&self, session: Session) -> Result<Session, SessionError> {
         // Validate venue exists
-        if self.storage.get_venue(session.venue_id).await.is_none() {
+        if self.storage.get_venue(session.venue_id).await?.is_none() {
             return Err(SessionError::VenueNotFound);
         }
         
-        self.storage.create_session(session.clone()).await;
+        self.storage.create_session(session.clone()).await?;
+        self.events.publish(DomainEvent::SessionCreated {
+            session: session.clone(),
+        });
         Ok(session)
     }
 
-    pub async fn get_session(&self, id: Uuid) -> Option<Session> {
+    pub async fn cancel_session(&self, id: Uuid, reason: &str) -> Result<Session, SessionError> {
+        if let Some(cancelled) = self.storage.cancel_session(id, reason).await? {
+            self.events.publish(DomainEvent::SessionCancelled {
+                session: cancelled.clone(),
+            });
+            return Ok(cancelled);
+        }
+        // Nothing was cancelled; tell a missing session from one that's already closed
+        match self.storage.get_session(id).await? {
+            Some(_) => Err(SessionError::NotScheduled),
+            None => Err(SessionError::SessionNotFound),
+        }
+    }
+
+    /// Apply an organiser's edit, moving substitutes into any places it adds.
+    /// Returns the session before and after.
+    pub async fn update_session(
+        &self,
+        id: Uuid,
+        update: &SessionUpdate,
+    ) -> Result<(Session, Session), SessionError> {
+        let before = self
+            .storage
+            .get_session(id)
+            .await?
+            .ok_or(SessionError::SessionNotFound)?;
+        if !before.is_scheduled() {
+            return Err(SessionError::NotScheduled);
+        }
+
+        let after = before.updated(update).map_err(SessionError::Invalid)?;
+        if after.venue_id != before.venue_id
+            && self.storage.get_venue(after.venue_id).await?.is_none()
+        {
+            return Err(SessionError::VenueNotFound);
+        }
+
+        let promoted = match self.storage.update_session(after.clone()).await? {
+            SessionEdit::Saved { promoted } => promoted,
+            // Cancelled between the read and the write
+            SessionEdit::NotScheduled => return Err(SessionError::NotScheduled),
+            SessionEdit::OverCapacity => return Err(SessionError::OverCapacity),
+        };
+        self.events.publish(DomainEvent::SessionUpdated {
+            before: before.clone(),
+            after: after.clone(),
+        });
+        for registration in promoted {
+            self.events.publish(DomainEvent::SubstitutePromoted { registration });
+        }
+        Ok((before, after))
+    }
+
+    pub async fn get_session(&self, id: Uuid) -> StorageResult<Option<Session>> {
         self.storage.get_session(id).await
     }
 
-    pub async fn list_sessions(&self, session_type: Option<SessionType>) -> Vec<Session> {
-        self.storage.list_sessions(session_type).await
+    pub async fn list_sessions(&self, query: &SessionQuery) -> StorageResult<Vec<Session>> {
+        self.storage.list_sessions(query).await
+    }
+
+    /// Sessions matching `query` in the Monday-to-Sunday week containing `now`
+    /// (`weeks_ahead` 0) or a later one, where each session's week is its
+    /// venue's local week
+    pub async fn list_sessions_in_week(
+        &self,
+        query: &SessionQuery,
+        now: DateTime<Utc>,
+        weeks_ahead: u32,
+    ) -> StorageResult<Vec<Session>> {
+        let timezones: HashMap<_, _> = self
+            .storage
+            .list_venues()
+            .await?
+            .into_iter()
+            .map(|venue| (venue.id, venue.timezone))
+            .collect();
+
+        let sessions = self
+            .storage
+            .list_sessions(&week_candidates(query, now, weeks_ahead))
+            .await?;
+        let in_week = sessions.into_iter().filter(|session| {
+            let timezone = timezones
+                .get(&session.venue_id)
+                .copied()
+                .unwrap_or(Venue::DEFAULT_TIMEZONE);
+            calendar::local_week(timezone, now, weeks_ahead).contains(&session.datetime)
+        });
+        Ok(page(in_week, query))
+    }
+
+    /// Sessions matching `query` along with their venue and sign-ups
+    pub async fn list_summaries(&self, query: &SessionQuery) -> StorageResult<Vec<SessionSummary>> {
+        self.storage.list_session_summaries(query).await
+    }
+
+    /// [`list_summaries`](Self::list_summaries) for a week, as in
+    /// [`list_sessions_in_week`](Self::list_sessions_in_week)
+    pub async fn list_summaries_in_week(
+        &self,
+        query: &SessionQuery,
+        now: DateTime<Utc>,
+        weeks_ahead: u32,
+    ) -> StorageResult<Vec<SessionSummary>> {
+        let summaries = self
+            .storage
+            .list_session_summaries(&week_candidates(query, now, weeks_ahead))
+            .await?;
+        let in_week = summaries.into_iter().filter(|summary| {
+            calendar::local_week(summary.venue.timezone, now, weeks_ahead)
+                .contains(&summary.session.datetime)
+        });
+        Ok(page(in_week, query))
     }
 }
 
+/// Every zone's week lies within a week either side of the UTC one. Paging
+/// waits until the sessions outside the local week are dropped.
+fn week_candidates(query: &SessionQuery, now: DateTime<Utc>, weeks_ahead: u32) -> SessionQuery {
+    let utc_week = calendar::local_week(chrono_tz::UTC, now, weeks_ahead);
+    let (start, end) = (
+        utc_week.start - Duration::weeks(1),
+        utc_week.end + Duration::weeks(1),
+    );
+    SessionQuery {
+        from: Some(query.from.map_or(start, |from| from.max(start))),
+        to: Some(query.to.map_or(end, |to| to.min(end))),
+        limit: None,
+        offset: 0,
+        ..query.clone()
+    }
+}
+
+pub(crate) fn page<T>(items: impl Iterator<Item = T>, query: &SessionQuery) -> Vec<T> {
+    items
+        .skip(query.offset as usize)
+        .take(query.limit.map_or(usize::MAX, |limit| limit as usize))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
-        models::{SessionType, Venue},
+        models::{SessionType, SortOrder, Venue},
         storage::InMemoryStorage,
         user::SkillLevel,
     };
@@ -56,18 +217,18 @@ mod tests {
             "Test Padel Club".to_string(),
             "123 Test Street".to_string(),
         );
-        storage.create_venue(venue.clone()).await;
+        storage.create_venue(venue.clone()).await.unwrap();
         
         // Create various sessions
         let sessions = vec![
-            Session::new(SessionType::Social, Utc::now(), 90, venue.id, Some(SkillLevel::Intermediate)).unwrap(),
-            Session::new(SessionType::Coaching, Utc::now(), 60, venue.id, Some(SkillLevel::Beginner)).unwrap(),
-            Session::new(SessionType::Social, Utc::now(), 120, venue.id, Some(SkillLevel::Advanced)).unwrap(),
-            Session::new(SessionType::League, Utc::now(), 90, venue.id, Some(SkillLevel::UpperIntermediate)).unwrap(),
+            Session::new(SessionType::Social, Utc::now(), 90, venue.id, Some(SkillLevel::Intermediate), Session::DEFAULT_MAX_PLAYERS, None).unwrap(),
+            Session::new(SessionType::Coaching, Utc::now(), 60, venue.id, Some(SkillLevel::Beginner), Session::DEFAULT_MAX_PLAYERS, None).unwrap(),
+            Session::new(SessionType::Social, Utc::now(), 120, venue.id, Some(SkillLevel::Advanced), Session::DEFAULT_MAX_PLAYERS, None).unwrap(),
+            Session::new(SessionType::League, Utc::now(), 90, venue.id, Some(SkillLevel::UpperIntermediate), Session::DEFAULT_MAX_PLAYERS, None).unwrap(),
         ];
         
         for session in sessions {
-            storage.create_session(session).await;
+            storage.create_session(session).await.unwrap();
         }
         
         storage
@@ -79,14 +240,60 @@ mod tests {
         let service = SessionService::new(storage);
         
         // Test that filtering is handled by storage
-        let social_sessions = service.list_sessions(Some(SessionType::Social)).await;
+        let social_sessions = service.list_sessions(&SessionQuery::of_type(SessionType::Social)).await.unwrap();
         assert_eq!(social_sessions.len(), 2);
         assert!(social_sessions.iter().all(|s| s.session_type == SessionType::Social));
         
-        let all_sessions = service.list_sessions(None).await;
+        let all_sessions = service.list_sessions(&SessionQuery::default()).await.unwrap();
         assert_eq!(all_sessions.len(), 4);
     }
 
+    #[tokio::test]
+    async fn query_filters_sorts_and_pages() {
+        use crate::registration::{Registration, RegistrationStatus};
+        use chrono::Duration;
+
+        let storage = Arc::new(InMemoryStorage::new());
+        let service = SessionService::new(storage.clone());
+        let venue = Venue::new("Test Venue".to_string(), "Test Address".to_string());
+        storage.create_venue(venue.clone()).await.unwrap();
+
+        // One session a day for five days, each at the next level up
+        let start = Utc::now();
+        let levels = &SkillLevel::ALL[..5];
+        let mut sessions = Vec::new();
+        for (day, level) in levels.iter().copied().enumerate() {
+            let session = Session::new(SessionType::Social, start + Duration::days(day as i64), 90, venue.id, Some(level), 2, None)
+                .unwrap();
+            storage.create_session(session.clone()).await.unwrap();
+            sessions.push(session);
+        }
+        // Fill the first session
+        for _ in 0..2 {
+            let registration = Registration::new(Uuid::new_v4(), sessions[0].id, RegistrationStatus::Confirmed);
+            storage.create_registration(registration).await.unwrap();
+        }
+
+        let ids = |listed: Vec<Session>| listed.into_iter().map(|s| s.id).collect::<Vec<_>>();
+
+        let query = SessionQuery { skill_levels: vec![levels[4], levels[0]], ..Default::default() };
+        assert_eq!(ids(service.list_sessions(&query).await.unwrap()), vec![sessions[0].id, sessions[4].id]);
+
+        let query = SessionQuery { from: Some(start + Duration::days(1)), to: Some(start + Duration::days(3)), ..Default::default() };
+        assert_eq!(ids(service.list_sessions(&query).await.unwrap()), vec![sessions[1].id, sessions[2].id]);
+
+        let query = SessionQuery { has_open_slots: true, limit: Some(2), ..Default::default() };
+        assert_eq!(ids(service.list_sessions(&query).await.unwrap()), vec![sessions[1].id, sessions[2].id]);
+
+        let query = SessionQuery { order: SortOrder::Descending, limit: Some(2), offset: 1, ..Default::default() };
+        assert_eq!(ids(service.list_sessions(&query).await.unwrap()), vec![sessions[3].id, sessions[2].id]);
+
+        service.cancel_session(sessions[4].id, "Rain").await.unwrap();
+        assert_eq!(service.list_sessions(&SessionQuery::default()).await.unwrap().len(), 4);
+        let query = SessionQuery { include_cancelled: true, ..Default::default() };
+        assert_eq!(service.list_sessions(&query).await.unwrap().len(), 5);
+    }
+
     #[tokio::test]
     async fn create_session_with_valid_venue_succeeds() {
         let storage = Arc::new(InMemoryStorage::new());
@@ -94,7 +301,7 @@ mod tests {
         
         // Create venue first
         let venue = Venue::new("Test Venue".to_string(), "Test Address".to_string());
-        storage.create_venue(venue.clone()).await;
+        storage.create_venue(venue.clone()).await.unwrap();
         
         // Create session with valid venue
         let session = Session::new(
@@ -103,6 +310,8 @@ mod tests {
             90,
             venue.id,
             Some(SkillLevel::Intermediate),
+            Session::DEFAULT_MAX_PLAYERS,
+            None,
         ).unwrap();
         
         let result = service.create_session(session.clone()).await;
@@ -125,10 +334,150 @@ mod tests {
             90,
             fake_venue_id,
             Some(SkillLevel::Intermediate),
+            Session::DEFAULT_MAX_PLAYERS,
+            None,
         ).unwrap();
         
         let result = service.create_session(session).await;
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), SessionError::VenueNotFound);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn week_listing_uses_each_venues_local_week() {
+        use chrono::TimeZone;
+
+        let storage = Arc::new(InMemoryStorage::new());
+        let service = SessionService::new(storage.clone());
+        let lisbon = Venue::new("Lisbon Club".to_string(), "Lisbon".to_string());
+        let tokyo = Venue::new("Tokyo Club".to_string(), "Tokyo".to_string())
+            .with_timezone(chrono_tz::Asia::Tokyo);
+        storage.create_venue(lisbon.clone()).await.unwrap();
+        storage.create_venue(tokyo.clone()).await.unwrap();
+
+        // Sunday 14 September 2025 22:30 UTC: 23:30 on Sunday in Lisbon,
+        // already Monday morning in Tokyo
+        let sunday_night = Utc.with_ymd_and_hms(2025, 9, 14, 22, 30, 0).unwrap();
+        for venue in [&lisbon, &tokyo] {
+            let session = Session::new(SessionType::Social, sunday_night, 90, venue.id, Some(SkillLevel::Intermediate), Session::DEFAULT_MAX_PLAYERS, None).unwrap();
+            storage.create_session(session).await.unwrap();
+        }
+
+        let now = Utc.with_ymd_and_hms(2025, 9, 10, 12, 0, 0).unwrap();
+        let this_week = service.list_sessions_in_week(&SessionQuery::default(), now, 0).await.unwrap();
+        assert_eq!(this_week.len(), 1);
+        assert_eq!(this_week[0].venue_id, lisbon.id);
+
+        let next_week = service.list_sessions_in_week(&SessionQuery::default(), now, 1).await.unwrap();
+        assert_eq!(next_week.len(), 1);
+        assert_eq!(next_week[0].venue_id, tokyo.id);
+    }
+
+    #[tokio::test]
+    async fn cancelled_session_leaves_listing() {
+        let storage = setup_test_storage().await;
+        let service = SessionService::new(storage);
+        let session = service.list_sessions(&SessionQuery::of_type(SessionType::League)).await.unwrap()[0].clone();
+
+        let cancelled = service.cancel_session(session.id, "Court flooded").await.unwrap();
+        assert_eq!(cancelled.status, crate::models::SessionStatus::Cancelled);
+        assert_eq!(cancelled.cancellation_reason.as_deref(), Some("Court flooded"));
+
+        assert!(service.list_sessions(&SessionQuery::of_type(SessionType::League)).await.unwrap().is_empty());
+        assert_eq!(service.list_sessions(&SessionQuery::default()).await.unwrap().len(), 3);
+        // Still reachable directly, with its reason
+        let fetched = service.get_session(session.id).await.unwrap().unwrap();
+        assert_eq!(fetched.status, crate::models::SessionStatus::Cancelled);
+    }
+
+    #[tokio::test]
+    async fn cancel_only_applies_to_scheduled_sessions() {
+        let storage = setup_test_storage().await;
+        let service = SessionService::new(storage);
+        let session = service.list_sessions(&SessionQuery::default()).await.unwrap()[0].clone();
+
+        service.cancel_session(session.id, "Rain").await.unwrap();
+        assert_eq!(
+            service.cancel_session(session.id, "Rain again").await.unwrap_err(),
+            SessionError::NotScheduled
+        );
+        assert_eq!(
+            service.cancel_session(Uuid::new_v4(), "Rain").await.unwrap_err(),
+            SessionError::SessionNotFound
+        );
+    }
+
+    #[tokio::test]
+    async fn update_session_moves_time() {
+        let storage = setup_test_storage().await;
+        let service = SessionService::new(storage);
+        let session = service.list_sessions(&SessionQuery::of_type(SessionType::League)).await.unwrap()[0].clone();
+        let later = session.datetime + chrono::Duration::minutes(30);
+
+        let update = SessionUpdate { datetime: Some(later), ..Default::default() };
+        let (before, after) = service.update_session(session.id, &update).await.unwrap();
+
+        assert_eq!(before.datetime, session.datetime);
+        assert_eq!(after.datetime, later);
+        let stored = service.get_session(session.id).await.unwrap().unwrap();
+        assert_eq!(stored.datetime, later);
+    }
+
+    #[tokio::test]
+    async fn update_session_rejects_invalid_changes() {
+        let storage = setup_test_storage().await;
+        let service = SessionService::new(storage);
+        let session = service.list_sessions(&SessionQuery::of_type(SessionType::League)).await.unwrap()[0].clone();
+
+        let update = SessionUpdate { duration_minutes: Some(45), ..Default::default() };
+        assert_eq!(
+            service.update_session(session.id, &update).await.unwrap_err(),
+            SessionError::Invalid("Duration must be between 60 and 120 minutes")
+        );
+
+        let update = SessionUpdate { venue_id: Some(Uuid::new_v4()), ..Default::default() };
+        assert_eq!(
+            service.update_session(session.id, &update).await.unwrap_err(),
+            SessionError::VenueNotFound
+        );
+
+        service.cancel_session(session.id, "Rain").await.unwrap();
+        let update = SessionUpdate { duration_minutes: Some(60), ..Default::default() };
+        assert_eq!(
+            service.update_session(session.id, &update).await.unwrap_err(),
+            SessionError::NotScheduled
+        );
+    }
+
+    #[tokio::test]
+    async fn update_session_capacity_is_checked_against_sign_ups() {
+        use crate::registration::{RegistrationActor, RegistrationStatus};
+
+        let storage = setup_test_storage().await;
+        let service = SessionService::new(storage.clone());
+        let session = service.list_sessions(&SessionQuery::of_type(SessionType::League)).await.unwrap()[0].clone();
+        // Four players and two substitutes
+        for _ in 0..6 {
+            storage.register_within_capacity(session.id, Uuid::new_v4(), RegistrationActor::Player).await.unwrap();
+        }
+
+        let fewer_players = SessionUpdate { max_players: Some(3), ..Default::default() };
+        assert_eq!(
+            service.update_session(session.id, &fewer_players).await.unwrap_err(),
+            SessionError::OverCapacity
+        );
+        let fewer_substitutes = SessionUpdate { max_substitutes: Some(Some(1)), ..Default::default() };
+        assert_eq!(
+            service.update_session(session.id, &fewer_substitutes).await.unwrap_err(),
+            SessionError::OverCapacity
+        );
+
+        // One more place takes a substitute, leaving one waiting
+        let bigger = SessionUpdate { max_players: Some(5), max_substitutes: Some(Some(1)), ..Default::default() };
+        let (_, after) = service.update_session(session.id, &bigger).await.unwrap();
+        assert_eq!((after.max_players, after.max_substitutes), (5, Some(1)));
+        let registrations = storage.get_registrations(session.id).await.unwrap();
+        let confirmed = registrations.iter().filter(|r| r.status == RegistrationStatus::Confirmed).count();
+        assert_eq!((confirmed, registrations.len()), (5, 6));
+    }
+}
@@ -0,0 +1,278 @@
+use crate::{
+    events::{DomainEvent, EventBus},
+    models::Session,
+    repository::TemplateError,
+    storage::{Storage, StorageError, StorageResult},
+    template::SessionTemplate,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub struct TemplateService<S> {
+    storage: Arc<S>,
+    events: EventBus,
+}
+
+impl<S: Storage> TemplateService<S> {
+    pub fn new(storage: Arc<S>) -> Self {
+        Self {
+            storage,
+            events: EventBus::new(),
+        }
+    }
+
+    /// Publish the generated sessions on `events`
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = events;
+        self
+    }
+
+    pub async fn create_template(
+        &self,
+        template: SessionTemplate,
+    ) -> Result<SessionTemplate, TemplateError> {
+        if self.storage.get_venue(template.venue_id).await?.is_none() {
+            return Err(TemplateError::VenueNotFound);
+        }
+        self.storage.create_session_template(template.clone()).await?;
+        Ok(template)
+    }
+
+    pub async fn get_template(&self, id: Uuid) -> StorageResult<Option<SessionTemplate>> {
+        self.storage.get_session_template(id).await
+    }
+
+    pub async fn list_templates(&self) -> StorageResult<Vec<SessionTemplate>> {
+        self.storage.list_session_templates().await
+    }
+
+    pub async fn delete_template(&self, id: Uuid) -> Result<(), TemplateError> {
+        if !self.storage.delete_session_template(id).await? {
+            return Err(TemplateError::TemplateNotFound);
+        }
+        Ok(())
+    }
+
+    /// Skip the week of `date`. Sessions already generated for it are left for
+    /// an organiser to cancel, so the players on them get told.
+    pub async fn add_exception(
+        &self,
+        id: Uuid,
+        date: NaiveDate,
+    ) -> Result<SessionTemplate, TemplateError> {
+        self.change_template(id, |template| {
+            template.add_exception(date);
+        })
+        .await
+    }
+
+    pub async fn remove_exception(
+        &self,
+        id: Uuid,
+        date: NaiveDate,
+    ) -> Result<SessionTemplate, TemplateError> {
+        self.change_template(id, |template| {
+            template.remove_exception(date);
+        })
+        .await
+    }
+
+    /// Create the template's sessions from `from` up to `weeks` weeks ahead.
+    /// Weeks that already have a session are left alone, so running this again
+    /// only fills in what's missing. Returns the sessions created.
+    pub async fn generate(
+        &self,
+        id: Uuid,
+        from: DateTime<Utc>,
+        weeks: u32,
+    ) -> Result<Vec<Session>, TemplateError> {
+        let template = self
+            .storage
+            .get_session_template(id)
+            .await?
+            .ok_or(TemplateError::TemplateNotFound)?;
+        self.materialise(&template, from, weeks).await
+    }
+
+    /// [`generate`](Self::generate) for every template
+    pub async fn generate_all(
+        &self,
+        from: DateTime<Utc>,
+        weeks: u32,
+    ) -> Result<Vec<Session>, TemplateError> {
+        let mut created = Vec::new();
+        for template in self.storage.list_session_templates().await? {
+            created.extend(self.materialise(&template, from, weeks).await?);
+        }
+        Ok(created)
+    }
+
+    async fn materialise(
+        &self,
+        template: &SessionTemplate,
+        from: DateTime<Utc>,
+        weeks: u32,
+    ) -> Result<Vec<Session>, TemplateError> {
+        let venue = self
+            .storage
+            .get_venue(template.venue_id)
+            .await?
+            .ok_or(TemplateError::VenueNotFound)?;
+
+        let mut created = Vec::new();
+        for (date, start) in template.occurrences(from, weeks, venue.timezone) {
+            let session = template.session_at(date, start).map_err(TemplateError::Invalid)?;
+            match self.storage.create_session(session.clone()).await {
+                Ok(()) => {
+                    self.events.publish(DomainEvent::SessionCreated {
+                        session: session.clone(),
+                    });
+                    created.push(session)
+                }
+                // Generated on an earlier run
+                Err(StorageError::Conflict(_)) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(created)
+    }
+
+    async fn change_template(
+        &self,
+        id: Uuid,
+        change: impl FnOnce(&mut SessionTemplate),
+    ) -> Result<SessionTemplate, TemplateError> {
+        let mut template = self
+            .storage
+            .get_session_template(id)
+            .await?
+            .ok_or(TemplateError::TemplateNotFound)?;
+        change(&mut template);
+        // Deleted between the read and the write
+        if !self.storage.update_session_template(template.clone()).await? {
+            return Err(TemplateError::TemplateNotFound);
+        }
+        Ok(template)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::{SessionQuery, SessionType, Venue},
+        storage::InMemoryStorage,
+        user::SkillLevel,
+    };
+    use chrono::{NaiveTime, TimeZone, Weekday};
+
+    async fn setup() -> (Arc<InMemoryStorage>, TemplateService<InMemoryStorage>, SessionTemplate) {
+        let storage = Arc::new(InMemoryStorage::new());
+        let service = TemplateService::new(storage.clone());
+
+        let venue = Venue::new("Test Padel Club".to_string(), "123 Test Street".to_string());
+        storage.create_venue(venue.clone()).await.unwrap();
+        let template = SessionTemplate::new(
+            SessionType::Social,
+            Weekday::Tue,
+            NaiveTime::from_hms_opt(19, 0, 0).unwrap(),
+            90,
+            venue.id,
+            Some(SkillLevel::Intermediate),
+            8,
+            Some(2),
+        )
+        .unwrap();
+        let template = service.create_template(template).await.unwrap();
+        (storage, service, template)
+    }
+
+    fn monday() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2030, 9, 2, 12, 0, 0).unwrap()
+    }
+
+    #[tokio::test]
+    async fn generating_twice_creates_no_duplicates() {
+        let (storage, service, template) = setup().await;
+
+        let created = service.generate(template.id, monday(), 4).await.unwrap();
+        assert_eq!(created.len(), 4);
+        assert!(created.iter().all(|s| s.template_id == Some(template.id)));
+
+        // A week further on only the new week is added
+        let created = service
+            .generate(template.id, monday() + chrono::Duration::weeks(1), 4)
+            .await
+            .unwrap();
+        assert_eq!(created.len(), 1);
+        assert_eq!(storage.list_sessions(&SessionQuery::default()).await.unwrap().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn moving_a_generated_session_does_not_regenerate_it() {
+        let (storage, service, template) = setup().await;
+        let created = service.generate(template.id, monday(), 2).await.unwrap();
+
+        let mut moved = created[0].clone();
+        moved.datetime += chrono::Duration::hours(1);
+        storage.update_session(moved).await.unwrap();
+
+        let created = service.generate(template.id, monday(), 2).await.unwrap();
+        assert!(created.is_empty());
+        assert_eq!(storage.list_sessions(&SessionQuery::default()).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn exception_weeks_are_skipped() {
+        let (_, service, template) = setup().await;
+        let holiday = NaiveDate::from_ymd_opt(2030, 9, 10).unwrap();
+
+        let updated = service.add_exception(template.id, holiday).await.unwrap();
+        assert_eq!(updated.exception_dates, vec![holiday]);
+
+        let created = service.generate(template.id, monday(), 3).await.unwrap();
+        assert_eq!(created.len(), 2);
+        assert!(created.iter().all(|s| s.datetime.date_naive() != holiday));
+
+        // Lifting the exception fills the gap on the next run
+        service.remove_exception(template.id, holiday).await.unwrap();
+        let created = service.generate(template.id, monday(), 3).await.unwrap();
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0].datetime.date_naive(), holiday);
+    }
+
+    #[tokio::test]
+    async fn unknown_template_or_venue_is_rejected() {
+        let (_, service, template) = setup().await;
+
+        assert_eq!(
+            service.generate(Uuid::new_v4(), monday(), 1).await.unwrap_err(),
+            TemplateError::TemplateNotFound
+        );
+
+        let mut orphan = template.clone();
+        orphan.id = Uuid::new_v4();
+        orphan.venue_id = Uuid::new_v4();
+        assert_eq!(
+            service.create_template(orphan).await.unwrap_err(),
+            TemplateError::VenueNotFound
+        );
+    }
+
+    #[tokio::test]
+    async fn deleting_a_template_keeps_its_sessions() {
+        let (storage, service, template) = setup().await;
+        service.generate(template.id, monday(), 2).await.unwrap();
+
+        service.delete_template(template.id).await.unwrap();
+        assert_eq!(
+            service.delete_template(template.id).await.unwrap_err(),
+            TemplateError::TemplateNotFound
+        );
+
+        let sessions = storage.list_sessions(&SessionQuery::default()).await.unwrap();
+        assert_eq!(sessions.len(), 2);
+        assert!(sessions.iter().all(|s| s.template_id.is_none()));
+    }
+}
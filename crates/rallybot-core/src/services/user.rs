@@ -0,0 +1,183 @@
+use crate::{
+    events::{DomainEvent, EventBus},
+    repository::UserError,
+    storage::{Storage, StorageResult},
+    user::{User, UserStatus, UserStatusChange},
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub struct UserService<S> {
+    storage: Arc<S>,
+    events: EventBus,
+}
+
+impl<S: Storage> UserService<S> {
+    pub fn new(storage: Arc<S>) -> Self {
+        Self {
+            storage,
+            events: EventBus::new(),
+        }
+    }
+
+    /// Publish approvals on `events`
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = events;
+        self
+    }
+
+    pub async fn list_pending(&self) -> StorageResult<Vec<User>> {
+        self.storage.list_users_by_status(UserStatus::Pending).await
+    }
+
+    pub async fn approve(
+        &self,
+        id: Uuid,
+        by: &str,
+        reason: Option<String>,
+    ) -> Result<User, UserError> {
+        self.change_status(id, UserStatus::Approved, by, reason).await
+    }
+
+    pub async fn reject(
+        &self,
+        id: Uuid,
+        by: &str,
+        reason: Option<String>,
+    ) -> Result<User, UserError> {
+        self.change_status(id, UserStatus::Rejected, by, reason).await
+    }
+
+    /// Take an approved member's access away; approving them again restores it
+    pub async fn suspend(
+        &self,
+        id: Uuid,
+        by: &str,
+        reason: Option<String>,
+    ) -> Result<User, UserError> {
+        self.change_status(id, UserStatus::Suspended, by, reason).await
+    }
+
+    pub async fn status_history(&self, id: Uuid) -> StorageResult<Vec<UserStatusChange>> {
+        self.storage.get_user_status_changes(id).await
+    }
+
+    async fn change_status(
+        &self,
+        id: Uuid,
+        to: UserStatus,
+        by: &str,
+        reason: Option<String>,
+    ) -> Result<User, UserError> {
+        let mut user = self
+            .storage
+            .get_user(id)
+            .await?
+            .ok_or(UserError::UserNotFound)?;
+        if !user.status.can_become(to) {
+            return Err(UserError::InvalidTransition {
+                from: user.status,
+                to,
+            });
+        }
+
+        let change = UserStatusChange::new(&user, to, by.to_string(), reason);
+        if !self.storage.change_user_status(&change).await? {
+            // Another admin got there first; report what they left it as
+            let from = self
+                .storage
+                .get_user(id)
+                .await?
+                .ok_or(UserError::UserNotFound)?
+                .status;
+            return Err(UserError::InvalidTransition { from, to });
+        }
+        user.status = to;
+        if to == UserStatus::Approved {
+            self.events.publish(DomainEvent::UserApproved {
+                user: Box::new(user.clone()),
+            });
+        }
+        Ok(user)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        storage::InMemoryStorage,
+        user::{Gender, LookingFor, PlayFrequency, PreferredSide, SkillLevel},
+    };
+
+    async fn setup() -> (UserService<InMemoryStorage>, User) {
+        let storage = Arc::new(InMemoryStorage::new());
+        let user = User::new(
+            "Ana".to_string(),
+            "Silva".to_string(),
+            "+351911111111".to_string(),
+            "ana@example.com".to_string(),
+            "Lisbon".to_string(),
+            None,
+            "Engineer".to_string(),
+            "Acme".to_string(),
+            "Software".to_string(),
+            "https://linkedin.com/in/ana".to_string(),
+            Gender::Female,
+            vec![SkillLevel::Intermediate],
+            PreferredSide::Left,
+            PlayFrequency::OnceWeek,
+            vec![LookingFor::SocialConnections],
+        );
+        storage.create_user(user.clone()).await.unwrap();
+        (UserService::new(storage), user)
+    }
+
+    #[tokio::test]
+    async fn approval_is_recorded_in_history() {
+        let (service, user) = setup().await;
+        assert_eq!(service.list_pending().await.unwrap().len(), 1);
+
+        let approved = service
+            .approve(user.id, "admin@rally.pt", None)
+            .await
+            .unwrap();
+        assert!(approved.is_approved());
+        assert!(service.list_pending().await.unwrap().is_empty());
+
+        service
+            .suspend(user.id, "admin@rally.pt", Some("No-shows".to_string()))
+            .await
+            .unwrap();
+        let history = service.status_history(user.id).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(
+            (history[0].from_status, history[0].to_status),
+            (UserStatus::Pending, UserStatus::Approved)
+        );
+        assert_eq!(history[1].to_status, UserStatus::Suspended);
+        assert_eq!(history[1].changed_by, "admin@rally.pt");
+        assert_eq!(history[1].reason.as_deref(), Some("No-shows"));
+    }
+
+    #[tokio::test]
+    async fn invalid_transitions_are_rejected() {
+        let (service, user) = setup().await;
+
+        assert_eq!(
+            service.suspend(user.id, "admin", None).await.unwrap_err(),
+            UserError::InvalidTransition {
+                from: UserStatus::Pending,
+                to: UserStatus::Suspended
+            }
+        );
+        assert_eq!(
+            service.approve(Uuid::new_v4(), "admin", None).await.unwrap_err(),
+            UserError::UserNotFound
+        );
+
+        service.reject(user.id, "admin", None).await.unwrap();
+        assert!(service.reject(user.id, "admin", None).await.is_err());
+        assert_eq!(service.status_history(user.id).await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,74 @@
+use std::fmt;
+
+/// Why a storage call failed
+#[derive(Debug, Clone, PartialEq)]
+pub enum StorageError {
+    /// The row the call depends on does not exist
+    NotFound,
+    /// A unique key is already taken, e.g. a second user with the same email
+    Conflict(String),
+    /// A check, foreign key or not-null constraint rejected the write
+    Constraint(String),
+    /// The database could not be reached
+    Connection(String),
+    /// Any other database failure
+    Database(String),
+}
+
+pub type StorageResult<T> = Result<T, StorageError>;
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::NotFound => write!(f, "record not found"),
+            StorageError::Conflict(detail) => write!(f, "conflict: {}", detail),
+            StorageError::Constraint(detail) => write!(f, "constraint violation: {}", detail),
+            StorageError::Connection(detail) => write!(f, "connection error: {}", detail),
+            StorageError::Database(detail) => write!(f, "database error: {}", detail),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<sqlx::Error> for StorageError {
+    fn from(error: sqlx::Error) -> Self {
+        match error {
+            sqlx::Error::RowNotFound => StorageError::NotFound,
+            sqlx::Error::Database(db) => {
+                let detail = db.constraint().unwrap_or(db.message()).to_string();
+                match db.kind() {
+                    sqlx::error::ErrorKind::UniqueViolation => StorageError::Conflict(detail),
+                    sqlx::error::ErrorKind::ForeignKeyViolation
+                    | sqlx::error::ErrorKind::NotNullViolation
+                    | sqlx::error::ErrorKind::CheckViolation => StorageError::Constraint(detail),
+                    _ => StorageError::Database(db.message().to_string()),
+                }
+            }
+            sqlx::Error::Io(_)
+            | sqlx::Error::Tls(_)
+            | sqlx::Error::PoolTimedOut
+            | sqlx::Error::PoolClosed
+            | sqlx::Error::WorkerCrashed => StorageError::Connection(error.to_string()),
+            other => StorageError::Database(other.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sqlx_errors_map_to_storage_errors() {
+        assert_eq!(StorageError::from(sqlx::Error::RowNotFound), StorageError::NotFound);
+        assert!(matches!(
+            StorageError::from(sqlx::Error::PoolTimedOut),
+            StorageError::Connection(_)
+        ));
+        assert!(matches!(
+            StorageError::from(sqlx::Error::Protocol("bad message".to_string())),
+            StorageError::Database(_)
+        ));
+    }
+}
@@ -1,18 +1,38 @@
-use super::Storage;
+use super::{Storage, StorageError, StorageResult};
 use crate::{
-    models::{Session, SessionType, Venue},
-    registration::Registration,
-    user::User,
+    application::{Application, ApplicationStatus, ApplicationStatusChange},
+    attendance::Attendance,
+    cancellation::LateCancellation,
+    conversation::ConversationState,
+    models::{Session, SessionQuery, SessionStatus, SessionSummary, SortOrder, Venue},
+    registration::{
+        Registration, RegistrationActor, RegistrationAttempt, RegistrationEvent,
+        RegistrationEventKind, RegistrationStatus, SessionEdit, Withdrawal,
+    },
+    template::SessionTemplate,
+    user::{User, UserStatus, UserStatusChange},
 };
+use chrono::{DateTime, Utc};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use uuid::Uuid;
 
+/// Methods holding more than one lock take `registrations` first, then
+/// `sessions`, then the registration log, late cancellations or attendance.
+/// Taking them in any other order can deadlock against a sign-up.
 pub struct InMemoryStorage {
     sessions: Arc<Mutex<Vec<Session>>>,
     users: Arc<Mutex<Vec<User>>>,
+    user_status_changes: Arc<Mutex<Vec<UserStatusChange>>>,
+    applications: Arc<Mutex<Vec<Application>>>,
+    application_status_changes: Arc<Mutex<Vec<ApplicationStatusChange>>>,
     registrations: Arc<Mutex<Vec<Registration>>>,
+    registration_events: Arc<Mutex<Vec<RegistrationEvent>>>,
+    late_cancellations: Arc<Mutex<Vec<LateCancellation>>>,
+    attendance: Arc<Mutex<Vec<Attendance>>>,
     venues: Arc<Mutex<Vec<Venue>>>,
+    templates: Arc<Mutex<Vec<SessionTemplate>>>,
+    conversation_states: Arc<Mutex<Vec<ConversationState>>>,
 }
 
 impl InMemoryStorage {
@@ -20,112 +40,709 @@ impl InMemoryStorage {
         Self {
             sessions: Arc::new(Mutex::new(Vec::new())),
             users: Arc::new(Mutex::new(Vec::new())),
+            user_status_changes: Arc::new(Mutex::new(Vec::new())),
+            applications: Arc::new(Mutex::new(Vec::new())),
+            application_status_changes: Arc::new(Mutex::new(Vec::new())),
             registrations: Arc::new(Mutex::new(Vec::new())),
+            registration_events: Arc::new(Mutex::new(Vec::new())),
+            late_cancellations: Arc::new(Mutex::new(Vec::new())),
+            attendance: Arc::new(Mutex::new(Vec::new())),
             venues: Arc::new(Mutex::new(Vec::new())),
+            templates: Arc::new(Mutex::new(Vec::new())),
+            conversation_states: Arc::new(Mutex::new(Vec::new())),
         }
     }
+
+    /// Append to the registration log. Take this lock after the registrations
+    /// one, never before.
+    async fn log(&self, events: impl IntoIterator<Item = RegistrationEvent>) {
+        self.registration_events.lock().await.extend(events);
+    }
+
+    /// Remove a registration, only if its promotion lapsed by `lapsed_by` when
+    /// that is given, and promote substitutes into the freed place
+    async fn remove_and_promote(
+        &self,
+        session_id: Uuid,
+        user_id: Uuid,
+        lapsed_by: Option<DateTime<Utc>>,
+        confirm_by: Option<DateTime<Utc>>,
+        late: Option<&LateCancellation>,
+        actor: RegistrationActor,
+    ) -> StorageResult<Withdrawal> {
+        let mut registrations = self.registrations.lock().await;
+        let Some(position) = registrations
+            .iter()
+            .position(|r| {
+                r.session_id == session_id
+                    && r.user_id == user_id
+                    && lapsed_by.is_none_or(|now| {
+                        r.confirm_by.is_some_and(|confirm_by| confirm_by <= now)
+                    })
+            })
+        else {
+            return Ok(Withdrawal::NotRegistered);
+        };
+        // The attendance table's foreign key restricts the delete
+        let registration_id = registrations[position].id;
+        if self.attendance.lock().await.iter().any(|a| a.registration_id == registration_id) {
+            return Ok(Withdrawal::AttendanceRecorded);
+        }
+        let registration = registrations.remove(position);
+
+        let mut promoted = Vec::new();
+        let mut late_cancelled = false;
+        let session = match registration.status {
+            RegistrationStatus::Confirmed => self.get_session(session_id).await?,
+            RegistrationStatus::Substitute => None,
+        };
+        if let Some(session) = session {
+            let confirmed_count = registrations
+                .iter()
+                .filter(|r| r.session_id == session_id && r.status == RegistrationStatus::Confirmed)
+                .count();
+            let open_slots = (session.max_players as usize).saturating_sub(confirmed_count);
+
+            let mut substitutes: Vec<&mut Registration> = registrations
+                .iter_mut()
+                .filter(|r| r.session_id == session_id && r.status == RegistrationStatus::Substitute)
+                .collect();
+            substitutes.sort_by_key(|r| r.created_at);
+            if late.is_some() && substitutes.is_empty() {
+                registrations.insert(position, registration);
+                return Ok(Withdrawal::NoReplacement);
+            }
+
+            for substitute in substitutes.into_iter().take(open_slots) {
+                substitute.status = RegistrationStatus::Confirmed;
+                substitute.confirm_by = confirm_by;
+                promoted.push(substitute.clone());
+            }
+            if let Some(late) = late {
+                self.late_cancellations.lock().await.push(late.clone());
+                late_cancelled = true;
+            }
+        }
+
+        let kind = if late_cancelled {
+            RegistrationEventKind::LateCancelled
+        } else {
+            RegistrationEventKind::Cancelled
+        };
+        let promotions = promoted.iter().map(|r| {
+            RegistrationEvent::new(r, RegistrationEventKind::Promoted, RegistrationActor::System)
+        });
+        self.log(std::iter::once(RegistrationEvent::new(&registration, kind, actor)).chain(promotions))
+            .await;
+        Ok(Withdrawal::Removed {
+            registration,
+            promoted,
+            late_cancelled,
+        })
+    }
+}
+
+impl Default for InMemoryStorage {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[async_trait::async_trait]
 impl Storage for InMemoryStorage {
-    async fn get_session(&self, id: Uuid) -> Option<Session> {
+    async fn get_session(&self, id: Uuid) -> StorageResult<Option<Session>> {
         let sessions = self.sessions.lock().await;
-        sessions.iter().find(|s| s.id == id).cloned()
+        Ok(sessions.iter().find(|s| s.id == id).cloned())
     }
 
-    async fn list_sessions(&self, session_type: Option<SessionType>) -> Vec<Session> {
+    async fn list_sessions(&self, query: &SessionQuery) -> StorageResult<Vec<Session>> {
+        // Same order as the sign-up and withdrawal paths
+        let registrations = self.registrations.lock().await;
         let sessions = self.sessions.lock().await;
-        match session_type {
-            Some(st) => sessions
+        let confirmed = |session: &Session| {
+            registrations
                 .iter()
-                .filter(|s| s.session_type == st)
-                .cloned()
-                .collect(),
-            None => sessions.clone(),
+                .filter(|r| r.session_id == session.id && r.status == RegistrationStatus::Confirmed)
+                .count()
+        };
+
+        let mut listed: Vec<Session> = sessions
+            .iter()
+            .filter(|s| query.include_cancelled || s.status != SessionStatus::Cancelled)
+            .filter(|s| query.session_type.is_none_or(|st| s.session_type == st))
+            .filter(|s| query.from.is_none_or(|from| s.datetime >= from))
+            .filter(|s| query.to.is_none_or(|to| s.datetime < to))
+            .filter(|s| query.venue_id.is_none_or(|venue_id| s.venue_id == venue_id))
+            .filter(|s| {
+                query.skill_levels.is_empty()
+                    || s.skill_level.is_some_and(|level| query.skill_levels.contains(&level))
+            })
+            .filter(|s| !query.has_open_slots || confirmed(s) < s.max_players as usize)
+            .cloned()
+            .collect();
+
+        listed.sort_by(|a, b| {
+            let by_time = match query.order {
+                SortOrder::Ascending => a.datetime.cmp(&b.datetime),
+                SortOrder::Descending => b.datetime.cmp(&a.datetime),
+            };
+            by_time.then(a.id.cmp(&b.id))
+        });
+        Ok(listed
+            .into_iter()
+            .skip(query.offset as usize)
+            .take(query.limit.map_or(usize::MAX, |limit| limit as usize))
+            .collect())
+    }
+
+    async fn list_session_summaries(&self, query: &SessionQuery) -> StorageResult<Vec<SessionSummary>> {
+        let sessions = self.list_sessions(query).await?;
+        let venues = self.venues.lock().await;
+        let users = self.users.lock().await;
+        let mut registrations = self.registrations.lock().await.clone();
+        registrations.sort_by_key(|r| r.created_at);
+
+        let mut summaries = Vec::with_capacity(sessions.len());
+        for session in sessions {
+            // An inner join in Postgres
+            let Some(venue) = venues.iter().find(|v| v.id == session.venue_id) else {
+                continue;
+            };
+            let signed_up = registrations.iter().filter(|r| r.session_id == session.id);
+            let confirmed: Vec<&Registration> = signed_up
+                .clone()
+                .filter(|r| r.status == RegistrationStatus::Confirmed)
+                .collect();
+            let confirmed_first_names = confirmed
+                .iter()
+                .filter_map(|r| users.iter().find(|u| u.id == r.user_id))
+                .map(|u| u.first_name.clone())
+                .collect();
+
+            summaries.push(SessionSummary {
+                venue: venue.clone(),
+                confirmed_count: confirmed.len(),
+                substitute_count: signed_up
+                    .filter(|r| r.status == RegistrationStatus::Substitute)
+                    .count(),
+                confirmed_first_names,
+                session,
+            });
         }
+        Ok(summaries)
     }
 
-    async fn create_session(&self, session: Session) {
+    async fn create_session(&self, session: Session) -> StorageResult<()> {
+        // Mirror the foreign key on sessions.venue_id
+        if self.get_venue(session.venue_id).await?.is_none() {
+            return Err(StorageError::Constraint("sessions_venue_id_fkey".to_string()));
+        }
         let mut sessions = self.sessions.lock().await;
+        if sessions.iter().any(|s| s.id == session.id) {
+            return Err(StorageError::Conflict("sessions_pkey".to_string()));
+        }
+        if session.template_id.is_some()
+            && sessions.iter().any(|s| {
+                s.template_id == session.template_id && s.occurrence_date == session.occurrence_date
+            })
+        {
+            return Err(StorageError::Conflict(
+                "sessions_template_id_occurrence_date_key".to_string(),
+            ));
+        }
         sessions.push(session);
+        Ok(())
     }
 
-    async fn get_user(&self, id: Uuid) -> Option<User> {
+    async fn cancel_session(&self, id: Uuid, reason: &str) -> StorageResult<Option<Session>> {
+        let mut sessions = self.sessions.lock().await;
+        let Some(session) = sessions.iter_mut().find(|s| s.id == id && s.is_scheduled()) else {
+            return Ok(None);
+        };
+        session.status = SessionStatus::Cancelled;
+        session.cancellation_reason = Some(reason.to_string());
+        Ok(Some(session.clone()))
+    }
+
+    async fn update_session(&self, session: Session) -> StorageResult<SessionEdit> {
+        if self.get_venue(session.venue_id).await?.is_none() {
+            return Err(StorageError::Constraint("sessions_venue_id_fkey".to_string()));
+        }
+        let mut registrations = self.registrations.lock().await;
+        let mut sessions = self.sessions.lock().await;
+        let Some(existing) = sessions.iter_mut().find(|s| s.id == session.id && s.is_scheduled()) else {
+            return Ok(SessionEdit::NotScheduled);
+        };
+
+        let confirmed_count = registrations
+            .iter()
+            .filter(|r| r.session_id == session.id && r.status == RegistrationStatus::Confirmed)
+            .count();
+        let mut substitutes: Vec<&mut Registration> = registrations
+            .iter_mut()
+            .filter(|r| r.session_id == session.id && r.status == RegistrationStatus::Substitute)
+            .collect();
+        let open_slots = (session.max_players as usize).checked_sub(confirmed_count);
+        let Some(open_slots) = open_slots.filter(|&open| {
+            let waiting = substitutes.len().saturating_sub(open);
+            session.max_substitutes.is_none_or(|max| waiting <= max as usize)
+        }) else {
+            return Ok(SessionEdit::OverCapacity);
+        };
+
+        existing.session_type = session.session_type;
+        existing.datetime = session.datetime;
+        existing.duration_minutes = session.duration_minutes;
+        existing.venue_id = session.venue_id;
+        existing.skill_level = session.skill_level;
+        existing.max_players = session.max_players;
+        existing.max_substitutes = session.max_substitutes;
+
+        substitutes.sort_by_key(|r| r.created_at);
+        let mut promoted = Vec::new();
+        for substitute in substitutes.into_iter().take(open_slots) {
+            substitute.status = RegistrationStatus::Confirmed;
+            promoted.push(substitute.clone());
+        }
+        self.log(promoted.iter().map(|r| {
+            RegistrationEvent::new(r, RegistrationEventKind::Promoted, RegistrationActor::System)
+        }))
+        .await;
+        Ok(SessionEdit::Saved { promoted })
+    }
+
+    async fn get_user(&self, id: Uuid) -> StorageResult<Option<User>> {
         let users = self.users.lock().await;
-        users.iter().find(|u| u.id == id).cloned()
+        Ok(users.iter().find(|u| u.id == id).cloned())
     }
 
-    async fn get_user_by_phone(&self, phone: &str) -> Option<User> {
+    async fn get_user_by_phone(&self, phone: &str) -> StorageResult<Option<User>> {
         let users = self.users.lock().await;
-        users.iter().find(|u| u.phone_number == phone).cloned()
+        Ok(users.iter().find(|u| u.phone_number == phone).cloned())
     }
 
-    async fn create_user(&self, user: User) {
+    async fn create_user(&self, user: User) -> StorageResult<()> {
         let mut users = self.users.lock().await;
+        // Same unique keys as the users table
+        if users.iter().any(|u| u.id == user.id) {
+            return Err(StorageError::Conflict("users_pkey".to_string()));
+        }
+        if users.iter().any(|u| u.phone_number == user.phone_number) {
+            return Err(StorageError::Conflict("users_phone_number_key".to_string()));
+        }
+        if users.iter().any(|u| u.email == user.email) {
+            return Err(StorageError::Conflict("users_email_key".to_string()));
+        }
         users.push(user);
+        Ok(())
+    }
+
+    async fn list_users_by_status(&self, status: UserStatus) -> StorageResult<Vec<User>> {
+        let users = self.users.lock().await;
+        let mut listed: Vec<User> = users.iter().filter(|u| u.status == status).cloned().collect();
+        listed.sort_by_key(|u| u.created_at);
+        Ok(listed)
+    }
+
+    async fn change_user_status(&self, change: &UserStatusChange) -> StorageResult<bool> {
+        let mut users = self.users.lock().await;
+        let mut changes = self.user_status_changes.lock().await;
+        let Some(user) = users
+            .iter_mut()
+            .find(|u| u.id == change.user_id && u.status == change.from_status)
+        else {
+            return Ok(false);
+        };
+        user.status = change.to_status;
+        changes.push(change.clone());
+        Ok(true)
+    }
+
+    async fn get_user_status_changes(&self, user_id: Uuid) -> StorageResult<Vec<UserStatusChange>> {
+        let changes = self.user_status_changes.lock().await;
+        let mut for_user: Vec<UserStatusChange> =
+            changes.iter().filter(|c| c.user_id == user_id).cloned().collect();
+        for_user.sort_by_key(|c| c.changed_at);
+        Ok(for_user)
+    }
+
+    async fn get_application(&self, id: Uuid) -> StorageResult<Option<Application>> {
+        let applications = self.applications.lock().await;
+        Ok(applications.iter().find(|a| a.id == id).cloned())
+    }
+
+    async fn find_applications(&self, phone: &str, email: &str) -> StorageResult<Vec<Application>> {
+        let applications = self.applications.lock().await;
+        Ok(applications
+            .iter()
+            .filter(|a| a.phone_number == phone || a.email == email)
+            .cloned()
+            .collect())
+    }
+
+    async fn list_applications_by_status(&self, status: ApplicationStatus) -> StorageResult<Vec<Application>> {
+        let applications = self.applications.lock().await;
+        let mut listed: Vec<Application> =
+            applications.iter().filter(|a| a.status == status).cloned().collect();
+        listed.sort_by_key(|a| a.created_at);
+        Ok(listed)
     }
 
-    async fn get_registrations(&self, session_id: Uuid) -> Vec<Registration> {
+    async fn create_application(&self, application: Application) -> StorageResult<()> {
+        let mut applications = self.applications.lock().await;
+        // Same unique keys as the applications table
+        if applications.iter().any(|a| a.id == application.id) {
+            return Err(StorageError::Conflict("applications_pkey".to_string()));
+        }
+        let pending = applications.iter().filter(|a| a.status == ApplicationStatus::Pending);
+        if pending.clone().any(|a| a.phone_number == application.phone_number) {
+            return Err(StorageError::Conflict("applications_pending_phone_number_key".to_string()));
+        }
+        if pending.clone().any(|a| a.email == application.email) {
+            return Err(StorageError::Conflict("applications_pending_email_key".to_string()));
+        }
+        applications.push(application);
+        Ok(())
+    }
+
+    async fn decide_application(&self, change: &ApplicationStatusChange, member: Option<&User>) -> StorageResult<bool> {
+        let mut applications = self.applications.lock().await;
+        let Some(application) = applications
+            .iter_mut()
+            .find(|a| a.id == change.application_id && a.status == change.from_status)
+        else {
+            return Ok(false);
+        };
+        if let Some(member) = member {
+            // Fails on a taken phone or email, leaving the application as it was
+            self.create_user(member.clone()).await?;
+            application.user_id = Some(member.id);
+        }
+        application.status = change.to_status;
+        self.application_status_changes.lock().await.push(change.clone());
+        Ok(true)
+    }
+
+    async fn get_application_status_changes(&self, application_id: Uuid) -> StorageResult<Vec<ApplicationStatusChange>> {
+        let changes = self.application_status_changes.lock().await;
+        let mut for_application: Vec<ApplicationStatusChange> = changes
+            .iter()
+            .filter(|c| c.application_id == application_id)
+            .cloned()
+            .collect();
+        for_application.sort_by_key(|c| c.changed_at);
+        Ok(for_application)
+    }
+
+    async fn get_registrations(&self, session_id: Uuid) -> StorageResult<Vec<Registration>> {
         let registrations = self.registrations.lock().await;
-        registrations
+        let mut for_session: Vec<Registration> = registrations
             .iter()
             .filter(|r| r.session_id == session_id)
             .cloned()
-            .collect()
+            .collect();
+        for_session.sort_by_key(|r| r.created_at);
+        Ok(for_session)
     }
 
-    async fn get_user_registrations(&self, user_id: Uuid) -> Vec<Registration> {
+    async fn get_user_registrations(&self, user_id: Uuid) -> StorageResult<Vec<Registration>> {
         let registrations = self.registrations.lock().await;
-        registrations
+        Ok(registrations
             .iter()
             .filter(|r| r.user_id == user_id)
             .cloned()
-            .collect()
+            .collect())
     }
 
-    async fn create_registration(&self, registration: Registration) {
+    async fn create_registration(&self, registration: Registration) -> StorageResult<()> {
         let mut registrations = self.registrations.lock().await;
+        if registrations
+            .iter()
+            .any(|r| r.session_id == registration.session_id && r.user_id == registration.user_id)
+        {
+            return Err(StorageError::Conflict(
+                "registrations_user_id_session_id_key".to_string(),
+            ));
+        }
+        let event = RegistrationEvent::new(
+            &registration,
+            RegistrationEventKind::Registered,
+            RegistrationActor::Organiser,
+        );
         registrations.push(registration);
+        self.log([event]).await;
+        Ok(())
     }
 
-    async fn registration_exists(&self, session_id: Uuid, user_id: Uuid) -> bool {
+    async fn registration_exists(&self, session_id: Uuid, user_id: Uuid) -> StorageResult<bool> {
         let registrations = self.registrations.lock().await;
-        registrations
+        Ok(registrations
             .iter()
-            .any(|r| r.session_id == session_id && r.user_id == user_id)
+            .any(|r| r.session_id == session_id && r.user_id == user_id))
     }
 
-    async fn delete_registration(&self, session_id: Uuid, user_id: Uuid) -> bool {
+    async fn delete_registration(&self, session_id: Uuid, user_id: Uuid) -> StorageResult<bool> {
         let mut registrations = self.registrations.lock().await;
-        let initial_len = registrations.len();
-        registrations.retain(|r| !(r.session_id == session_id && r.user_id == user_id));
-        registrations.len() < initial_len
+        let Some(position) = registrations
+            .iter()
+            .position(|r| r.session_id == session_id && r.user_id == user_id)
+        else {
+            return Ok(false);
+        };
+        let registration_id = registrations[position].id;
+        if self.attendance.lock().await.iter().any(|a| a.registration_id == registration_id) {
+            return Err(StorageError::Constraint("attendance_registration_id_fkey".to_string()));
+        }
+        let registration = registrations.remove(position);
+        self.log([RegistrationEvent::new(
+            &registration,
+            RegistrationEventKind::Cancelled,
+            RegistrationActor::Organiser,
+        )])
+        .await;
+        Ok(true)
     }
 
-    async fn update_registration(&self, registration: Registration) -> bool {
+    async fn update_registration(&self, registration: Registration) -> StorageResult<bool> {
         let mut registrations = self.registrations.lock().await;
         if let Some(pos) = registrations.iter().position(|r| 
             r.session_id == registration.session_id && r.user_id == registration.user_id
         ) {
+            let kind = match (registrations[pos].status, registration.status) {
+                (RegistrationStatus::Substitute, RegistrationStatus::Confirmed) => {
+                    Some(RegistrationEventKind::Promoted)
+                }
+                (RegistrationStatus::Confirmed, RegistrationStatus::Substitute) => {
+                    Some(RegistrationEventKind::Demoted)
+                }
+                _ => None,
+            };
             registrations[pos] = registration;
-            true
+            if let Some(kind) = kind {
+                let event = RegistrationEvent::new(&registrations[pos], kind, RegistrationActor::Organiser);
+                self.log([event]).await;
+            }
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    async fn register_within_capacity(&self, session_id: Uuid, user_id: Uuid, actor: RegistrationActor) -> StorageResult<RegistrationAttempt> {
+        // Holding the registrations lock for the whole check-and-insert keeps
+        // concurrent sign-ups from both taking the last place
+        let mut registrations = self.registrations.lock().await;
+        let Some(session) = self.get_session(session_id).await? else {
+            return Ok(RegistrationAttempt::SessionNotFound);
+        };
+        if !session.is_scheduled() {
+            return Ok(RegistrationAttempt::SessionClosed);
+        }
+
+        let for_session = || registrations.iter().filter(|r| r.session_id == session_id);
+        if for_session().any(|r| r.user_id == user_id) {
+            return Ok(RegistrationAttempt::AlreadyRegistered);
+        }
+        let confirmed_count = for_session()
+            .filter(|r| r.status == RegistrationStatus::Confirmed)
+            .count();
+        let substitute_count = for_session().count() - confirmed_count;
+
+        let status = if session.has_open_slot(confirmed_count) {
+            RegistrationStatus::Confirmed
+        } else if session.has_substitute_slot(substitute_count) {
+            RegistrationStatus::Substitute
         } else {
-            false
+            return Ok(RegistrationAttempt::Full);
+        };
+
+        let registration = Registration::new(user_id, session_id, status);
+        registrations.push(registration.clone());
+        self.log([RegistrationEvent::new(&registration, RegistrationEventKind::Registered, actor)])
+            .await;
+        Ok(RegistrationAttempt::Created(registration))
+    }
+
+    async fn unregister_and_promote(&self, session_id: Uuid, user_id: Uuid, confirm_by: Option<DateTime<Utc>>, late: Option<&LateCancellation>, actor: RegistrationActor) -> StorageResult<Withdrawal> {
+        self.remove_and_promote(session_id, user_id, None, confirm_by, late, actor).await
+    }
+
+    async fn expire_and_promote(&self, session_id: Uuid, user_id: Uuid, now: DateTime<Utc>, confirm_by: Option<DateTime<Utc>>) -> StorageResult<Option<Vec<Registration>>> {
+        match self
+            .remove_and_promote(session_id, user_id, Some(now), confirm_by, None, RegistrationActor::System)
+            .await?
+        {
+            Withdrawal::Removed { promoted, .. } => Ok(Some(promoted)),
+            Withdrawal::NotRegistered | Withdrawal::NoReplacement | Withdrawal::AttendanceRecorded => Ok(None),
         }
     }
 
-    async fn get_venue(&self, id: Uuid) -> Option<Venue> {
+    async fn confirm_registration(&self, session_id: Uuid, user_id: Uuid, now: DateTime<Utc>) -> StorageResult<Option<Registration>> {
+        let mut registrations = self.registrations.lock().await;
+        let Some(registration) = registrations.iter_mut().find(|r| {
+            r.session_id == session_id && r.user_id == user_id && r.awaits_confirmation(now)
+        }) else {
+            return Ok(None);
+        };
+        registration.confirm_by = None;
+        Ok(Some(registration.clone()))
+    }
+
+    async fn list_lapsed_promotions(&self, now: DateTime<Utc>) -> StorageResult<Vec<Registration>> {
+        let mut lapsed: Vec<Registration> = self
+            .registrations
+            .lock()
+            .await
+            .iter()
+            .filter(|r| r.confirm_by.is_some_and(|confirm_by| confirm_by <= now))
+            .cloned()
+            .collect();
+        let sessions = self.sessions.lock().await;
+        lapsed.retain(|r| {
+            sessions
+                .iter()
+                .any(|s| s.id == r.session_id && s.is_scheduled() && s.datetime > now)
+        });
+        lapsed.sort_by_key(|r| r.confirm_by);
+        Ok(lapsed)
+    }
+
+    async fn list_late_cancellations(&self, user_id: Uuid) -> StorageResult<Vec<LateCancellation>> {
+        let late_cancellations = self.late_cancellations.lock().await;
+        let mut found: Vec<LateCancellation> = late_cancellations
+            .iter()
+            .filter(|c| c.user_id == user_id)
+            .cloned()
+            .collect();
+        found.sort_by_key(|c| c.cancelled_at);
+        Ok(found)
+    }
+
+    async fn get_registration_events(&self, session_id: Uuid) -> StorageResult<Vec<RegistrationEvent>> {
+        let events = self.registration_events.lock().await;
+        Ok(events
+            .iter()
+            .filter(|e| e.session_id == session_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn record_attendance(&self, attendance: &Attendance) -> StorageResult<()> {
+        let mut records = self.attendance.lock().await;
+        records.retain(|a| a.registration_id != attendance.registration_id);
+        records.push(attendance.clone());
+        Ok(())
+    }
+
+    async fn get_session_attendance(&self, session_id: Uuid) -> StorageResult<Vec<Attendance>> {
+        let records = self.attendance.lock().await;
+        let mut found: Vec<Attendance> = records
+            .iter()
+            .filter(|a| a.session_id == session_id)
+            .cloned()
+            .collect();
+        found.sort_by_key(|a| a.recorded_at);
+        Ok(found)
+    }
+
+    async fn get_user_attendance(&self, user_id: Uuid) -> StorageResult<Vec<Attendance>> {
+        let records = self.attendance.lock().await;
+        let mut found: Vec<Attendance> = records
+            .iter()
+            .filter(|a| a.user_id == user_id)
+            .cloned()
+            .collect();
+        found.sort_by_key(|a| a.recorded_at);
+        Ok(found)
+    }
+
+    async fn get_venue(&self, id: Uuid) -> StorageResult<Option<Venue>> {
         let venues = self.venues.lock().await;
-        venues.iter().find(|v| v.id == id).cloned()
+        Ok(venues.iter().find(|v| v.id == id).cloned())
     }
 
-    async fn list_venues(&self) -> Vec<Venue> {
+    async fn list_venues(&self) -> StorageResult<Vec<Venue>> {
         let venues = self.venues.lock().await;
-        venues.clone()
+        Ok(venues.clone())
     }
 
-    async fn create_venue(&self, venue: Venue) {
+    async fn create_venue(&self, venue: Venue) -> StorageResult<()> {
         let mut venues = self.venues.lock().await;
+        if venues.iter().any(|v| v.id == venue.id) {
+            return Err(StorageError::Conflict("venues_pkey".to_string()));
+        }
         venues.push(venue);
+        Ok(())
+    }
+
+    async fn get_session_template(&self, id: Uuid) -> StorageResult<Option<SessionTemplate>> {
+        let templates = self.templates.lock().await;
+        Ok(templates.iter().find(|t| t.id == id).cloned())
+    }
+
+    async fn list_session_templates(&self) -> StorageResult<Vec<SessionTemplate>> {
+        let mut templates = self.templates.lock().await.clone();
+        templates.sort_by_key(|t| (t.weekday.num_days_from_monday(), t.start_time));
+        Ok(templates)
+    }
+
+    async fn create_session_template(&self, template: SessionTemplate) -> StorageResult<()> {
+        if self.get_venue(template.venue_id).await?.is_none() {
+            return Err(StorageError::Constraint(
+                "session_templates_venue_id_fkey".to_string(),
+            ));
+        }
+        let mut templates = self.templates.lock().await;
+        if templates.iter().any(|t| t.id == template.id) {
+            return Err(StorageError::Conflict("session_templates_pkey".to_string()));
+        }
+        templates.push(template);
+        Ok(())
+    }
+
+    async fn update_session_template(&self, template: SessionTemplate) -> StorageResult<bool> {
+        let mut templates = self.templates.lock().await;
+        let Some(existing) = templates.iter_mut().find(|t| t.id == template.id) else {
+            return Ok(false);
+        };
+        *existing = template;
+        Ok(true)
+    }
+
+    async fn delete_session_template(&self, id: Uuid) -> StorageResult<bool> {
+        let mut templates = self.templates.lock().await;
+        let initial_len = templates.len();
+        templates.retain(|t| t.id != id);
+        if templates.len() == initial_len {
+            return Ok(false);
+        }
+        // Mirror ON DELETE SET NULL on sessions.template_id
+        let mut sessions = self.sessions.lock().await;
+        for session in sessions.iter_mut().filter(|s| s.template_id == Some(id)) {
+            session.template_id = None;
+        }
+        Ok(true)
     }
-}
\ No newline at end of file
+
+    async fn get_conversation_state(&self, phone: &str) -> StorageResult<Option<ConversationState>> {
+        let states = self.conversation_states.lock().await;
+        Ok(states
+            .iter()
+            .find(|s| s.phone_number == phone && !s.is_expired())
+            .cloned())
+    }
+
+    async fn save_conversation_state(&self, state: ConversationState) -> StorageResult<()> {
+        let mut states = self.conversation_states.lock().await;
+        states.retain(|s| s.phone_number != state.phone_number);
+        states.push(state);
+        Ok(())
+    }
+
+    async fn delete_conversation_state(&self, phone: &str) -> StorageResult<bool> {
+        let mut states = self.conversation_states.lock().await;
+        let initial_len = states.len();
+        states.retain(|s| s.phone_number != phone);
+        Ok(states.len() < initial_len)
+    }
+}
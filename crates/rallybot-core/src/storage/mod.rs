@@ -1,7 +1,9 @@
+mod error;
 mod in_memory;
 mod postgres;
 mod traits;
 
+pub use error::{StorageError, StorageResult};
 pub use in_memory::InMemoryStorage;
 pub use postgres::PostgresStorage;
-pub use traits::Storage;
\ No newline at end of file
+pub use traits::Storage;
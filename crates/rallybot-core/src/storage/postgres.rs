@@ -1,12 +1,143 @@
-use super::Storage;
+use super::{Storage, StorageError, StorageResult};
 use crate::{
-    models::{Session, SessionType, Venue},
-    registration::{Registration, RegistrationStatus},
-    user::{Gender, LookingFor, PlayFrequency, PreferredSide, SkillLevel, User},
+    application::{Application, ApplicationStatus, ApplicationStatusChange},
+    attendance::{Attendance, AttendanceStatus},
+    cancellation::LateCancellation,
+    conversation::{ConversationMenu, ConversationState},
+    models::{Session, SessionQuery, SessionStatus, SessionSummary, SessionType, SortOrder, Venue},
+    registration::{
+        Registration, RegistrationActor, RegistrationAttempt, RegistrationEvent,
+        RegistrationEventKind, RegistrationStatus, SessionEdit, Withdrawal,
+    },
+    template::SessionTemplate,
+    user::{
+        Gender, LookingFor, PlayFrequency, PreferredSide, SkillLevel, User, UserStatus,
+        UserStatusChange,
+    },
 };
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc, Weekday};
 use sqlx::{postgres::PgPoolOptions, PgPool};
 use uuid::Uuid;
 
+/// A `venues` row; the timezone is stored by its IANA name
+struct VenueRow {
+    id: Uuid,
+    name: String,
+    address: String,
+    timezone: String,
+}
+
+impl TryFrom<VenueRow> for Venue {
+    type Error = StorageError;
+
+    fn try_from(row: VenueRow) -> Result<Self, Self::Error> {
+        let timezone = row
+            .timezone
+            .parse()
+            .map_err(|_| StorageError::Database(format!("unknown timezone {}", row.timezone)))?;
+        Ok(Self {
+            id: row.id,
+            name: row.name,
+            address: row.address,
+            timezone,
+        })
+    }
+}
+
+/// A session joined with its venue and sign-up counts
+struct SummaryRow {
+    id: Uuid,
+    session_type: SessionType,
+    datetime: DateTime<Utc>,
+    duration_minutes: i32,
+    venue_id: Uuid,
+    skill_level: Option<SkillLevel>,
+    max_players: i32,
+    max_substitutes: Option<i32>,
+    status: SessionStatus,
+    cancellation_reason: Option<String>,
+    template_id: Option<Uuid>,
+    occurrence_date: Option<NaiveDate>,
+    venue_name: String,
+    venue_address: String,
+    venue_timezone: String,
+    confirmed_count: i64,
+    substitute_count: i64,
+    confirmed_first_names: Vec<String>,
+}
+
+impl TryFrom<SummaryRow> for SessionSummary {
+    type Error = StorageError;
+
+    fn try_from(row: SummaryRow) -> Result<Self, Self::Error> {
+        let venue = Venue::try_from(VenueRow {
+            id: row.venue_id,
+            name: row.venue_name,
+            address: row.venue_address,
+            timezone: row.venue_timezone,
+        })?;
+        Ok(Self {
+            session: Session {
+                id: row.id,
+                session_type: row.session_type,
+                datetime: row.datetime,
+                duration_minutes: row.duration_minutes,
+                venue_id: row.venue_id,
+                skill_level: row.skill_level,
+                max_players: row.max_players,
+                max_substitutes: row.max_substitutes,
+                status: row.status,
+                cancellation_reason: row.cancellation_reason,
+                template_id: row.template_id,
+                occurrence_date: row.occurrence_date,
+            },
+            venue,
+            confirmed_count: row.confirmed_count as usize,
+            substitute_count: row.substitute_count as usize,
+            confirmed_first_names: row.confirmed_first_names,
+        })
+    }
+}
+
+/// A `session_templates` row; the weekday is stored as 0 = Monday
+struct TemplateRow {
+    id: Uuid,
+    session_type: SessionType,
+    weekday: i16,
+    start_time: NaiveTime,
+    duration_minutes: i32,
+    venue_id: Uuid,
+    skill_level: Option<SkillLevel>,
+    max_players: i32,
+    max_substitutes: Option<i32>,
+    exception_dates: Vec<NaiveDate>,
+    created_at: DateTime<Utc>,
+}
+
+impl TryFrom<TemplateRow> for SessionTemplate {
+    type Error = StorageError;
+
+    fn try_from(row: TemplateRow) -> Result<Self, Self::Error> {
+        let weekday = u8::try_from(row.weekday)
+            .ok()
+            .and_then(|day| Weekday::try_from(day).ok())
+            .ok_or_else(|| StorageError::Database(format!("invalid weekday {}", row.weekday)))?;
+        Ok(Self {
+            id: row.id,
+            session_type: row.session_type,
+            weekday,
+            start_time: row.start_time,
+            duration_minutes: row.duration_minutes,
+            venue_id: row.venue_id,
+            skill_level: row.skill_level,
+            max_players: row.max_players,
+            max_substitutes: row.max_substitutes,
+            exception_dates: row.exception_dates,
+            created_at: row.created_at,
+        })
+    }
+}
+
 #[derive(Clone)]
 pub struct PostgresStorage {
     pool: PgPool,
@@ -25,77 +156,397 @@ impl PostgresStorage {
     pub fn new_with_pool(pool: PgPool) -> Self {
         Self { pool }
     }
-}
 
-#[async_trait::async_trait]
-impl Storage for PostgresStorage {
-    async fn get_session(&self, id: Uuid) -> Option<Session> {
+    /// Lock the session row for the rest of the transaction, so sign-ups and
+    /// cancellations for the same session run one at a time
+    async fn lock_session(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        session_id: Uuid,
+    ) -> StorageResult<Option<Session>> {
         sqlx::query_as!(
             Session,
             r#"
-            SELECT id, session_type as "session_type: SessionType", datetime, duration_minutes, venue_id, skill_level as "skill_level: SkillLevel"
+            SELECT id, session_type as "session_type: SessionType", datetime, duration_minutes, venue_id, skill_level as "skill_level: SkillLevel", max_players, max_substitutes, status as "status: SessionStatus", cancellation_reason, template_id, occurrence_date
             FROM sessions
             WHERE id = $1
+            FOR UPDATE
             "#,
-            id
+            session_id
         )
-        .fetch_optional(&self.pool)
+        .fetch_optional(&mut **tx)
         .await
-        .ok()
-        .flatten()
+        .map_err(StorageError::from)
     }
 
-    async fn list_sessions(&self, session_type: Option<SessionType>) -> Vec<Session> {
-        match session_type {
-            Some(st) => {
-                sqlx::query_as!(
-                    Session,
-                    r#"
-                    SELECT id, session_type as "session_type: SessionType", datetime, duration_minutes, venue_id, skill_level as "skill_level: SkillLevel"
-                    FROM sessions
-                    WHERE session_type = $1
-                    ORDER BY datetime
-                    "#,
-                    st as SessionType
-                )
-                .fetch_all(&self.pool)
-                .await
-                .unwrap_or_default()
+    /// Append to the registration log as part of the transaction
+    async fn log(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        events: &[RegistrationEvent],
+    ) -> StorageResult<()> {
+        for event in events {
+            sqlx::query!(
+                r#"
+                INSERT INTO registration_events (id, registration_id, session_id, user_id, kind, status, actor, occurred_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                "#,
+                event.id,
+                event.registration_id,
+                event.session_id,
+                event.user_id,
+                event.kind as RegistrationEventKind,
+                event.status as Option<RegistrationStatus>,
+                event.actor as RegistrationActor,
+                event.occurred_at
+            )
+            .execute(&mut **tx)
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Remove a registration, only if its promotion lapsed by `lapsed_by` when
+    /// that is given, and promote substitutes into the freed place
+    async fn remove_and_promote(
+        &self,
+        session_id: Uuid,
+        user_id: Uuid,
+        lapsed_by: Option<DateTime<Utc>>,
+        confirm_by: Option<DateTime<Utc>>,
+        late: Option<&LateCancellation>,
+        actor: RegistrationActor,
+    ) -> StorageResult<Withdrawal> {
+        let mut tx = self.pool.begin().await?;
+        let Some(session) = Self::lock_session(&mut tx, session_id).await? else {
+            return Ok(Withdrawal::NotRegistered);
+        };
+
+        // The attendance table's foreign key would refuse the delete
+        let attended = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM attendance a
+                JOIN registrations r ON r.id = a.registration_id
+                WHERE r.session_id = $1 AND r.user_id = $2
+            ) as "exists!"
+            "#,
+            session_id,
+            user_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if attended {
+            return Ok(Withdrawal::AttendanceRecorded);
+        }
+
+        let Some(registration) = sqlx::query_as!(
+            Registration,
+            r#"
+            DELETE FROM registrations
+            WHERE session_id = $1 AND user_id = $2
+              AND ($3::timestamptz IS NULL OR confirm_by <= $3)
+            RETURNING id, user_id, session_id, status as "status: RegistrationStatus", created_at, confirm_by
+            "#,
+            session_id,
+            user_id,
+            lapsed_by
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Ok(Withdrawal::NotRegistered);
+        };
+
+        let mut promoted = Vec::new();
+        let mut late_cancelled = false;
+        if registration.status == RegistrationStatus::Confirmed {
+            let counts = sqlx::query!(
+                r#"
+                SELECT COUNT(*) FILTER (WHERE status = 'Confirmed') as "confirmed!",
+                       COUNT(*) FILTER (WHERE status = 'Substitute') as "substitutes!"
+                FROM registrations
+                WHERE session_id = $1
+                "#,
+                session_id
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+            // Dropping the transaction puts the registration back
+            if late.is_some() && counts.substitutes == 0 {
+                return Ok(Withdrawal::NoReplacement);
             }
-            None => {
-                sqlx::query_as!(
-                    Session,
+            let open_slots = (session.max_players as i64 - counts.confirmed).max(0);
+
+            promoted = sqlx::query_as!(
+                Registration,
+                r#"
+                UPDATE registrations
+                SET status = 'Confirmed', confirm_by = $3
+                WHERE id IN (
+                    SELECT id FROM registrations
+                    WHERE session_id = $1 AND status = 'Substitute'
+                    ORDER BY created_at
+                    LIMIT $2
+                )
+                RETURNING id, user_id, session_id, status as "status: RegistrationStatus", created_at, confirm_by
+                "#,
+                session_id,
+                open_slots,
+                confirm_by
+            )
+            .fetch_all(&mut *tx)
+            .await?;
+            promoted.sort_by_key(|r| r.created_at);
+
+            if let Some(late) = late {
+                sqlx::query!(
                     r#"
-                    SELECT id, session_type as "session_type: SessionType", datetime, duration_minutes, venue_id, skill_level as "skill_level: SkillLevel"
-                    FROM sessions
-                    ORDER BY datetime
-                    "#
+                    INSERT INTO late_cancellations (id, user_id, session_id, session_starts_at, cancelled_at)
+                    VALUES ($1, $2, $3, $4, $5)
+                    "#,
+                    late.id,
+                    late.user_id,
+                    late.session_id,
+                    late.session_starts_at,
+                    late.cancelled_at
                 )
-                .fetch_all(&self.pool)
-                .await
-                .unwrap_or_default()
+                .execute(&mut *tx)
+                .await?;
+                late_cancelled = true;
             }
         }
+
+        let kind = if late_cancelled {
+            RegistrationEventKind::LateCancelled
+        } else {
+            RegistrationEventKind::Cancelled
+        };
+        let mut events = vec![RegistrationEvent::new(&registration, kind, actor)];
+        events.extend(promoted.iter().map(|r| {
+            RegistrationEvent::new(r, RegistrationEventKind::Promoted, RegistrationActor::System)
+        }));
+        Self::log(&mut tx, &events).await?;
+        tx.commit().await?;
+        Ok(Withdrawal::Removed {
+            registration,
+            promoted,
+            late_cancelled,
+        })
     }
+}
 
-    async fn create_session(&self, session: Session) {
-        let _ = sqlx::query!(
+#[async_trait::async_trait]
+impl Storage for PostgresStorage {
+    async fn get_session(&self, id: Uuid) -> StorageResult<Option<Session>> {
+        sqlx::query_as!(
+            Session,
             r#"
-            INSERT INTO sessions (id, session_type, datetime, duration_minutes, venue_id, skill_level)
-            VALUES ($1, $2, $3, $4, $5, $6)
+            SELECT id, session_type as "session_type: SessionType", datetime, duration_minutes, venue_id, skill_level as "skill_level: SkillLevel", max_players, max_substitutes, status as "status: SessionStatus", cancellation_reason, template_id, occurrence_date
+            FROM sessions
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(StorageError::from)
+    }
+
+    async fn list_sessions(&self, query: &SessionQuery) -> StorageResult<Vec<Session>> {
+        // Unset filters are passed as NULL (or an empty array) and match everything
+        sqlx::query_as!(
+            Session,
+            r#"
+            SELECT id, session_type as "session_type: SessionType", datetime, duration_minutes, venue_id, skill_level as "skill_level: SkillLevel", max_players, max_substitutes, status as "status: SessionStatus", cancellation_reason, template_id, occurrence_date
+            FROM sessions s
+            WHERE ($1::session_type IS NULL OR s.session_type = $1)
+              AND ($2::timestamptz IS NULL OR s.datetime >= $2)
+              AND ($3::timestamptz IS NULL OR s.datetime < $3)
+              AND ($4::uuid IS NULL OR s.venue_id = $4)
+              AND (cardinality($5::skill_level[]) = 0 OR s.skill_level = ANY($5))
+              AND (NOT $6 OR s.max_players > (
+                  SELECT COUNT(*) FROM registrations r
+                  WHERE r.session_id = s.id AND r.status = 'Confirmed'
+              ))
+              AND ($7 OR s.status <> 'Cancelled')
+            ORDER BY CASE WHEN $8 THEN s.datetime END DESC, s.datetime, s.id
+            LIMIT $9 OFFSET $10
+            "#,
+            query.session_type as Option<SessionType>,
+            query.from,
+            query.to,
+            query.venue_id,
+            &query.skill_levels as &[SkillLevel],
+            query.has_open_slots,
+            query.include_cancelled,
+            query.order == SortOrder::Descending,
+            query.limit.map(i64::from),
+            i64::from(query.offset)
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(StorageError::from)
+    }
+
+    async fn list_session_summaries(&self, query: &SessionQuery) -> StorageResult<Vec<SessionSummary>> {
+        // The filters match list_sessions; open slots are counted from the join
+        sqlx::query_as!(
+            SummaryRow,
+            r#"
+            SELECT s.id, s.session_type as "session_type: SessionType", s.datetime, s.duration_minutes, s.venue_id, s.skill_level as "skill_level: SkillLevel", s.max_players, s.max_substitutes, s.status as "status: SessionStatus", s.cancellation_reason, s.template_id, s.occurrence_date,
+                   v.name AS venue_name, v.address AS venue_address, v.timezone AS venue_timezone,
+                   COUNT(r.id) FILTER (WHERE r.status = 'Confirmed') AS "confirmed_count!",
+                   COUNT(r.id) FILTER (WHERE r.status = 'Substitute') AS "substitute_count!",
+                   COALESCE(
+                       array_agg(u.first_name ORDER BY r.created_at) FILTER (WHERE r.status = 'Confirmed'),
+                       '{}'
+                   ) AS "confirmed_first_names!"
+            FROM sessions s
+            JOIN venues v ON v.id = s.venue_id
+            LEFT JOIN registrations r ON r.session_id = s.id
+            LEFT JOIN users u ON u.id = r.user_id
+            WHERE ($1::session_type IS NULL OR s.session_type = $1)
+              AND ($2::timestamptz IS NULL OR s.datetime >= $2)
+              AND ($3::timestamptz IS NULL OR s.datetime < $3)
+              AND ($4::uuid IS NULL OR s.venue_id = $4)
+              AND (cardinality($5::skill_level[]) = 0 OR s.skill_level = ANY($5))
+              AND ($7 OR s.status <> 'Cancelled')
+            GROUP BY s.id, v.id
+            HAVING NOT $6 OR s.max_players > COUNT(r.id) FILTER (WHERE r.status = 'Confirmed')
+            ORDER BY CASE WHEN $8 THEN s.datetime END DESC, s.datetime, s.id
+            LIMIT $9 OFFSET $10
+            "#,
+            query.session_type as Option<SessionType>,
+            query.from,
+            query.to,
+            query.venue_id,
+            &query.skill_levels as &[SkillLevel],
+            query.has_open_slots,
+            query.include_cancelled,
+            query.order == SortOrder::Descending,
+            query.limit.map(i64::from),
+            i64::from(query.offset)
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(SessionSummary::try_from)
+        .collect()
+    }
+
+    async fn create_session(&self, session: Session) -> StorageResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO sessions (id, session_type, datetime, duration_minutes, venue_id, skill_level, max_players, max_substitutes, status, cancellation_reason, template_id, occurrence_date)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
             "#,
             session.id,
             session.session_type as SessionType,
             session.datetime,
             session.duration_minutes as i32,
             session.venue_id,
-            session.skill_level as _
+            session.skill_level as _,
+            session.max_players,
+            session.max_substitutes,
+            session.status as SessionStatus,
+            session.cancellation_reason,
+            session.template_id,
+            session.occurrence_date
         )
         .execute(&self.pool)
-        .await;
+        .await?;
+        Ok(())
+    }
+
+    async fn cancel_session(&self, id: Uuid, reason: &str) -> StorageResult<Option<Session>> {
+        sqlx::query_as!(
+            Session,
+            r#"
+            UPDATE sessions
+            SET status = 'Cancelled', cancellation_reason = $2
+            WHERE id = $1 AND status = 'Scheduled'
+            RETURNING id, session_type as "session_type: SessionType", datetime, duration_minutes, venue_id, skill_level as "skill_level: SkillLevel", max_players, max_substitutes, status as "status: SessionStatus", cancellation_reason, template_id, occurrence_date
+            "#,
+            id,
+            reason
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(StorageError::from)
+    }
+
+    async fn update_session(&self, session: Session) -> StorageResult<SessionEdit> {
+        let mut tx = self.pool.begin().await?;
+        match Self::lock_session(&mut tx, session.id).await? {
+            Some(existing) if existing.is_scheduled() => {}
+            _ => return Ok(SessionEdit::NotScheduled),
+        }
+
+        let counts = sqlx::query!(
+            r#"
+            SELECT COUNT(*) FILTER (WHERE status = 'Confirmed') as "confirmed!",
+                   COUNT(*) FILTER (WHERE status = 'Substitute') as "substitutes!"
+            FROM registrations
+            WHERE session_id = $1
+            "#,
+            session.id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        let open_slots = session.max_players as i64 - counts.confirmed;
+        let waiting = (counts.substitutes - open_slots).max(0);
+        if open_slots < 0 || session.max_substitutes.is_some_and(|max| waiting > max as i64) {
+            return Ok(SessionEdit::OverCapacity);
+        }
+
+        sqlx::query!(
+            r#"
+            UPDATE sessions
+            SET session_type = $2, datetime = $3, duration_minutes = $4, venue_id = $5, skill_level = $6,
+                max_players = $7, max_substitutes = $8
+            WHERE id = $1
+            "#,
+            session.id,
+            session.session_type as SessionType,
+            session.datetime,
+            session.duration_minutes,
+            session.venue_id,
+            session.skill_level as _,
+            session.max_players,
+            session.max_substitutes
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let mut promoted = sqlx::query_as!(
+            Registration,
+            r#"
+            UPDATE registrations
+            SET status = 'Confirmed'
+            WHERE id IN (
+                SELECT id FROM registrations
+                WHERE session_id = $1 AND status = 'Substitute'
+                ORDER BY created_at
+                LIMIT $2
+            )
+            RETURNING id, user_id, session_id, status as "status: RegistrationStatus", created_at, confirm_by
+            "#,
+            session.id,
+            open_slots
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        promoted.sort_by_key(|r| r.created_at);
+
+        let events: Vec<_> = promoted
+            .iter()
+            .map(|r| RegistrationEvent::new(r, RegistrationEventKind::Promoted, RegistrationActor::System))
+            .collect();
+        Self::log(&mut tx, &events).await?;
+        tx.commit().await?;
+        Ok(SessionEdit::Saved { promoted })
     }
 
-    async fn get_user(&self, id: Uuid) -> Option<User> {
+    async fn get_user(&self, id: Uuid) -> StorageResult<Option<User>> {
         sqlx::query_as!(
             User,
             r#"
@@ -105,7 +556,7 @@ impl Storage for PostgresStorage {
                    preferred_side as "preferred_side: PreferredSide",
                    play_frequency as "play_frequency: PlayFrequency",
                    looking_for as "looking_for: Vec<LookingFor>",
-                   is_approved, created_at
+                   status as "status: UserStatus", created_at
             FROM users
             WHERE id = $1
             "#,
@@ -113,11 +564,10 @@ impl Storage for PostgresStorage {
         )
         .fetch_optional(&self.pool)
         .await
-        .ok()
-        .flatten()
+        .map_err(StorageError::from)
     }
 
-    async fn get_user_by_phone(&self, phone: &str) -> Option<User> {
+    async fn get_user_by_phone(&self, phone: &str) -> StorageResult<Option<User>> {
         sqlx::query_as!(
             User,
             r#"
@@ -127,7 +577,7 @@ impl Storage for PostgresStorage {
                    preferred_side as "preferred_side: PreferredSide",
                    play_frequency as "play_frequency: PlayFrequency",
                    looking_for as "looking_for: Vec<LookingFor>",
-                   is_approved, created_at
+                   status as "status: UserStatus", created_at
             FROM users
             WHERE phone_number = $1
             "#,
@@ -135,17 +585,16 @@ impl Storage for PostgresStorage {
         )
         .fetch_optional(&self.pool)
         .await
-        .ok()
-        .flatten()
+        .map_err(StorageError::from)
     }
 
-    async fn create_user(&self, user: User) {
-        let _ = sqlx::query!(
+    async fn create_user(&self, user: User) -> StorageResult<()> {
+        sqlx::query!(
             r#"
             INSERT INTO users (id, first_name, last_name, phone_number, email, city,
                              photo_url, occupation, company, industry, linkedin_url, gender,
                              skill_levels, preferred_side, play_frequency, looking_for,
-                             is_approved, created_at)
+                             status, created_at)
             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)
             "#,
             user.id,
@@ -164,18 +613,287 @@ impl Storage for PostgresStorage {
             user.preferred_side as PreferredSide,
             user.play_frequency as PlayFrequency,
             &user.looking_for as &Vec<LookingFor>,
-            user.is_approved,
+            user.status as UserStatus,
             user.created_at
         )
         .execute(&self.pool)
-        .await;
+        .await?;
+        Ok(())
+    }
+
+    async fn list_users_by_status(&self, status: UserStatus) -> StorageResult<Vec<User>> {
+        sqlx::query_as!(
+            User,
+            r#"
+            SELECT id, first_name, last_name, phone_number, email, city, photo_url,
+                   occupation, company, industry, linkedin_url, gender as "gender: Gender",
+                   skill_levels as "skill_levels: Vec<SkillLevel>",
+                   preferred_side as "preferred_side: PreferredSide",
+                   play_frequency as "play_frequency: PlayFrequency",
+                   looking_for as "looking_for: Vec<LookingFor>",
+                   status as "status: UserStatus", created_at
+            FROM users
+            WHERE status = $1
+            ORDER BY created_at, id
+            "#,
+            status as UserStatus
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(StorageError::from)
+    }
+
+    async fn change_user_status(&self, change: &UserStatusChange) -> StorageResult<bool> {
+        let mut tx = self.pool.begin().await?;
+        let updated = sqlx::query!(
+            r#"
+            UPDATE users
+            SET status = $3
+            WHERE id = $1 AND status = $2
+            "#,
+            change.user_id,
+            change.from_status as UserStatus,
+            change.to_status as UserStatus
+        )
+        .execute(&mut *tx)
+        .await?;
+        if updated.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO user_status_changes (id, user_id, from_status, to_status, changed_by, reason, changed_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+            change.id,
+            change.user_id,
+            change.from_status as UserStatus,
+            change.to_status as UserStatus,
+            change.changed_by,
+            change.reason,
+            change.changed_at
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn get_user_status_changes(&self, user_id: Uuid) -> StorageResult<Vec<UserStatusChange>> {
+        sqlx::query_as!(
+            UserStatusChange,
+            r#"
+            SELECT id, user_id, from_status as "from_status: UserStatus",
+                   to_status as "to_status: UserStatus", changed_by, reason, changed_at
+            FROM user_status_changes
+            WHERE user_id = $1
+            ORDER BY changed_at, id
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(StorageError::from)
+    }
+
+    async fn get_application(&self, id: Uuid) -> StorageResult<Option<Application>> {
+        sqlx::query_as!(
+            Application,
+            r#"
+            SELECT id, first_name, last_name, phone_number, email, city, photo_url,
+                   occupation, company, industry, linkedin_url, gender as "gender: Gender",
+                   skill_levels as "skill_levels: Vec<SkillLevel>",
+                   preferred_side as "preferred_side: PreferredSide",
+                   play_frequency as "play_frequency: PlayFrequency",
+                   looking_for as "looking_for: Vec<LookingFor>",
+                   status as "status: ApplicationStatus", user_id, created_at
+            FROM applications
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(StorageError::from)
+    }
+
+    async fn find_applications(&self, phone: &str, email: &str) -> StorageResult<Vec<Application>> {
+        sqlx::query_as!(
+            Application,
+            r#"
+            SELECT id, first_name, last_name, phone_number, email, city, photo_url,
+                   occupation, company, industry, linkedin_url, gender as "gender: Gender",
+                   skill_levels as "skill_levels: Vec<SkillLevel>",
+                   preferred_side as "preferred_side: PreferredSide",
+                   play_frequency as "play_frequency: PlayFrequency",
+                   looking_for as "looking_for: Vec<LookingFor>",
+                   status as "status: ApplicationStatus", user_id, created_at
+            FROM applications
+            WHERE phone_number = $1 OR email = $2
+            ORDER BY created_at, id
+            "#,
+            phone,
+            email
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(StorageError::from)
+    }
+
+    async fn list_applications_by_status(&self, status: ApplicationStatus) -> StorageResult<Vec<Application>> {
+        sqlx::query_as!(
+            Application,
+            r#"
+            SELECT id, first_name, last_name, phone_number, email, city, photo_url,
+                   occupation, company, industry, linkedin_url, gender as "gender: Gender",
+                   skill_levels as "skill_levels: Vec<SkillLevel>",
+                   preferred_side as "preferred_side: PreferredSide",
+                   play_frequency as "play_frequency: PlayFrequency",
+                   looking_for as "looking_for: Vec<LookingFor>",
+                   status as "status: ApplicationStatus", user_id, created_at
+            FROM applications
+            WHERE status = $1
+            ORDER BY created_at, id
+            "#,
+            status as ApplicationStatus
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(StorageError::from)
+    }
+
+    async fn create_application(&self, application: Application) -> StorageResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO applications (id, first_name, last_name, phone_number, email, city,
+                                      photo_url, occupation, company, industry, linkedin_url, gender,
+                                      skill_levels, preferred_side, play_frequency, looking_for,
+                                      status, user_id, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19)
+            "#,
+            application.id,
+            application.first_name,
+            application.last_name,
+            application.phone_number,
+            application.email,
+            application.city,
+            application.photo_url,
+            application.occupation,
+            application.company,
+            application.industry,
+            application.linkedin_url,
+            application.gender as Gender,
+            &application.skill_levels as &Vec<SkillLevel>,
+            application.preferred_side as PreferredSide,
+            application.play_frequency as PlayFrequency,
+            &application.looking_for as &Vec<LookingFor>,
+            application.status as ApplicationStatus,
+            application.user_id,
+            application.created_at
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
     }
 
-    async fn get_registrations(&self, session_id: Uuid) -> Vec<Registration> {
+    async fn decide_application(&self, change: &ApplicationStatusChange, member: Option<&User>) -> StorageResult<bool> {
+        let mut tx = self.pool.begin().await?;
+        // Inserted first so the application can reference it
+        if let Some(user) = member {
+            sqlx::query!(
+                r#"
+                INSERT INTO users (id, first_name, last_name, phone_number, email, city,
+                                 photo_url, occupation, company, industry, linkedin_url, gender,
+                                 skill_levels, preferred_side, play_frequency, looking_for,
+                                 status, created_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)
+                "#,
+                user.id,
+                user.first_name,
+                user.last_name,
+                user.phone_number,
+                user.email,
+                user.city,
+                user.photo_url,
+                user.occupation,
+                user.company,
+                user.industry,
+                user.linkedin_url,
+                user.gender as Gender,
+                &user.skill_levels as &Vec<SkillLevel>,
+                user.preferred_side as PreferredSide,
+                user.play_frequency as PlayFrequency,
+                &user.looking_for as &Vec<LookingFor>,
+                user.status as UserStatus,
+                user.created_at
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        // Only applies if nobody decided in the meantime; returning early
+        // rolls back the member inserted above
+        let updated = sqlx::query!(
+            r#"
+            UPDATE applications
+            SET status = $3, user_id = $4
+            WHERE id = $1 AND status = $2
+            "#,
+            change.application_id,
+            change.from_status as ApplicationStatus,
+            change.to_status as ApplicationStatus,
+            member.map(|user| user.id)
+        )
+        .execute(&mut *tx)
+        .await?;
+        if updated.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO application_status_changes (id, application_id, from_status, to_status, changed_by, reason, changed_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+            change.id,
+            change.application_id,
+            change.from_status as ApplicationStatus,
+            change.to_status as ApplicationStatus,
+            change.changed_by,
+            change.reason,
+            change.changed_at
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn get_application_status_changes(&self, application_id: Uuid) -> StorageResult<Vec<ApplicationStatusChange>> {
+        sqlx::query_as!(
+            ApplicationStatusChange,
+            r#"
+            SELECT id, application_id, from_status as "from_status: ApplicationStatus",
+                   to_status as "to_status: ApplicationStatus", changed_by, reason, changed_at
+            FROM application_status_changes
+            WHERE application_id = $1
+            ORDER BY changed_at, id
+            "#,
+            application_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(StorageError::from)
+    }
+
+    async fn get_registrations(&self, session_id: Uuid) -> StorageResult<Vec<Registration>> {
         sqlx::query_as!(
             Registration,
             r#"
-            SELECT id, user_id, session_id, status as "status: RegistrationStatus", created_at
+            SELECT id, user_id, session_id, status as "status: RegistrationStatus", created_at, confirm_by
             FROM registrations
             WHERE session_id = $1
             ORDER BY created_at
@@ -184,14 +902,14 @@ impl Storage for PostgresStorage {
         )
         .fetch_all(&self.pool)
         .await
-        .unwrap_or_default()
+        .map_err(StorageError::from)
     }
 
-    async fn get_user_registrations(&self, user_id: Uuid) -> Vec<Registration> {
+    async fn get_user_registrations(&self, user_id: Uuid) -> StorageResult<Vec<Registration>> {
         sqlx::query_as!(
             Registration,
             r#"
-            SELECT id, user_id, session_id, status as "status: RegistrationStatus", created_at
+            SELECT id, user_id, session_id, status as "status: RegistrationStatus", created_at, confirm_by
             FROM registrations
             WHERE user_id = $1
             ORDER BY created_at DESC
@@ -200,26 +918,36 @@ impl Storage for PostgresStorage {
         )
         .fetch_all(&self.pool)
         .await
-        .unwrap_or_default()
+        .map_err(StorageError::from)
     }
 
-    async fn create_registration(&self, registration: Registration) {
-        let _ = sqlx::query!(
+    async fn create_registration(&self, registration: Registration) -> StorageResult<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query!(
             r#"
-            INSERT INTO registrations (id, user_id, session_id, status, created_at)
-            VALUES ($1, $2, $3, $4, $5)
+            INSERT INTO registrations (id, user_id, session_id, status, created_at, confirm_by)
+            VALUES ($1, $2, $3, $4, $5, $6)
             "#,
             registration.id,
             registration.user_id,
             registration.session_id,
             registration.status as RegistrationStatus,
-            registration.created_at
+            registration.created_at,
+            registration.confirm_by
         )
-        .execute(&self.pool)
-        .await;
+        .execute(&mut *tx)
+        .await?;
+        let event = RegistrationEvent::new(
+            &registration,
+            RegistrationEventKind::Registered,
+            RegistrationActor::Organiser,
+        );
+        Self::log(&mut tx, &[event]).await?;
+        tx.commit().await?;
+        Ok(())
     }
 
-    async fn registration_exists(&self, session_id: Uuid, user_id: Uuid) -> bool {
+    async fn registration_exists(&self, session_id: Uuid, user_id: Uuid) -> StorageResult<bool> {
         sqlx::query!(
             r#"
             SELECT EXISTS(
@@ -233,69 +961,471 @@ impl Storage for PostgresStorage {
         .fetch_one(&self.pool)
         .await
         .map(|r| r.exists)
-        .unwrap_or(false)
+        .map_err(StorageError::from)
     }
 
-    async fn delete_registration(&self, session_id: Uuid, user_id: Uuid) -> bool {
-        sqlx::query!(
+    async fn delete_registration(&self, session_id: Uuid, user_id: Uuid) -> StorageResult<bool> {
+        let mut tx = self.pool.begin().await?;
+        let Some(registration) = sqlx::query_as!(
+            Registration,
             r#"
             DELETE FROM registrations
             WHERE session_id = $1 AND user_id = $2
+            RETURNING id, user_id, session_id, status as "status: RegistrationStatus", created_at, confirm_by
             "#,
             session_id,
             user_id
         )
-        .execute(&self.pool)
-        .await
-        .map(|result| result.rows_affected() > 0)
-        .unwrap_or(false)
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Ok(false);
+        };
+        let event = RegistrationEvent::new(
+            &registration,
+            RegistrationEventKind::Cancelled,
+            RegistrationActor::Organiser,
+        );
+        Self::log(&mut tx, &[event]).await?;
+        tx.commit().await?;
+        Ok(true)
     }
 
-    async fn update_registration(&self, registration: Registration) -> bool {
+    async fn update_registration(&self, registration: Registration) -> StorageResult<bool> {
+        let mut tx = self.pool.begin().await?;
+        let Some(previous) = sqlx::query_scalar!(
+            r#"
+            SELECT status as "status: RegistrationStatus"
+            FROM registrations
+            WHERE session_id = $1 AND user_id = $2
+            FOR UPDATE
+            "#,
+            registration.session_id,
+            registration.user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Ok(false);
+        };
+
         sqlx::query!(
             r#"
             UPDATE registrations
-            SET status = $3, created_at = $4
+            SET status = $3, created_at = $4, confirm_by = $5
             WHERE session_id = $1 AND user_id = $2
             "#,
             registration.session_id,
             registration.user_id,
             registration.status as RegistrationStatus,
+            registration.created_at,
+            registration.confirm_by
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let kind = match (previous, registration.status) {
+            (RegistrationStatus::Substitute, RegistrationStatus::Confirmed) => {
+                Some(RegistrationEventKind::Promoted)
+            }
+            (RegistrationStatus::Confirmed, RegistrationStatus::Substitute) => {
+                Some(RegistrationEventKind::Demoted)
+            }
+            _ => None,
+        };
+        if let Some(kind) = kind {
+            let event = RegistrationEvent::new(&registration, kind, RegistrationActor::Organiser);
+            Self::log(&mut tx, &[event]).await?;
+        }
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn register_within_capacity(
+        &self,
+        session_id: Uuid,
+        user_id: Uuid,
+        actor: RegistrationActor,
+    ) -> StorageResult<RegistrationAttempt> {
+        let mut tx = self.pool.begin().await?;
+        let Some(session) = Self::lock_session(&mut tx, session_id).await? else {
+            return Ok(RegistrationAttempt::SessionNotFound);
+        };
+        if !session.is_scheduled() {
+            return Ok(RegistrationAttempt::SessionClosed);
+        }
+
+        let counts = sqlx::query!(
+            r#"
+            SELECT COUNT(*) FILTER (WHERE status = 'Confirmed') as "confirmed!",
+                   COUNT(*) FILTER (WHERE status = 'Substitute') as "substitutes!",
+                   COUNT(*) FILTER (WHERE user_id = $2) as "existing!"
+            FROM registrations
+            WHERE session_id = $1
+            "#,
+            session_id,
+            user_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if counts.existing > 0 {
+            return Ok(RegistrationAttempt::AlreadyRegistered);
+        }
+        let status = if session.has_open_slot(counts.confirmed as usize) {
+            RegistrationStatus::Confirmed
+        } else if session.has_substitute_slot(counts.substitutes as usize) {
+            RegistrationStatus::Substitute
+        } else {
+            return Ok(RegistrationAttempt::Full);
+        };
+
+        let registration = Registration::new(user_id, session_id, status);
+        sqlx::query!(
+            r#"
+            INSERT INTO registrations (id, user_id, session_id, status, created_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+            registration.id,
+            registration.user_id,
+            registration.session_id,
+            registration.status as RegistrationStatus,
             registration.created_at
         )
+        .execute(&mut *tx)
+        .await?;
+        let event = RegistrationEvent::new(&registration, RegistrationEventKind::Registered, actor);
+        Self::log(&mut tx, &[event]).await?;
+
+        tx.commit().await?;
+        Ok(RegistrationAttempt::Created(registration))
+    }
+
+    async fn unregister_and_promote(
+        &self,
+        session_id: Uuid,
+        user_id: Uuid,
+        confirm_by: Option<DateTime<Utc>>,
+        late: Option<&LateCancellation>,
+        actor: RegistrationActor,
+    ) -> StorageResult<Withdrawal> {
+        self.remove_and_promote(session_id, user_id, None, confirm_by, late, actor).await
+    }
+
+    async fn expire_and_promote(
+        &self,
+        session_id: Uuid,
+        user_id: Uuid,
+        now: DateTime<Utc>,
+        confirm_by: Option<DateTime<Utc>>,
+    ) -> StorageResult<Option<Vec<Registration>>> {
+        match self
+            .remove_and_promote(session_id, user_id, Some(now), confirm_by, None, RegistrationActor::System)
+            .await?
+        {
+            Withdrawal::Removed { promoted, .. } => Ok(Some(promoted)),
+            Withdrawal::NotRegistered | Withdrawal::NoReplacement | Withdrawal::AttendanceRecorded => Ok(None),
+        }
+    }
+
+    async fn confirm_registration(
+        &self,
+        session_id: Uuid,
+        user_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> StorageResult<Option<Registration>> {
+        sqlx::query_as!(
+            Registration,
+            r#"
+            UPDATE registrations
+            SET confirm_by = NULL
+            WHERE session_id = $1 AND user_id = $2 AND confirm_by > $3
+            RETURNING id, user_id, session_id, status as "status: RegistrationStatus", created_at, confirm_by
+            "#,
+            session_id,
+            user_id,
+            now
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(StorageError::from)
+    }
+
+    async fn list_lapsed_promotions(&self, now: DateTime<Utc>) -> StorageResult<Vec<Registration>> {
+        sqlx::query_as!(
+            Registration,
+            r#"
+            SELECT r.id, r.user_id, r.session_id, r.status as "status: RegistrationStatus",
+                   r.created_at, r.confirm_by
+            FROM registrations r
+            JOIN sessions s ON s.id = r.session_id
+            WHERE r.confirm_by <= $1 AND s.status = 'Scheduled' AND s.datetime > $1
+            ORDER BY r.confirm_by
+            "#,
+            now
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(StorageError::from)
+    }
+
+    async fn list_late_cancellations(&self, user_id: Uuid) -> StorageResult<Vec<LateCancellation>> {
+        sqlx::query_as!(
+            LateCancellation,
+            r#"
+            SELECT id, user_id, session_id, session_starts_at, cancelled_at
+            FROM late_cancellations
+            WHERE user_id = $1
+            ORDER BY cancelled_at
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(StorageError::from)
+    }
+
+    async fn get_registration_events(&self, session_id: Uuid) -> StorageResult<Vec<RegistrationEvent>> {
+        sqlx::query_as!(
+            RegistrationEvent,
+            r#"
+            SELECT id, registration_id, session_id, user_id, kind as "kind: RegistrationEventKind",
+                   status as "status: RegistrationStatus", actor as "actor: RegistrationActor", occurred_at
+            FROM registration_events
+            WHERE session_id = $1
+            ORDER BY seq
+            "#,
+            session_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(StorageError::from)
+    }
+
+    async fn record_attendance(&self, attendance: &Attendance) -> StorageResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO attendance (registration_id, session_id, user_id, status, recorded_by, recorded_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (registration_id) DO UPDATE
+            SET status = EXCLUDED.status, recorded_by = EXCLUDED.recorded_by, recorded_at = EXCLUDED.recorded_at
+            "#,
+            attendance.registration_id,
+            attendance.session_id,
+            attendance.user_id,
+            attendance.status as AttendanceStatus,
+            attendance.recorded_by,
+            attendance.recorded_at
+        )
         .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_session_attendance(&self, session_id: Uuid) -> StorageResult<Vec<Attendance>> {
+        sqlx::query_as!(
+            Attendance,
+            r#"
+            SELECT registration_id, session_id, user_id, status as "status: AttendanceStatus", recorded_by, recorded_at
+            FROM attendance
+            WHERE session_id = $1
+            ORDER BY recorded_at
+            "#,
+            session_id
+        )
+        .fetch_all(&self.pool)
         .await
-        .map(|result| result.rows_affected() > 0)
-        .unwrap_or(false)
+        .map_err(StorageError::from)
+    }
+
+    async fn get_user_attendance(&self, user_id: Uuid) -> StorageResult<Vec<Attendance>> {
+        sqlx::query_as!(
+            Attendance,
+            r#"
+            SELECT registration_id, session_id, user_id, status as "status: AttendanceStatus", recorded_by, recorded_at
+            FROM attendance
+            WHERE user_id = $1
+            ORDER BY recorded_at
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(StorageError::from)
     }
 
-    async fn get_venue(&self, id: Uuid) -> Option<Venue> {
+    async fn get_venue(&self, id: Uuid) -> StorageResult<Option<Venue>> {
         sqlx::query_as!(
-            Venue,
-            "SELECT id, name, address FROM venues WHERE id = $1",
+            VenueRow,
+            "SELECT id, name, address, timezone FROM venues WHERE id = $1",
             id
         )
         .fetch_optional(&self.pool)
-        .await
-        .ok()
-        .flatten()
+        .await?
+        .map(Venue::try_from)
+        .transpose()
     }
 
-    async fn list_venues(&self) -> Vec<Venue> {
-        sqlx::query_as!(Venue, "SELECT id, name, address FROM venues ORDER BY name")
+    async fn list_venues(&self) -> StorageResult<Vec<Venue>> {
+        sqlx::query_as!(VenueRow, "SELECT id, name, address, timezone FROM venues ORDER BY name")
             .fetch_all(&self.pool)
-            .await
-            .unwrap_or_default()
+            .await?
+            .into_iter()
+            .map(Venue::try_from)
+            .collect()
     }
 
-    async fn create_venue(&self, venue: Venue) {
-        let _ = sqlx::query!(
-            "INSERT INTO venues (id, name, address) VALUES ($1, $2, $3)",
+    async fn create_venue(&self, venue: Venue) -> StorageResult<()> {
+        sqlx::query!(
+            "INSERT INTO venues (id, name, address, timezone) VALUES ($1, $2, $3, $4)",
             venue.id,
             venue.name,
-            venue.address
+            venue.address,
+            venue.timezone.name()
         )
         .execute(&self.pool)
-        .await;
+        .await?;
+        Ok(())
+    }
+
+    async fn get_session_template(&self, id: Uuid) -> StorageResult<Option<SessionTemplate>> {
+        sqlx::query_as!(
+            TemplateRow,
+            r#"
+            SELECT id, session_type as "session_type: SessionType", weekday, start_time, duration_minutes, venue_id, skill_level as "skill_level: SkillLevel", max_players, max_substitutes, exception_dates, created_at
+            FROM session_templates
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .map(SessionTemplate::try_from)
+        .transpose()
+    }
+
+    async fn list_session_templates(&self) -> StorageResult<Vec<SessionTemplate>> {
+        sqlx::query_as!(
+            TemplateRow,
+            r#"
+            SELECT id, session_type as "session_type: SessionType", weekday, start_time, duration_minutes, venue_id, skill_level as "skill_level: SkillLevel", max_players, max_substitutes, exception_dates, created_at
+            FROM session_templates
+            ORDER BY weekday, start_time
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(SessionTemplate::try_from)
+        .collect()
+    }
+
+    async fn create_session_template(&self, template: SessionTemplate) -> StorageResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO session_templates (id, session_type, weekday, start_time, duration_minutes, venue_id, skill_level, max_players, max_substitutes, exception_dates, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            "#,
+            template.id,
+            template.session_type as SessionType,
+            template.weekday.num_days_from_monday() as i16,
+            template.start_time,
+            template.duration_minutes,
+            template.venue_id,
+            template.skill_level as _,
+            template.max_players,
+            template.max_substitutes,
+            &template.exception_dates,
+            template.created_at
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn update_session_template(&self, template: SessionTemplate) -> StorageResult<bool> {
+        sqlx::query!(
+            r#"
+            UPDATE session_templates
+            SET session_type = $2, weekday = $3, start_time = $4, duration_minutes = $5, venue_id = $6,
+                skill_level = $7, max_players = $8, max_substitutes = $9, exception_dates = $10
+            WHERE id = $1
+            "#,
+            template.id,
+            template.session_type as SessionType,
+            template.weekday.num_days_from_monday() as i16,
+            template.start_time,
+            template.duration_minutes,
+            template.venue_id,
+            template.skill_level as _,
+            template.max_players,
+            template.max_substitutes,
+            &template.exception_dates
+        )
+        .execute(&self.pool)
+        .await
+        .map(|result| result.rows_affected() > 0)
+        .map_err(StorageError::from)
+    }
+
+    async fn delete_session_template(&self, id: Uuid) -> StorageResult<bool> {
+        sqlx::query!("DELETE FROM session_templates WHERE id = $1", id)
+            .execute(&self.pool)
+            .await
+            .map(|result| result.rows_affected() > 0)
+            .map_err(StorageError::from)
+    }
+
+    async fn get_conversation_state(&self, phone: &str) -> StorageResult<Option<ConversationState>> {
+        sqlx::query_as!(
+            ConversationState,
+            r#"
+            SELECT phone_number, menu as "menu: ConversationMenu",
+                   session_type as "session_type: SessionType",
+                   session_ids, expires_at, updated_at
+            FROM conversation_states
+            WHERE phone_number = $1 AND expires_at > NOW()
+            "#,
+            phone
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(StorageError::from)
+    }
+
+    async fn save_conversation_state(&self, state: ConversationState) -> StorageResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO conversation_states (phone_number, menu, session_type, session_ids,
+                                             expires_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (phone_number) DO UPDATE
+            SET menu = EXCLUDED.menu,
+                session_type = EXCLUDED.session_type,
+                session_ids = EXCLUDED.session_ids,
+                expires_at = EXCLUDED.expires_at,
+                updated_at = EXCLUDED.updated_at
+            "#,
+            state.phone_number,
+            state.menu as ConversationMenu,
+            state.session_type as Option<SessionType>,
+            &state.session_ids,
+            state.expires_at,
+            state.updated_at
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_conversation_state(&self, phone: &str) -> StorageResult<bool> {
+        sqlx::query!(
+            "DELETE FROM conversation_states WHERE phone_number = $1",
+            phone
+        )
+        .execute(&self.pool)
+        .await
+        .map(|result| result.rows_affected() > 0)
+        .map_err(StorageError::from)
     }
 }
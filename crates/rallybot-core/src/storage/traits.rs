@@ -1,97 +1,300 @@
 use crate::{
-    models::{Session, SessionType, Venue},
-    registration::Registration,
-    user::User,
+    application::{Application, ApplicationStatus, ApplicationStatusChange},
+    conversation::ConversationState,
+    models::{Session, SessionQuery, SessionSummary, Venue},
+    attendance::Attendance,
+    cancellation::LateCancellation,
+    registration::{Registration, RegistrationActor, RegistrationAttempt, RegistrationEvent, SessionEdit, Withdrawal},
+    template::SessionTemplate,
+    user::{User, UserStatus, UserStatusChange},
 };
+use super::StorageResult;
+use chrono::{DateTime, Utc};
 use std::sync::Arc;
 use uuid::Uuid;
 
 #[async_trait::async_trait]
 pub trait Storage: Send + Sync {
     // Session operations
-    async fn get_session(&self, id: Uuid) -> Option<Session>;
-    async fn list_sessions(&self, session_type: Option<SessionType>) -> Vec<Session>;
-    async fn create_session(&self, session: Session);
+    async fn get_session(&self, id: Uuid) -> StorageResult<Option<Session>>;
+    /// Sessions matching the query, sorted and paged as it asks
+    async fn list_sessions(&self, query: &SessionQuery) -> StorageResult<Vec<Session>>;
+    /// [`list_sessions`](Self::list_sessions) with each session's venue and
+    /// sign-ups. Sessions whose venue is missing are left out.
+    async fn list_session_summaries(&self, query: &SessionQuery) -> StorageResult<Vec<SessionSummary>>;
+    async fn create_session(&self, session: Session) -> StorageResult<()>;
+    /// Move a scheduled session to cancelled. Returns the updated session, or
+    /// `None` if there is no scheduled session with that id.
+    async fn cancel_session(&self, id: Uuid, reason: &str) -> StorageResult<Option<Session>>;
+    /// Save the type, time, duration, venue, level and capacity of a scheduled
+    /// session, checked against who is signed up, and promote substitutes into
+    /// any places it adds, atomically
+    async fn update_session(&self, session: Session) -> StorageResult<SessionEdit>;
     
     // User operations
-    async fn get_user(&self, id: Uuid) -> Option<User>;
-    async fn get_user_by_phone(&self, phone: &str) -> Option<User>;
-    async fn create_user(&self, user: User);
+    async fn get_user(&self, id: Uuid) -> StorageResult<Option<User>>;
+    async fn get_user_by_phone(&self, phone: &str) -> StorageResult<Option<User>>;
+    async fn create_user(&self, user: User) -> StorageResult<()>;
+    /// Users with this status, oldest first
+    async fn list_users_by_status(&self, status: UserStatus) -> StorageResult<Vec<User>>;
+    /// Move the user to `change.to_status` and record the change, atomically.
+    /// Returns `false` if the user is no longer in `change.from_status`.
+    async fn change_user_status(&self, change: &UserStatusChange) -> StorageResult<bool>;
+    /// A user's status changes, oldest first
+    async fn get_user_status_changes(&self, user_id: Uuid) -> StorageResult<Vec<UserStatusChange>>;
     
+    // Application operations
+    async fn get_application(&self, id: Uuid) -> StorageResult<Option<Application>>;
+    /// Applications with this phone number or email
+    async fn find_applications(&self, phone: &str, email: &str) -> StorageResult<Vec<Application>>;
+    /// Applications with this status, oldest first
+    async fn list_applications_by_status(&self, status: ApplicationStatus) -> StorageResult<Vec<Application>>;
+    async fn create_application(&self, application: Application) -> StorageResult<()>;
+    /// Move the application to `change.to_status`, record the change and create
+    /// `member` if given, atomically. Returns `false` if the application is no
+    /// longer in `change.from_status`.
+    async fn decide_application(&self, change: &ApplicationStatusChange, member: Option<&User>) -> StorageResult<bool>;
+    /// An application's decisions, oldest first
+    async fn get_application_status_changes(&self, application_id: Uuid) -> StorageResult<Vec<ApplicationStatusChange>>;
+
     // Registration operations
-    async fn get_registrations(&self, session_id: Uuid) -> Vec<Registration>;
-    async fn get_user_registrations(&self, user_id: Uuid) -> Vec<Registration>;
-    async fn create_registration(&self, registration: Registration);
-    async fn registration_exists(&self, session_id: Uuid, user_id: Uuid) -> bool;
-    async fn delete_registration(&self, session_id: Uuid, user_id: Uuid) -> bool;
-    async fn update_registration(&self, registration: Registration) -> bool;
+    async fn get_registrations(&self, session_id: Uuid) -> StorageResult<Vec<Registration>>;
+    async fn get_user_registrations(&self, user_id: Uuid) -> StorageResult<Vec<Registration>>;
+    /// Every change made through the registration operations below is also
+    /// appended to the session's registration log, in the same step. The
+    /// direct create, update and delete are logged as the organiser's.
+    async fn create_registration(&self, registration: Registration) -> StorageResult<()>;
+    async fn registration_exists(&self, session_id: Uuid, user_id: Uuid) -> StorageResult<bool>;
+    async fn delete_registration(&self, session_id: Uuid, user_id: Uuid) -> StorageResult<bool>;
+    async fn update_registration(&self, registration: Registration) -> StorageResult<bool>;
+    /// Sign the user up as confirmed or substitute depending on the session's
+    /// capacity. The check and the insert happen atomically.
+    async fn register_within_capacity(&self, session_id: Uuid, user_id: Uuid, actor: RegistrationActor) -> StorageResult<RegistrationAttempt>;
+    /// Remove the user's registration and promote the oldest substitutes into any
+    /// freed places, atomically. Promoted players must confirm by `confirm_by`
+    /// when it is set. A `late` cancellation of a confirmed place only goes
+    /// ahead if there is a substitute to take it, and is then recorded.
+    async fn unregister_and_promote(&self, session_id: Uuid, user_id: Uuid, confirm_by: Option<DateTime<Utc>>, late: Option<&LateCancellation>, actor: RegistrationActor) -> StorageResult<Withdrawal>;
+    /// [`unregister_and_promote`](Self::unregister_and_promote), but only if
+    /// the registration's confirmation deadline had passed by `now`
+    async fn expire_and_promote(&self, session_id: Uuid, user_id: Uuid, now: DateTime<Utc>, confirm_by: Option<DateTime<Utc>>) -> StorageResult<Option<Vec<Registration>>>;
+    /// Clear the deadline of a promotion still open at `now`. Returns the
+    /// registration, or `None` if there was nothing to confirm.
+    async fn confirm_registration(&self, session_id: Uuid, user_id: Uuid, now: DateTime<Utc>) -> StorageResult<Option<Registration>>;
+    /// Promotions whose deadline passed by `now`, on scheduled sessions that
+    /// haven't started
+    async fn list_lapsed_promotions(&self, now: DateTime<Utc>) -> StorageResult<Vec<Registration>>;
+    /// The user's late cancellations, oldest first
+    async fn list_late_cancellations(&self, user_id: Uuid) -> StorageResult<Vec<LateCancellation>>;
+    /// The session's registration log, in the order things happened
+    async fn get_registration_events(&self, session_id: Uuid) -> StorageResult<Vec<RegistrationEvent>>;
+
+    // Attendance operations
+    /// Insert or replace the attendance of `attendance.registration_id`
+    async fn record_attendance(&self, attendance: &Attendance) -> StorageResult<()>;
+    async fn get_session_attendance(&self, session_id: Uuid) -> StorageResult<Vec<Attendance>>;
+    async fn get_user_attendance(&self, user_id: Uuid) -> StorageResult<Vec<Attendance>>;
     
     // Venue operations
-    async fn get_venue(&self, id: Uuid) -> Option<Venue>;
-    async fn list_venues(&self) -> Vec<Venue>;
-    async fn create_venue(&self, venue: Venue);
+    async fn get_venue(&self, id: Uuid) -> StorageResult<Option<Venue>>;
+    async fn list_venues(&self) -> StorageResult<Vec<Venue>>;
+    async fn create_venue(&self, venue: Venue) -> StorageResult<()>;
+
+    // Session template operations
+    async fn get_session_template(&self, id: Uuid) -> StorageResult<Option<SessionTemplate>>;
+    async fn list_session_templates(&self) -> StorageResult<Vec<SessionTemplate>>;
+    async fn create_session_template(&self, template: SessionTemplate) -> StorageResult<()>;
+    /// Overwrite a template. Returns `false` if there is no template with that id.
+    async fn update_session_template(&self, template: SessionTemplate) -> StorageResult<bool>;
+    /// Sessions already generated from the template are kept but unlinked
+    async fn delete_session_template(&self, id: Uuid) -> StorageResult<bool>;
+
+    // Conversation state operations
+    async fn get_conversation_state(&self, phone: &str) -> StorageResult<Option<ConversationState>>;
+    async fn save_conversation_state(&self, state: ConversationState) -> StorageResult<()>;
+    async fn delete_conversation_state(&self, phone: &str) -> StorageResult<bool>;
 }
 
 // Implement Storage for Arc<S> where S: Storage
 #[async_trait::async_trait]
 impl<S: Storage> Storage for Arc<S> {
-    async fn get_session(&self, id: Uuid) -> Option<Session> {
+    async fn get_session(&self, id: Uuid) -> StorageResult<Option<Session>> {
         (**self).get_session(id).await
     }
 
-    async fn list_sessions(&self, session_type: Option<SessionType>) -> Vec<Session> {
-        (**self).list_sessions(session_type).await
+    async fn list_sessions(&self, query: &SessionQuery) -> StorageResult<Vec<Session>> {
+        (**self).list_sessions(query).await
     }
 
-    async fn create_session(&self, session: Session) {
+    async fn list_session_summaries(&self, query: &SessionQuery) -> StorageResult<Vec<SessionSummary>> {
+        (**self).list_session_summaries(query).await
+    }
+
+    async fn create_session(&self, session: Session) -> StorageResult<()> {
         (**self).create_session(session).await
     }
 
-    async fn get_user(&self, id: Uuid) -> Option<User> {
+    async fn cancel_session(&self, id: Uuid, reason: &str) -> StorageResult<Option<Session>> {
+        (**self).cancel_session(id, reason).await
+    }
+
+    async fn update_session(&self, session: Session) -> StorageResult<SessionEdit> {
+        (**self).update_session(session).await
+    }
+
+    async fn get_user(&self, id: Uuid) -> StorageResult<Option<User>> {
         (**self).get_user(id).await
     }
 
-    async fn get_user_by_phone(&self, phone: &str) -> Option<User> {
+    async fn get_user_by_phone(&self, phone: &str) -> StorageResult<Option<User>> {
         (**self).get_user_by_phone(phone).await
     }
 
-    async fn create_user(&self, user: User) {
+    async fn create_user(&self, user: User) -> StorageResult<()> {
         (**self).create_user(user).await
     }
 
-    async fn get_registrations(&self, session_id: Uuid) -> Vec<Registration> {
+    async fn list_users_by_status(&self, status: UserStatus) -> StorageResult<Vec<User>> {
+        (**self).list_users_by_status(status).await
+    }
+
+    async fn change_user_status(&self, change: &UserStatusChange) -> StorageResult<bool> {
+        (**self).change_user_status(change).await
+    }
+
+    async fn get_user_status_changes(&self, user_id: Uuid) -> StorageResult<Vec<UserStatusChange>> {
+        (**self).get_user_status_changes(user_id).await
+    }
+
+    async fn get_application(&self, id: Uuid) -> StorageResult<Option<Application>> {
+        (**self).get_application(id).await
+    }
+
+    async fn find_applications(&self, phone: &str, email: &str) -> StorageResult<Vec<Application>> {
+        (**self).find_applications(phone, email).await
+    }
+
+    async fn list_applications_by_status(&self, status: ApplicationStatus) -> StorageResult<Vec<Application>> {
+        (**self).list_applications_by_status(status).await
+    }
+
+    async fn create_application(&self, application: Application) -> StorageResult<()> {
+        (**self).create_application(application).await
+    }
+
+    async fn decide_application(&self, change: &ApplicationStatusChange, member: Option<&User>) -> StorageResult<bool> {
+        (**self).decide_application(change, member).await
+    }
+
+    async fn get_application_status_changes(&self, application_id: Uuid) -> StorageResult<Vec<ApplicationStatusChange>> {
+        (**self).get_application_status_changes(application_id).await
+    }
+
+    async fn get_registrations(&self, session_id: Uuid) -> StorageResult<Vec<Registration>> {
         (**self).get_registrations(session_id).await
     }
 
-    async fn get_user_registrations(&self, user_id: Uuid) -> Vec<Registration> {
+    async fn get_user_registrations(&self, user_id: Uuid) -> StorageResult<Vec<Registration>> {
         (**self).get_user_registrations(user_id).await
     }
 
-    async fn create_registration(&self, registration: Registration) {
+    async fn create_registration(&self, registration: Registration) -> StorageResult<()> {
         (**self).create_registration(registration).await
     }
 
-    async fn registration_exists(&self, session_id: Uuid, user_id: Uuid) -> bool {
+    async fn registration_exists(&self, session_id: Uuid, user_id: Uuid) -> StorageResult<bool> {
         (**self).registration_exists(session_id, user_id).await
     }
 
-    async fn delete_registration(&self, session_id: Uuid, user_id: Uuid) -> bool {
+    async fn delete_registration(&self, session_id: Uuid, user_id: Uuid) -> StorageResult<bool> {
         (**self).delete_registration(session_id, user_id).await
     }
 
-    async fn update_registration(&self, registration: Registration) -> bool {
+    async fn update_registration(&self, registration: Registration) -> StorageResult<bool> {
         (**self).update_registration(registration).await
     }
 
-    async fn get_venue(&self, id: Uuid) -> Option<Venue> {
+    async fn register_within_capacity(&self, session_id: Uuid, user_id: Uuid, actor: RegistrationActor) -> StorageResult<RegistrationAttempt> {
+        (**self).register_within_capacity(session_id, user_id, actor).await
+    }
+
+    async fn unregister_and_promote(&self, session_id: Uuid, user_id: Uuid, confirm_by: Option<DateTime<Utc>>, late: Option<&LateCancellation>, actor: RegistrationActor) -> StorageResult<Withdrawal> {
+        (**self).unregister_and_promote(session_id, user_id, confirm_by, late, actor).await
+    }
+
+    async fn list_late_cancellations(&self, user_id: Uuid) -> StorageResult<Vec<LateCancellation>> {
+        (**self).list_late_cancellations(user_id).await
+    }
+
+    async fn get_registration_events(&self, session_id: Uuid) -> StorageResult<Vec<RegistrationEvent>> {
+        (**self).get_registration_events(session_id).await
+    }
+
+    async fn record_attendance(&self, attendance: &Attendance) -> StorageResult<()> {
+        (**self).record_attendance(attendance).await
+    }
+
+    async fn get_session_attendance(&self, session_id: Uuid) -> StorageResult<Vec<Attendance>> {
+        (**self).get_session_attendance(session_id).await
+    }
+
+    async fn get_user_attendance(&self, user_id: Uuid) -> StorageResult<Vec<Attendance>> {
+        (**self).get_user_attendance(user_id).await
+    }
+
+    async fn expire_and_promote(&self, session_id: Uuid, user_id: Uuid, now: DateTime<Utc>, confirm_by: Option<DateTime<Utc>>) -> StorageResult<Option<Vec<Registration>>> {
+        (**self).expire_and_promote(session_id, user_id, now, confirm_by).await
+    }
+
+    async fn confirm_registration(&self, session_id: Uuid, user_id: Uuid, now: DateTime<Utc>) -> StorageResult<Option<Registration>> {
+        (**self).confirm_registration(session_id, user_id, now).await
+    }
+
+    async fn list_lapsed_promotions(&self, now: DateTime<Utc>) -> StorageResult<Vec<Registration>> {
+        (**self).list_lapsed_promotions(now).await
+    }
+
+    async fn get_venue(&self, id: Uuid) -> StorageResult<Option<Venue>> {
         (**self).get_venue(id).await
     }
 
-    async fn list_venues(&self) -> Vec<Venue> {
+    async fn list_venues(&self) -> StorageResult<Vec<Venue>> {
         (**self).list_venues().await
     }
 
-    async fn create_venue(&self, venue: Venue) {
+    async fn create_venue(&self, venue: Venue) -> StorageResult<()> {
         (**self).create_venue(venue).await
     }
+
+    async fn get_session_template(&self, id: Uuid) -> StorageResult<Option<SessionTemplate>> {
+        (**self).get_session_template(id).await
+    }
+
+    async fn list_session_templates(&self) -> StorageResult<Vec<SessionTemplate>> {
+        (**self).list_session_templates().await
+    }
+
+    async fn create_session_template(&self, template: SessionTemplate) -> StorageResult<()> {
+        (**self).create_session_template(template).await
+    }
+
+    async fn update_session_template(&self, template: SessionTemplate) -> StorageResult<bool> {
+        (**self).update_session_template(template).await
+    }
+
+    async fn delete_session_template(&self, id: Uuid) -> StorageResult<bool> {
+        (**self).delete_session_template(id).await
+    }
+
+    async fn get_conversation_state(&self, phone: &str) -> StorageResult<Option<ConversationState>> {
+        (**self).get_conversation_state(phone).await
+    }
+
+    async fn save_conversation_state(&self, state: ConversationState) -> StorageResult<()> {
+        (**self).save_conversation_state(state).await
+    }
+
+    async fn delete_conversation_state(&self, phone: &str) -> StorageResult<bool> {
+        (**self).delete_conversation_state(phone).await
+    }
 }
\ No newline at end of file
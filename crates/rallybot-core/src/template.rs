@@ -0,0 +1,255 @@
+use crate::{
+    calendar,
+    models::{Session, SessionType},
+    user::SkillLevel,
+};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A session that repeats every week on the same weekday and time, e.g. the
+/// Tuesday 19:00 Social. Concrete sessions are generated from it a few weeks
+/// at a time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionTemplate {
+    pub id: Uuid,
+    pub session_type: SessionType,
+    pub weekday: Weekday,
+    /// Start time on the venue's wall clock, so the session stays at 19:00
+    /// when the clocks change
+    pub start_time: NaiveTime,
+    pub duration_minutes: i32,
+    pub venue_id: Uuid,
+    pub skill_level: Option<SkillLevel>,
+    pub max_players: i32,
+    pub max_substitutes: Option<i32>,
+    /// Weeks the session does not run, e.g. public holidays
+    pub exception_dates: Vec<NaiveDate>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl SessionTemplate {
+    /// Checked against the same rules as a single session
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        session_type: SessionType,
+        weekday: Weekday,
+        start_time: NaiveTime,
+        duration_minutes: i32,
+        venue_id: Uuid,
+        skill_level: Option<SkillLevel>,
+        max_players: i32,
+        max_substitutes: Option<i32>,
+    ) -> Result<Self, &'static str> {
+        Session::new(
+            session_type,
+            Utc::now(),
+            duration_minutes,
+            venue_id,
+            skill_level,
+            max_players,
+            max_substitutes,
+        )?;
+
+        Ok(Self {
+            id: Uuid::new_v4(),
+            session_type,
+            weekday,
+            start_time,
+            duration_minutes,
+            venue_id,
+            skill_level,
+            max_players,
+            max_substitutes,
+            exception_dates: Vec::new(),
+            created_at: Utc::now(),
+        })
+    }
+
+    /// Local dates and start times of the sessions due from `from` up to
+    /// `weeks` weeks later, skipping exception dates. `timezone` is the venue's.
+    pub fn occurrences(
+        &self,
+        from: DateTime<Utc>,
+        weeks: u32,
+        timezone: Tz,
+    ) -> Vec<(NaiveDate, DateTime<Utc>)> {
+        let until = from + Duration::weeks(weeks as i64);
+        let today = from.with_timezone(&timezone).date_naive();
+        let days_ahead = (self.weekday.num_days_from_monday() + 7
+            - today.weekday().num_days_from_monday())
+            % 7;
+        let mut date = today + Duration::days(days_ahead as i64);
+
+        let mut starts = Vec::new();
+        loop {
+            let start = calendar::at_local(timezone, date.and_time(self.start_time));
+            if start >= until {
+                break;
+            }
+            // Today's session may already have started
+            if start >= from && !self.exception_dates.contains(&date) {
+                starts.push((date, start));
+            }
+            date += Duration::weeks(1);
+        }
+        starts
+    }
+
+    /// The concrete session for the week of `date`, starting at `datetime`
+    pub fn session_at(
+        &self,
+        date: NaiveDate,
+        datetime: DateTime<Utc>,
+    ) -> Result<Session, &'static str> {
+        let mut session = Session::new(
+            self.session_type,
+            datetime,
+            self.duration_minutes,
+            self.venue_id,
+            self.skill_level,
+            self.max_players,
+            self.max_substitutes,
+        )?;
+        session.template_id = Some(self.id);
+        session.occurrence_date = Some(date);
+        Ok(session)
+    }
+
+    /// Skip the session on `date`. Returns `false` if it was already skipped.
+    pub fn add_exception(&mut self, date: NaiveDate) -> bool {
+        if self.exception_dates.contains(&date) {
+            return false;
+        }
+        self.exception_dates.push(date);
+        self.exception_dates.sort();
+        true
+    }
+
+    pub fn remove_exception(&mut self, date: NaiveDate) -> bool {
+        let before = self.exception_dates.len();
+        self.exception_dates.retain(|d| *d != date);
+        self.exception_dates.len() < before
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const LISBON: Tz = Tz::Europe__Lisbon;
+
+    fn tuesday_social() -> SessionTemplate {
+        SessionTemplate::new(
+            SessionType::Social,
+            Weekday::Tue,
+            NaiveTime::from_hms_opt(19, 0, 0).unwrap(),
+            90,
+            Uuid::new_v4(),
+            Some(SkillLevel::Intermediate),
+            8,
+            Some(2),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn template_follows_session_rules() {
+        let result = SessionTemplate::new(
+            SessionType::Mixed,
+            Weekday::Sat,
+            NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+            90,
+            Uuid::new_v4(),
+            Some(SkillLevel::Intermediate),
+            4,
+            None,
+        );
+        assert_eq!(result.unwrap_err(), "Mixed sessions cannot have a skill level");
+    }
+
+    fn starts(occurrences: &[(NaiveDate, DateTime<Utc>)]) -> Vec<DateTime<Utc>> {
+        occurrences.iter().map(|(_, start)| *start).collect()
+    }
+
+    #[test]
+    fn occurrences_fall_on_the_weekday() {
+        // Monday 1 September 2025
+        let from = Utc.with_ymd_and_hms(2025, 9, 1, 12, 0, 0).unwrap();
+        let occurrences = tuesday_social().occurrences(from, 3, Tz::UTC);
+
+        assert_eq!(occurrences[0].0, NaiveDate::from_ymd_opt(2025, 9, 2).unwrap());
+        assert_eq!(
+            starts(&occurrences),
+            vec![
+                Utc.with_ymd_and_hms(2025, 9, 2, 19, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2025, 9, 9, 19, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2025, 9, 16, 19, 0, 0).unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn occurrences_keep_local_time_across_dst() {
+        // Lisbon clocks go back on Sunday 26 October 2025
+        let from = Utc.with_ymd_and_hms(2025, 10, 20, 12, 0, 0).unwrap();
+        let occurrences = tuesday_social().occurrences(from, 2, LISBON);
+
+        assert_eq!(
+            starts(&occurrences),
+            vec![
+                Utc.with_ymd_and_hms(2025, 10, 21, 18, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2025, 10, 28, 19, 0, 0).unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn occurrences_skip_past_starts_and_exceptions() {
+        let mut template = tuesday_social();
+        template.add_exception(NaiveDate::from_ymd_opt(2025, 9, 9).unwrap());
+
+        // Tuesday 2 September, after the session has started
+        let from = Utc.with_ymd_and_hms(2025, 9, 2, 20, 0, 0).unwrap();
+        let occurrences = template.occurrences(from, 3, Tz::UTC);
+
+        assert_eq!(
+            starts(&occurrences),
+            vec![
+                Utc.with_ymd_and_hms(2025, 9, 16, 19, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2025, 9, 23, 19, 0, 0).unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn generated_session_copies_the_template() {
+        let template = tuesday_social();
+        let date = NaiveDate::from_ymd_opt(2025, 9, 2).unwrap();
+        let start = Utc.with_ymd_and_hms(2025, 9, 2, 19, 0, 0).unwrap();
+        let session = template.session_at(date, start).unwrap();
+
+        assert_eq!(session.datetime, start);
+        assert_eq!(session.occurrence_date, Some(date));
+        assert_eq!(session.max_players, 8);
+        assert_eq!(session.max_substitutes, Some(2));
+        assert_eq!(session.template_id, Some(template.id));
+    }
+
+    #[test]
+    fn exceptions_are_kept_sorted_and_unique() {
+        let mut template = tuesday_social();
+        let later = NaiveDate::from_ymd_opt(2025, 12, 30).unwrap();
+        let earlier = NaiveDate::from_ymd_opt(2025, 12, 23).unwrap();
+
+        assert!(template.add_exception(later));
+        assert!(template.add_exception(earlier));
+        assert!(!template.add_exception(later));
+        assert_eq!(template.exception_dates, vec![earlier, later]);
+
+        assert!(template.remove_exception(later));
+        assert!(!template.remove_exception(later));
+    }
+}
@@ -1,9 +1,12 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
 use uuid::Uuid;
 
-/// Player skill level in padel
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type)]
+/// Player skill level in padel, ordered from Beginner to Elite
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, sqlx::Type,
+)]
 #[sqlx(type_name = "skill_level")]
 pub enum SkillLevel {
     /// A - Beginner: New to padel, learning basic shots, positioning, and rules
@@ -40,6 +43,123 @@ pub enum SkillLevel {
     Elite,
 }
 
+impl SkillLevel {
+    /// Every level, lowest first
+    pub const ALL: [SkillLevel; 8] = [
+        SkillLevel::Beginner,
+        SkillLevel::LowIntermediate,
+        SkillLevel::Intermediate,
+        SkillLevel::UpperIntermediate,
+        SkillLevel::Advanced,
+        SkillLevel::HighAdvanced,
+        SkillLevel::Expert,
+        SkillLevel::Elite,
+    ];
+
+    /// The single-letter code used on the wire and in the database
+    pub fn letter(self) -> char {
+        match self {
+            SkillLevel::Beginner => 'A',
+            SkillLevel::LowIntermediate => 'B',
+            SkillLevel::Intermediate => 'C',
+            SkillLevel::UpperIntermediate => 'D',
+            SkillLevel::Advanced => 'E',
+            SkillLevel::HighAdvanced => 'F',
+            SkillLevel::Expert => 'G',
+            SkillLevel::Elite => 'H',
+        }
+    }
+
+    /// Label shown to players, e.g. "Upper-Intermediate"
+    pub fn name(self) -> &'static str {
+        match self {
+            SkillLevel::Beginner => "Beginner",
+            SkillLevel::LowIntermediate => "Low Intermediate",
+            SkillLevel::Intermediate => "Intermediate",
+            SkillLevel::UpperIntermediate => "Upper-Intermediate",
+            SkillLevel::Advanced => "Advanced",
+            SkillLevel::HighAdvanced => "High Advanced",
+            SkillLevel::Expert => "Expert",
+            SkillLevel::Elite => "Elite",
+        }
+    }
+
+    /// Men's/women's rating equivalent, e.g. "M4/F4". Beginners have none.
+    pub fn rating(self) -> Option<&'static str> {
+        match self {
+            SkillLevel::Beginner => None,
+            SkillLevel::LowIntermediate => Some("M6/F6"),
+            SkillLevel::Intermediate => Some("M5/F5"),
+            SkillLevel::UpperIntermediate => Some("M4/F4"),
+            SkillLevel::Advanced => Some("M4+/F4+"),
+            SkillLevel::HighAdvanced => Some("M3/F3"),
+            SkillLevel::Expert => Some("M2/F2"),
+            SkillLevel::Elite => Some("M1/F1"),
+        }
+    }
+
+    /// Position on the scale, 0 for Beginner up to 7 for Elite
+    pub fn rank(self) -> u8 {
+        self as u8
+    }
+
+    /// How many levels apart two levels are
+    pub fn distance(self, other: SkillLevel) -> u8 {
+        self.rank().abs_diff(other.rank())
+    }
+
+    /// The level `steps` above (positive) or below (negative) this one,
+    /// clamped to the ends of the scale
+    pub fn offset(self, steps: i32) -> SkillLevel {
+        let rank = (self.rank() as i32 + steps).clamp(0, Self::ALL.len() as i32 - 1);
+        Self::ALL[rank as usize]
+    }
+}
+
+impl fmt::Display for SkillLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseSkillLevelError(pub String);
+
+impl fmt::Display for ParseSkillLevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown skill level: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseSkillLevelError {}
+
+impl FromStr for SkillLevel {
+    type Err = ParseSkillLevelError;
+
+    /// Accepts a letter ("D"), a name ("Upper-Intermediate", "upper intermediate")
+    /// or a rating ("M4/F4", "M4", "F4"), ignoring case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalize = |text: &str| -> String {
+            text.chars()
+                .filter(|c| !c.is_whitespace() && *c != '-' && *c != '_')
+                .flat_map(char::to_uppercase)
+                .collect()
+        };
+        let input = normalize(s);
+
+        SkillLevel::ALL
+            .into_iter()
+            .find(|level| {
+                input == level.letter().to_string()
+                    || input == normalize(level.name())
+                    || level.rating().is_some_and(|rating| {
+                        input == rating || rating.split('/').any(|part| input == part)
+                    })
+            })
+            .ok_or_else(|| ParseSkillLevelError(s.to_string()))
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, sqlx::Type)]
 #[serde(rename_all = "lowercase")]
 #[sqlx(type_name = "gender")]
@@ -86,7 +206,73 @@ pub enum LookingFor {
     SocialConnections,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Where a member is in the approval process. Only approved members can sign
+/// up for sessions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "user_status")]
+pub enum UserStatus {
+    /// Applied and waiting for an admin
+    #[default]
+    #[sqlx(rename = "Pending")]
+    Pending,
+    #[sqlx(rename = "Approved")]
+    Approved,
+    #[sqlx(rename = "Rejected")]
+    Rejected,
+    /// Approved once, access taken away for now
+    #[sqlx(rename = "Suspended")]
+    Suspended,
+}
+
+impl UserStatus {
+    /// Pending, rejected and suspended users can be approved; only pending
+    /// ones rejected and only approved ones suspended
+    pub fn can_become(self, to: UserStatus) -> bool {
+        matches!(
+            (self, to),
+            (
+                UserStatus::Pending | UserStatus::Rejected | UserStatus::Suspended,
+                UserStatus::Approved
+            ) | (UserStatus::Pending, UserStatus::Rejected)
+                | (UserStatus::Approved, UserStatus::Suspended)
+        )
+    }
+}
+
+/// Audit record of an admin moving a user from one status to another
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserStatusChange {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub from_status: UserStatus,
+    pub to_status: UserStatus,
+    /// Who made the change, e.g. the admin's email
+    pub changed_by: String,
+    pub reason: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
+
+impl UserStatusChange {
+    pub fn new(
+        user: &User,
+        to_status: UserStatus,
+        changed_by: String,
+        reason: Option<String>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            user_id: user.id,
+            from_status: user.status,
+            to_status,
+            changed_by,
+            reason,
+            changed_at: Utc::now(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
 pub struct User {
     pub id: Uuid,
     pub first_name: String,
@@ -104,11 +290,41 @@ pub struct User {
     pub preferred_side: PreferredSide,
     pub play_frequency: PlayFrequency,
     pub looking_for: Vec<LookingFor>,
-    pub is_approved: bool,
+    pub status: UserStatus,
     pub created_at: DateTime<Utc>,
 }
 
+// Serialised by hand to keep `is_approved`, which API clients read from
+// before statuses replaced it
+impl Serialize for User {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("User", 19)?;
+        state.serialize_field("id", &self.id)?;
+        state.serialize_field("first_name", &self.first_name)?;
+        state.serialize_field("last_name", &self.last_name)?;
+        state.serialize_field("phone_number", &self.phone_number)?;
+        state.serialize_field("email", &self.email)?;
+        state.serialize_field("city", &self.city)?;
+        state.serialize_field("photo_url", &self.photo_url)?;
+        state.serialize_field("occupation", &self.occupation)?;
+        state.serialize_field("company", &self.company)?;
+        state.serialize_field("industry", &self.industry)?;
+        state.serialize_field("linkedin_url", &self.linkedin_url)?;
+        state.serialize_field("gender", &self.gender)?;
+        state.serialize_field("skill_levels", &self.skill_levels)?;
+        state.serialize_field("preferred_side", &self.preferred_side)?;
+        state.serialize_field("play_frequency", &self.play_frequency)?;
+        state.serialize_field("looking_for", &self.looking_for)?;
+        state.serialize_field("status", &self.status)?;
+        state.serialize_field("is_approved", &self.is_approved())?;
+        state.serialize_field("created_at", &self.created_at)?;
+        state.end()
+    }
+}
+
 impl User {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         first_name: String,
         last_name: String,
@@ -143,12 +359,79 @@ impl User {
             preferred_side,
             play_frequency,
             looking_for,
-            is_approved: false,
+            status: UserStatus::Pending,
             created_at: Utc::now(),
         }
     }
 
+    pub fn is_approved(&self) -> bool {
+        self.status == UserStatus::Approved
+    }
+
     pub fn full_name(&self) -> String {
         format!("{} {}", self.first_name, self.last_name)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_transitions() {
+        use UserStatus::*;
+        assert!(Pending.can_become(Approved));
+        assert!(Pending.can_become(Rejected));
+        assert!(Rejected.can_become(Approved));
+        assert!(Approved.can_become(Suspended));
+        assert!(Suspended.can_become(Approved));
+
+        assert!(!Pending.can_become(Suspended));
+        assert!(!Approved.can_become(Approved));
+        assert!(!Approved.can_become(Rejected));
+        assert!(!Suspended.can_become(Rejected));
+    }
+
+    #[test]
+    fn skill_level_display_uses_names() {
+        assert_eq!(SkillLevel::UpperIntermediate.to_string(), "Upper-Intermediate");
+        assert_eq!(SkillLevel::LowIntermediate.to_string(), "Low Intermediate");
+    }
+
+    #[test]
+    fn skill_level_parses_letters_names_and_ratings() {
+        for level in SkillLevel::ALL {
+            assert_eq!(level.letter().to_string().parse::<SkillLevel>(), Ok(level));
+            assert_eq!(level.name().parse::<SkillLevel>(), Ok(level));
+            if let Some(rating) = level.rating() {
+                assert_eq!(rating.parse::<SkillLevel>(), Ok(level));
+            }
+        }
+
+        assert_eq!("d".parse(), Ok(SkillLevel::UpperIntermediate));
+        assert_eq!("upper intermediate".parse(), Ok(SkillLevel::UpperIntermediate));
+        assert_eq!("high-advanced".parse(), Ok(SkillLevel::HighAdvanced));
+        assert_eq!("M4".parse(), Ok(SkillLevel::UpperIntermediate));
+        assert_eq!("f4+".parse(), Ok(SkillLevel::Advanced));
+        assert_eq!("M6/F6".parse(), Ok(SkillLevel::LowIntermediate));
+    }
+
+    #[test]
+    fn skill_level_rejects_unknown_input() {
+        assert!("Z".parse::<SkillLevel>().is_err());
+        assert!("M7".parse::<SkillLevel>().is_err());
+        assert!("".parse::<SkillLevel>().is_err());
+    }
+
+    #[test]
+    fn skill_level_ordering() {
+        assert!(SkillLevel::Beginner < SkillLevel::Elite);
+        assert!(SkillLevel::UpperIntermediate > SkillLevel::Intermediate);
+        assert_eq!(SkillLevel::ALL.iter().max(), Some(&SkillLevel::Elite));
+        assert_eq!(SkillLevel::Intermediate.distance(SkillLevel::Advanced), 2);
+        assert_eq!(SkillLevel::Advanced.distance(SkillLevel::Intermediate), 2);
+        assert_eq!(SkillLevel::Intermediate.offset(1), SkillLevel::UpperIntermediate);
+        assert_eq!(SkillLevel::Beginner.offset(-1), SkillLevel::Beginner);
+        assert_eq!(SkillLevel::Elite.offset(3), SkillLevel::Elite);
+    }
+}
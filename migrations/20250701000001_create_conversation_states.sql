@@ -0,0 +1,15 @@
+-- Create conversation menu enum
+CREATE TYPE conversation_menu AS ENUM ('MainMenu', 'SessionList', 'MySessions');
+
+-- Create conversation_states table (one row per phone number)
+CREATE TABLE conversation_states (
+    phone_number VARCHAR(20) PRIMARY KEY,
+    menu conversation_menu NOT NULL,
+    session_type session_type,
+    session_ids UUID[] NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+-- Create index for purging expired conversations
+CREATE INDEX idx_conversation_states_expires_at ON conversation_states(expires_at);
@@ -0,0 +1,8 @@
+-- Add configurable capacity to sessions; existing sessions keep one court of four
+ALTER TABLE sessions ADD COLUMN max_players INTEGER NOT NULL DEFAULT 4;
+ALTER TABLE sessions ADD COLUMN max_substitutes INTEGER;
+
+ALTER TABLE sessions ADD CONSTRAINT check_max_players
+    CHECK (max_players >= 2 AND max_players <= 16);
+ALTER TABLE sessions ADD CONSTRAINT check_max_substitutes
+    CHECK (max_substitutes IS NULL OR max_substitutes >= 0);
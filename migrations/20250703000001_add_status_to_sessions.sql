@@ -0,0 +1,10 @@
+-- Session lifecycle; existing sessions are scheduled
+CREATE TYPE session_status AS ENUM ('Scheduled', 'Cancelled', 'Completed');
+
+ALTER TABLE sessions ADD COLUMN status session_status NOT NULL DEFAULT 'Scheduled';
+ALTER TABLE sessions ADD COLUMN cancellation_reason TEXT;
+
+ALTER TABLE sessions ADD CONSTRAINT check_cancellation_reason
+    CHECK (status = 'Cancelled' OR cancellation_reason IS NULL);
+
+CREATE INDEX idx_sessions_status ON sessions(status);
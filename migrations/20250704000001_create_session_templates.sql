@@ -0,0 +1,25 @@
+-- Weekly session templates; sessions generated from one point back to it
+CREATE TABLE session_templates (
+    id UUID PRIMARY KEY,
+    session_type session_type NOT NULL,
+    -- 0 = Monday ... 6 = Sunday
+    weekday SMALLINT NOT NULL CHECK (weekday >= 0 AND weekday <= 6),
+    start_time TIME NOT NULL,
+    duration_minutes INTEGER NOT NULL CHECK (duration_minutes >= 60 AND duration_minutes <= 120),
+    venue_id UUID NOT NULL REFERENCES venues(id),
+    skill_level skill_level,
+    max_players INTEGER NOT NULL CHECK (max_players >= 2 AND max_players <= 16),
+    max_substitutes INTEGER CHECK (max_substitutes IS NULL OR max_substitutes >= 0),
+    exception_dates DATE[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ DEFAULT NOW() NOT NULL,
+    CONSTRAINT template_duration_30_min_increment CHECK (duration_minutes % 30 = 0),
+    CONSTRAINT check_template_skill_level CHECK (
+        (session_type = 'X' AND skill_level IS NULL) OR
+        (session_type != 'X' AND skill_level IS NOT NULL)
+    )
+);
+
+ALTER TABLE sessions ADD COLUMN template_id UUID REFERENCES session_templates(id) ON DELETE SET NULL;
+
+-- Generating twice for the same week must not create a second session
+ALTER TABLE sessions ADD CONSTRAINT sessions_template_id_datetime_key UNIQUE (template_id, datetime);
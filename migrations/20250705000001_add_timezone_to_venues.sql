@@ -0,0 +1,2 @@
+-- IANA zone the venue's sessions are shown and grouped into weeks in
+ALTER TABLE venues ADD COLUMN timezone TEXT NOT NULL DEFAULT 'Europe/Lisbon';
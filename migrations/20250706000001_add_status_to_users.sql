@@ -0,0 +1,22 @@
+-- Where a member is in the approval process; approved users keep their access
+CREATE TYPE user_status AS ENUM ('Pending', 'Approved', 'Rejected', 'Suspended');
+
+ALTER TABLE users ADD COLUMN status user_status NOT NULL DEFAULT 'Pending';
+UPDATE users SET status = 'Approved' WHERE is_approved;
+
+DROP INDEX idx_users_is_approved;
+ALTER TABLE users DROP COLUMN is_approved;
+CREATE INDEX idx_users_status ON users(status);
+
+-- Who moved a user between statuses, when and why
+CREATE TABLE user_status_changes (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    from_status user_status NOT NULL,
+    to_status user_status NOT NULL,
+    changed_by TEXT NOT NULL,
+    reason TEXT,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_user_status_changes_user_id ON user_status_changes(user_id, changed_at);
@@ -0,0 +1,40 @@
+-- Membership applications; approving one creates the user
+CREATE TYPE application_status AS ENUM ('Pending', 'Approved', 'Rejected');
+
+CREATE TABLE applications (
+    id UUID PRIMARY KEY,
+    first_name VARCHAR(100) NOT NULL,
+    last_name VARCHAR(100) NOT NULL,
+    phone_number VARCHAR(20) UNIQUE NOT NULL,
+    email VARCHAR(255) UNIQUE NOT NULL,
+    city VARCHAR(100) NOT NULL,
+    photo_url VARCHAR(255),
+    occupation VARCHAR(100) NOT NULL,
+    company VARCHAR(100) NOT NULL,
+    industry VARCHAR(100) NOT NULL,
+    linkedin_url VARCHAR(255) NOT NULL,
+    gender gender NOT NULL,
+    skill_levels skill_level[] NOT NULL,
+    preferred_side preferred_side NOT NULL,
+    play_frequency play_frequency NOT NULL,
+    looking_for looking_for[] NOT NULL,
+    status application_status NOT NULL DEFAULT 'Pending',
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ DEFAULT NOW() NOT NULL
+);
+
+CREATE INDEX idx_applications_status ON applications(status, created_at);
+
+-- Who decided on an application, when and why
+CREATE TABLE application_status_changes (
+    id UUID PRIMARY KEY,
+    application_id UUID NOT NULL REFERENCES applications(id) ON DELETE CASCADE,
+    from_status application_status NOT NULL,
+    to_status application_status NOT NULL,
+    changed_by TEXT NOT NULL,
+    reason TEXT,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_application_status_changes_application_id
+    ON application_status_changes(application_id, changed_at);
@@ -0,0 +1,4 @@
+-- A promoted substitute keeps the spot only if they confirm by this time
+ALTER TABLE registrations ADD COLUMN confirm_by TIMESTAMPTZ;
+
+CREATE INDEX idx_registrations_confirm_by ON registrations(confirm_by) WHERE confirm_by IS NOT NULL;
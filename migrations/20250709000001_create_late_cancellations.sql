@@ -0,0 +1,10 @@
+-- Confirmed players who dropped out after their session's cancellation cutoff
+CREATE TABLE late_cancellations (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    session_id UUID NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+    session_starts_at TIMESTAMPTZ NOT NULL,
+    cancelled_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_late_cancellations_user_id ON late_cancellations(user_id, cancelled_at);
@@ -0,0 +1,14 @@
+-- Whether confirmed players turned up, one row per registration
+CREATE TYPE attendance_status AS ENUM ('Attended', 'NoShow', 'Excused');
+
+CREATE TABLE attendance (
+    registration_id UUID PRIMARY KEY REFERENCES registrations(id) ON DELETE CASCADE,
+    session_id UUID NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    status attendance_status NOT NULL,
+    recorded_by TEXT NOT NULL,
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_attendance_session_id ON attendance(session_id);
+CREATE INDEX idx_attendance_user_id ON attendance(user_id);
@@ -0,0 +1,24 @@
+-- Append-only log of everything that happened to a session's registrations.
+-- Rows outlive the registration they describe, so there is no foreign key to it.
+CREATE TYPE registration_event_kind AS ENUM ('Registered', 'Promoted', 'Demoted', 'Cancelled', 'LateCancelled');
+CREATE TYPE registration_actor AS ENUM ('Player', 'Organiser', 'System');
+
+CREATE TABLE registration_events (
+    seq BIGSERIAL PRIMARY KEY,
+    id UUID NOT NULL UNIQUE,
+    registration_id UUID NOT NULL,
+    session_id UUID NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind registration_event_kind NOT NULL,
+    status registration_status,
+    actor registration_actor NOT NULL,
+    occurred_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_registration_events_session_id ON registration_events(session_id, seq);
+
+-- Start the log with the sign-ups that already exist
+INSERT INTO registration_events (id, registration_id, session_id, user_id, kind, status, actor, occurred_at)
+SELECT gen_random_uuid(), id, session_id, user_id, 'Registered', status, 'Player', created_at
+FROM registrations
+ORDER BY created_at;
@@ -0,0 +1,12 @@
+-- The template week a generated session belongs to, as a date on the venue's
+-- calendar. It stays put when an organiser moves the session, so generating
+-- again doesn't bring the original slot back.
+ALTER TABLE sessions ADD COLUMN occurrence_date DATE;
+
+UPDATE sessions s
+SET occurrence_date = (s.datetime AT TIME ZONE v.timezone)::date
+FROM venues v
+WHERE v.id = s.venue_id AND s.template_id IS NOT NULL;
+
+ALTER TABLE sessions DROP CONSTRAINT sessions_template_id_datetime_key;
+ALTER TABLE sessions ADD CONSTRAINT sessions_template_id_occurrence_date_key UNIQUE (template_id, occurrence_date);
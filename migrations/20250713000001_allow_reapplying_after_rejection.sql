@@ -0,0 +1,9 @@
+-- A phone number or email is only unique among pending applications, so
+-- someone turned down can apply again later
+ALTER TABLE applications DROP CONSTRAINT applications_phone_number_key;
+ALTER TABLE applications DROP CONSTRAINT applications_email_key;
+
+CREATE UNIQUE INDEX applications_pending_phone_number_key
+    ON applications(phone_number) WHERE status = 'Pending';
+CREATE UNIQUE INDEX applications_pending_email_key
+    ON applications(email) WHERE status = 'Pending';
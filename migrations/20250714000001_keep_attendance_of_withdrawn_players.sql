@@ -0,0 +1,5 @@
+-- A recorded attendance keeps its registration: withdrawing a player who
+-- checked in or was marked would otherwise erase their no-show
+ALTER TABLE attendance DROP CONSTRAINT attendance_registration_id_fkey;
+ALTER TABLE attendance ADD CONSTRAINT attendance_registration_id_fkey
+    FOREIGN KEY (registration_id) REFERENCES registrations(id) ON DELETE RESTRICT;